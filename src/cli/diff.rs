@@ -0,0 +1,161 @@
+use clap::ValueEnum;
+
+use arc_isle::diff::{Change, SchemaDiff};
+use arc_isle::parser;
+use arc_isle::schema::Schema;
+
+use super::output::json_string;
+
+#[derive(Clone, Default, ValueEnum)]
+pub enum DiffFormat {
+    #[default]
+    Human,
+    Json,
+    Markdown,
+}
+
+pub fn run_diff(
+    parsed_schema: &Schema,
+    new_path: &str,
+    format: DiffFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let new_schema = parser::parse(new_path)?;
+    let diff = SchemaDiff {
+        old: parsed_schema,
+        new: &new_schema,
+    };
+    let changes = diff.changes();
+    let output = match format {
+        DiffFormat::Human => human(&changes),
+        DiffFormat::Json => json(&changes),
+        DiffFormat::Markdown => markdown(&changes),
+    };
+    print!("{}", output);
+    let breaking = changes.iter().filter(|change| change.is_breaking).count();
+    if breaking > 0 {
+        return Err(format!("{} breaking changes", breaking).into());
+    }
+    Ok(())
+}
+
+fn human(changes: &[Change]) -> String {
+    let mut result = String::new();
+    for change in changes {
+        let severity = if change.is_breaking {
+            "breaking"
+        } else {
+            "non-breaking"
+        };
+        result.push_str(&format!("{:<12}  {}\n", severity, change));
+    }
+    let breaking = changes.iter().filter(|change| change.is_breaking).count();
+    result.push_str(&format!(
+        "{} changes, {} breaking\n",
+        changes.len(),
+        breaking
+    ));
+    result
+}
+
+fn json(changes: &[Change]) -> String {
+    let items: Vec<String> = changes
+        .iter()
+        .map(|change| {
+            format!(
+                "    {{\"breaking\": {}, \"subject\": {}, \"change\": {}}}",
+                change.is_breaking,
+                json_string(&change.subject),
+                json_string(&change.kind.to_string())
+            )
+        })
+        .collect();
+    if items.is_empty() {
+        return "{\"changes\": []}\n".to_string();
+    }
+    format!("{{\"changes\": [\n{}\n]}}\n", items.join(",\n"))
+}
+
+fn markdown(changes: &[Change]) -> String {
+    let mut result = String::new();
+    for (title, is_breaking) in [("Breaking changes", true), ("Non-breaking changes", false)] {
+        let section: Vec<&Change> = changes
+            .iter()
+            .filter(|change| change.is_breaking == is_breaking)
+            .collect();
+        if section.is_empty() {
+            continue;
+        }
+        result.push_str(&format!("## {}\n\n", title));
+        for change in section {
+            result.push_str(&format!("- `{}`: {}\n", change.subject, change.kind));
+        }
+        result.push('\n');
+    }
+    if result.is_empty() {
+        result.push_str("No changes.\n");
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use arc_isle::diff::{Change, ChangeKind};
+
+    use super::{json, markdown};
+
+    fn changes() -> Vec<Change> {
+        vec![
+            Change {
+                subject: "DELETE users/{id} response 404".to_string(),
+                kind: ChangeKind::ResponseRemoved,
+                is_breaking: true,
+            },
+            Change {
+                subject: "type types.user".to_string(),
+                kind: ChangeKind::PropertyAdded {
+                    path: "nick \"name\"".to_string(),
+                    is_required: false,
+                },
+                is_breaking: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(
+            json(&changes()),
+            "{\"changes\": [
+    {\"breaking\": true, \"subject\": \"DELETE users/{id} response 404\", \"change\": \"response removed.\"},
+    {\"breaking\": false, \"subject\": \"type types.user\", \"change\": \"optional property `nick \\\"name\\\"` added.\"}
+]}
+"
+        );
+        assert_eq!(json(&[]), "{\"changes\": []}\n");
+    }
+
+    #[test]
+    fn test_markdown() {
+        assert_eq!(
+            markdown(&changes()),
+            "## Breaking changes
+
+- `DELETE users/{id} response 404`: response removed.
+
+## Non-breaking changes
+
+- `type types.user`: optional property `nick \"name\"` added.
+
+"
+        );
+        assert_eq!(
+            markdown(&changes()[1..]),
+            "## Non-breaking changes
+
+- `type types.user`: optional property `nick \"name\"` added.
+
+"
+        );
+        assert_eq!(markdown(&[]), "No changes.\n");
+    }
+}
@@ -0,0 +1,21 @@
+use arc_isle::format::format_spec;
+use arc_isle::schema::Schema;
+
+pub fn run_fmt(
+    parsed_schema: &Schema,
+    parent_path: &str,
+    check: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let changed = format_spec(parsed_schema, parent_path, check)?;
+    for file in &changed {
+        if check {
+            println!("Would reformat {}", file);
+        } else {
+            println!("Reformatted {}", file);
+        }
+    }
+    if check && !changed.is_empty() {
+        return Err(format!("{} file(s) are not formatted", changed.len()).into());
+    }
+    Ok(())
+}
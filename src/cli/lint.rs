@@ -0,0 +1,63 @@
+use clap::ValueEnum;
+
+use arc_isle::lint::{LintConfig, LintDiagnostic, LintLevel, Linter};
+use arc_isle::schema::Schema;
+
+use super::output::json_string;
+
+#[derive(Clone, Default, ValueEnum)]
+pub enum LintFormat {
+    #[default]
+    Human,
+    Json,
+}
+
+pub fn run_lint(
+    parsed_schema: &Schema,
+    parent_path: &str,
+    format: LintFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = LintConfig::load(parent_path)?;
+    let linter = Linter {
+        schema: parsed_schema,
+        config: &config,
+    };
+    let diagnostics = linter.lint();
+    match format {
+        LintFormat::Human => {
+            for diagnostic in &diagnostics {
+                println!("{}", diagnostic);
+            }
+        }
+        LintFormat::Json => print!("{}", json(&diagnostics)),
+    }
+    let errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.level == LintLevel::Error)
+        .count();
+    if errors > 0 {
+        return Err(format!("{} lint errors", errors).into());
+    }
+    Ok(())
+}
+
+fn json(diagnostics: &[LintDiagnostic]) -> String {
+    let items: Vec<String> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            format!(
+                "    {{\"rule\": {}, \"level\": {}, \"file\": {}, \"index\": {}, \"subject\": {}, \"message\": {}}}",
+                json_string(diagnostic.rule.name()),
+                json_string(&diagnostic.level.to_string()),
+                json_string(&diagnostic.location.file),
+                diagnostic.location.index,
+                json_string(&diagnostic.subject),
+                json_string(&diagnostic.message)
+            )
+        })
+        .collect();
+    if items.is_empty() {
+        return "{\"diagnostics\": []}\n".to_string();
+    }
+    format!("{{\"diagnostics\": [\n{}\n]}}\n", items.join(",\n"))
+}
@@ -0,0 +1,16 @@
+use arc_isle::mock::{MockServer, STATUS_HEADER, STATUS_PARAMETER};
+use arc_isle::schema::Schema;
+
+pub fn run_mock(parsed_schema: &Schema, port: u16) -> Result<(), Box<dyn std::error::Error>> {
+    let server = MockServer {
+        schema: parsed_schema,
+    };
+    let count = parsed_schema.interfaces.iter().flatten().count();
+    println!("Serving {} interfaces on http://127.0.0.1:{}", count, port);
+    println!(
+        "Pick a status code with the {} header or the {} query parameter, like 404 or 4xx",
+        STATUS_HEADER, STATUS_PARAMETER
+    );
+    server.serve(port)?;
+    Ok(())
+}
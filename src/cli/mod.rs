@@ -1,11 +1,26 @@
+mod diff;
+mod format;
+mod lint;
+mod mock;
+mod output;
+mod sample;
 mod show;
 mod modify;
+mod traffic;
+mod usage;
+mod validate;
 
 use crate::cli::show::ShowCommands;
 use arc_isle::parser;
 use clap::{Parser, Subcommand};
 
-use self::{modify::{run_modify, ModifyCommands}, show::run_show};
+use self::diff::{run_diff, DiffFormat};
+use self::format::run_fmt;
+use self::lint::{run_lint, LintFormat};
+use self::mock::run_mock;
+use self::sample::{run_sample, OptionalArg};
+use self::usage::run_unused;
+use self::{modify::{run_modify, ModifyCommands}, show::run_show, traffic::run_check_traffic, validate::run_validate_instance};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -25,14 +40,90 @@ enum Commands {
         #[command(subcommand)]
         commands: ModifyCommands,
     },
+    /// Pin versions and checksums of vendored packages in the lockfile.
+    Lock,
+    /// Check a JSON document against a declared type.
+    ValidateInstance {
+        #[arg(long = "type")]
+        type_name: String,
+        file: String,
+    },
+    /// Check requests and responses recorded in a HAR file against declared interfaces.
+    CheckTraffic {
+        file: String,
+    },
+    /// Compare the schema with its new version and report breaking changes.
+    Diff {
+        new: String,
+        #[arg(long, value_enum, default_value_t)]
+        format: DiffFormat,
+    },
+    /// Check the schema against lint rules configured in `.arcisle.yml`.
+    Lint {
+        #[arg(long, value_enum, default_value_t)]
+        format: LintFormat,
+    },
+    /// Report types and files no interface depends on.
+    Unused {
+        /// Write a copy of the schema without unused types to this directory.
+        #[arg(long)]
+        prune: Option<String>,
+    },
+    /// Rewrite spec files with canonical ordering, indentation and type strings, keeping comments.
+    Fmt {
+        /// Only report files which aren't formatted, failing if there are any.
+        #[arg(long)]
+        check: bool,
+    },
+    /// Serve declared interfaces on localhost, responding with generated data.
+    Mock {
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+    },
+    /// Generate JSON instances of a declared type, the same ones for the same seed.
+    Sample {
+        type_name: String,
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Whether instances have optional properties.
+        #[arg(long, value_enum, default_value_t)]
+        optional: OptionalArg,
+    },
 }
 
 pub fn run() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
+    if let Commands::Lock = cli.commands {
+        for package in parser::lock(&cli.path)? {
+            println!("Locked {} {} ({})", package.name, package.version, package.checksum);
+        }
+        return Ok(());
+    }
     let parsed_schema = parser::parse(&cli.path)?;
+    for warning in &parsed_schema.warnings {
+        eprintln!("Warning: {}", warning);
+    }
     match cli.commands {
         Commands::Show { commands } => run_show(&parsed_schema, commands)?,
-        Commands::Modify { commands } => run_modify(&parsed_schema, commands)?,
+        Commands::Modify { commands } => run_modify(&parsed_schema, &cli.path, commands)?,
+        Commands::ValidateInstance { type_name, file } => {
+            run_validate_instance(&parsed_schema, &type_name, &file)?
+        }
+        Commands::CheckTraffic { file } => run_check_traffic(&parsed_schema, &file)?,
+        Commands::Diff { new, format } => run_diff(&parsed_schema, &new, format)?,
+        Commands::Lint { format } => run_lint(&parsed_schema, &cli.path, format)?,
+        Commands::Unused { prune } => run_unused(&parsed_schema, &cli.path, prune.as_deref())?,
+        Commands::Fmt { check } => run_fmt(&parsed_schema, &cli.path, check)?,
+        Commands::Mock { port } => run_mock(&parsed_schema, port)?,
+        Commands::Sample {
+            type_name,
+            count,
+            seed,
+            optional,
+        } => run_sample(&parsed_schema, &type_name, count, seed, optional)?,
+        Commands::Lock => {}
     }
     Ok(())
 }
@@ -1,16 +1,187 @@
-use clap::Subcommand;
+use std::fs;
+use std::path::Path;
 
+use arc_isle::modify::{NewInterface, SpecEditor};
+use arc_isle::schema::Schema;
+use clap::Subcommand;
 
 #[derive(Subcommand)]
 pub enum ModifyCommands {
-    Add,
-    Remove,
-    Update,
+    /// Declare a new type, property or interface.
+    Add {
+        #[command(subcommand)]
+        target: AddTarget,
+    },
+    /// Remove a declared type, property or interface.
+    Remove {
+        #[command(subcommand)]
+        target: RemoveTarget,
+    },
+    /// Change a response code of an interface.
+    Update {
+        #[command(subcommand)]
+        target: UpdateTarget,
+    },
+    /// Rename a type together with every reference to it across the spec.
+    RenameType {
+        name: String,
+        new_name: String,
+        /// Print changed lines without writing them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AddTarget {
+    /// Declare a type with properties written as `name:type`, like `id:str`.
+    Type {
+        name: String,
+        #[arg(required = true)]
+        properties: Vec<String>,
+        /// File to declare the type in, by default the one declaring the last type.
+        #[arg(long)]
+        file: Option<String>,
+    },
+    Property {
+        type_name: String,
+        name: String,
+        data_type: String,
+    },
+    Interface {
+        method: String,
+        path: String,
+        /// Query parameter written as `name:type`, may be repeated.
+        #[arg(long)]
+        query: Vec<String>,
+        /// Body property written as `name:type`, may be repeated.
+        #[arg(long)]
+        body: Vec<String>,
+        #[arg(long)]
+        response: Option<String>,
+        /// File to declare the interface in, by default the one declaring the last interface.
+        #[arg(long)]
+        file: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RemoveTarget {
+    Type { name: String },
+    Property { type_name: String, name: String },
+    Interface { method: String, path: String },
+}
+
+#[derive(Subcommand)]
+pub enum UpdateTarget {
+    /// Change a status code, making an inferred one explicit.
+    ResponseCode {
+        method: String,
+        path: String,
+        from: String,
+        to: String,
+    },
 }
 
 pub fn run_modify(
-    _parsed_schema: &arc_isle::schema::Schema,
-    _command: ModifyCommands,
+    parsed_schema: &Schema,
+    parent_path: &str,
+    command: ModifyCommands,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let editor = SpecEditor {
+        schema: parsed_schema,
+        root_path: parent_path,
+    };
+    let edit = match command {
+        ModifyCommands::Add { target } => match target {
+            AddTarget::Type {
+                name,
+                properties,
+                file,
+            } => editor.add_type(&name, &properties_from(&properties)?, file.as_deref())?,
+            AddTarget::Property {
+                type_name,
+                name,
+                data_type,
+            } => editor.add_property(&type_name, &name, &data_type)?,
+            AddTarget::Interface {
+                method,
+                path,
+                query,
+                body,
+                response,
+                file,
+            } => {
+                let interface = NewInterface {
+                    method,
+                    path,
+                    query: properties_from(&query)?,
+                    body: properties_from(&body)?,
+                    response,
+                };
+                editor.add_interface(&interface, file.as_deref())?
+            }
+        },
+        ModifyCommands::Remove { target } => match target {
+            RemoveTarget::Type { name } => editor.remove_type(&name)?,
+            RemoveTarget::Property { type_name, name } => {
+                editor.remove_property(&type_name, &name)?
+            }
+            RemoveTarget::Interface { method, path } => editor.remove_interface(&method, &path)?,
+        },
+        ModifyCommands::Update { target } => match target {
+            UpdateTarget::ResponseCode {
+                method,
+                path,
+                from,
+                to,
+            } => editor.change_response_code(&method, &path, &from, &to)?,
+        },
+        ModifyCommands::RenameType {
+            name,
+            new_name,
+            dry_run,
+        } => {
+            let edits = editor.rename_type(&name, &new_name)?;
+            if dry_run {
+                for edit in &edits {
+                    let original = fs::read_to_string(Path::new(parent_path).join(&edit.file))?;
+                    print_diff(&edit.file, &original, &edit.contents);
+                }
+                return Ok(());
+            }
+            editor.apply(&edits)?;
+            for edit in &edits {
+                println!("Updated {}", edit.file);
+            }
+            return Ok(());
+        }
+    };
+    editor.apply(std::slice::from_ref(&edit))?;
+    println!("Updated {}", edit.file);
     Ok(())
 }
+
+/// Prints changed lines of a file, edits which replace lines one for one.
+fn print_diff(file: &str, original: &str, edited: &str) {
+    println!("--- a/{}", file);
+    println!("+++ b/{}", file);
+    for (index, (old, new)) in original.lines().zip(edited.lines()).enumerate() {
+        if old != new {
+            println!("@@ -{} +{} @@", index + 1, index + 1);
+            println!("-{}", old);
+            println!("+{}", new);
+        }
+    }
+}
+
+/// Properties written as `name:type`, like `id:str`.
+fn properties_from(values: &[String]) -> Result<Vec<(String, String)>, String> {
+    values
+        .iter()
+        .map(|val| match val.split_once(':') {
+            Some((name, data_type)) => Ok((name.trim().to_string(), data_type.trim().to_string())),
+            None => Err(format!("Property {} must be written as name:type", val)),
+        })
+        .collect()
+}
@@ -0,0 +1,7 @@
+use arc_isle::sample::to_json;
+use yaml_rust::Yaml;
+
+/// Quotes and escapes a value as a JSON string.
+pub fn json_string(value: &str) -> String {
+    to_json(&Yaml::String(value.to_string()))
+}
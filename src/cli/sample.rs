@@ -0,0 +1,35 @@
+use clap::ValueEnum;
+
+use arc_isle::sample::{to_json, OptionalFields, SampleGenerator};
+use arc_isle::schema::Schema;
+
+#[derive(Clone, Default, ValueEnum)]
+pub enum OptionalArg {
+    #[default]
+    Include,
+    Omit,
+    Random,
+}
+
+/// Prints generated instances of the type as JSON, one per line.
+pub fn run_sample(
+    parsed_schema: &Schema,
+    type_name: &str,
+    count: usize,
+    seed: u64,
+    optional: OptionalArg,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut generator = SampleGenerator::new(parsed_schema, seed);
+    generator.optional_fields = match optional {
+        OptionalArg::Include => OptionalFields::Include,
+        OptionalArg::Omit => OptionalFields::Omit,
+        OptionalArg::Random => OptionalFields::Random,
+    };
+    let samples = generator
+        .samples(type_name, count)
+        .ok_or_else(|| format!("Type {} is not declared", type_name))?;
+    for sample in &samples {
+        println!("{}", to_json(sample));
+    }
+    Ok(())
+}
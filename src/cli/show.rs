@@ -3,39 +3,63 @@ use std::{
     io::{stdout, Stdout},
 };
 
-use clap::Subcommand;
+use clap::{Subcommand, ValueEnum};
 use crossterm::{
     style::{Print, ResetColor, SetAttribute},
     ExecutableCommand,
 };
 
-use arc_isle::schema::{self, ApiSpec, HttpPayload, InterfaceSpec, Schema, StatusCode, TypeDecl};
+use arc_isle::schema::{
+    self, ApiSpec, DataType, DataTypeDecl, HttpPayload, ImportGraph, InterfaceDecl,
+    InterfaceDeclError, InterfaceSpec, InterfaceVersions, Schema, StatusCode, VersioningFormat,
+};
 
 #[derive(Subcommand)]
 pub enum ShowCommands {
     Hosts,
+    Imports,
     Versioning,
     Types,
-    Interfaces,
+    Responses,
+    Interfaces {
+        /// Show only interfaces existing in this API version, as they are in it.
+        #[arg(long)]
+        version: Option<u32>,
+        /// Split interfaces into sections by their group or tags.
+        #[arg(long)]
+        group_by: Option<Grouping>,
+    },
     All,
 }
 
+#[derive(Clone, ValueEnum)]
+pub enum Grouping {
+    Group,
+    Tag,
+}
+
 pub fn run_show(
     parsed_schema: &Schema,
     command: ShowCommands,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    Ok(match command {
-        ShowCommands::Hosts => print_hosts(&parsed_schema)?,
-        ShowCommands::Versioning => print_versioning(&parsed_schema)?,
-        ShowCommands::Types => print_types(&parsed_schema)?,
-        ShowCommands::Interfaces => print_interfaces(&parsed_schema)?,
+    match command {
+        ShowCommands::Hosts => print_hosts(parsed_schema),
+        ShowCommands::Imports => print_imports(parsed_schema),
+        ShowCommands::Versioning => print_versioning(parsed_schema),
+        ShowCommands::Types => print_types(parsed_schema),
+        ShowCommands::Responses => print_shared_responses(parsed_schema),
+        ShowCommands::Interfaces { version, group_by } => match group_by {
+            Some(grouping) => print_grouped_interfaces(parsed_schema, version, grouping),
+            None => print_interfaces(parsed_schema, version),
+        },
         ShowCommands::All => {
-            print_hosts(&parsed_schema)?;
-            print_versioning(&parsed_schema)?;
-            print_types(&parsed_schema)?;
-            print_interfaces(&parsed_schema)?
+            print_hosts(parsed_schema)?;
+            print_versioning(parsed_schema)?;
+            print_types(parsed_schema)?;
+            print_shared_responses(parsed_schema)?;
+            print_interfaces(parsed_schema, None)
         }
-    })
+    }
 }
 
 fn print_hosts(parsed_schema: &Schema) -> Result<(), Box<dyn std::error::Error>> {
@@ -46,6 +70,36 @@ fn print_hosts(parsed_schema: &Schema) -> Result<(), Box<dyn std::error::Error>>
             "{}- {}: {}\n",
             &indent, host.env, host.address
         )))?;
+        if let Some(description) = &host.description {
+            builder.execute(Print(format!("{}{}{}\n", indent, indent, description)))?;
+        }
+        for variable in &host.variables {
+            let mut output = format!("{}{}|- {{{}}}", indent, indent, variable.name);
+            if let Some(values) = &variable.values {
+                output.push_str(&format!(" one of [{}]", values.join(", ")));
+            }
+            if let Some(default) = &variable.default {
+                output.push_str(&format!(", default: {}", default));
+            }
+            builder.execute(Print(format!("{}\n", output)))?;
+        }
+        if let Some(interfaces) = &host.interfaces {
+            builder.execute(Print(format!(
+                "{}{}|- Interfaces: {}\n",
+                indent,
+                indent,
+                interfaces.join(", ")
+            )))?;
+        }
+        if let Some(versions) = &host.versions {
+            let versions: Vec<_> = versions.iter().map(|val| val.to_string()).collect();
+            builder.execute(Print(format!(
+                "{}{}|- Versions: {}\n",
+                indent,
+                indent,
+                versions.join(", ")
+            )))?;
+        }
     }
     builder
         .execute(Print(separator))?
@@ -53,17 +107,63 @@ fn print_hosts(parsed_schema: &Schema) -> Result<(), Box<dyn std::error::Error>>
         .map(|_| Ok(()))?
 }
 
+fn print_imports(parsed_schema: &Schema) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut out, indent, separator) = prepare();
+    let builder = section_decorator(&mut out, "Imports", &indent, &separator)?;
+    let mut output = String::new();
+    let mut chain = vec!["main.yaml"];
+    displayable_imports(&parsed_schema.imports, &mut chain, &indent, &mut output);
+    builder
+        .execute(Print(format!("{}main.yaml\n{}", indent, output)))?
+        .execute(Print(separator))?
+        .execute(Print("\r\n"))
+        .map(|_| Ok(()))?
+}
+
+fn displayable_imports<'a>(
+    imports: &'a ImportGraph,
+    chain: &mut Vec<&'a str>,
+    indent: &str,
+    output: &mut String,
+) {
+    let Some(imported) = chain.last().and_then(|file| imports.edges.get(*file)) else {
+        return;
+    };
+    for file in imported {
+        let level_indent = indent.repeat(chain.len() + 1);
+        if chain.contains(&file.as_str()) {
+            output.push_str(&format!("{}|- {} (cycle)\n", level_indent, file));
+            continue;
+        }
+        output.push_str(&format!("{}|- {}\n", level_indent, file));
+        chain.push(file);
+        displayable_imports(imports, chain, indent, output);
+        chain.pop();
+    }
+}
+
 fn print_versioning(parsed_schema: &Schema) -> Result<(), Box<dyn std::error::Error>> {
     let (mut out, indent, separator) = prepare();
-    section_decorator(&mut out, "Versioning", &indent, &separator)?
-        .execute(Print(format!(
-            "{}Format: {:?}\n",
-            indent, parsed_schema.versioning.format
-        )))?
-        .execute(Print(format!(
-            "{}Header: {:?}\n",
-            indent, parsed_schema.versioning.header
-        )))?
+    let builder = section_decorator(&mut out, "Versioning", &indent, &separator)?;
+    match &parsed_schema.versioning {
+        Some(versioning) => {
+            let format = match &versioning.format {
+                VersioningFormat::Headers { .. } => "headers",
+                VersioningFormat::Path { .. } => "path",
+                VersioningFormat::Query { .. } => "query",
+                VersioningFormat::MediaType { .. } => "media type",
+            };
+            builder
+                .execute(Print(format!("{}Format: {}\n", indent, format)))?
+                .execute(Print(format!(
+                    "{}Passed as: {}\n",
+                    indent,
+                    versioning.describe(None)
+                )))?
+        }
+        None => builder.execute(Print(format!("{}Not versioned\n", indent)))?,
+    };
+    builder
         .execute(Print(separator))?
         .execute(Print("\r\n"))
         .map(|_| Ok(()))?
@@ -76,7 +176,14 @@ fn print_types(parsed_schema: &Schema) -> Result<(), Box<dyn std::error::Error>>
         match type_ {
             Ok(val) => builder
                 .execute(Print(&indent))?
-                .execute(Print(displayable_type(val, &indent, 1)))?
+                .execute(Print(displayable_type(
+                    &schema::TypeDecl {
+                        name: val.qualified_name(),
+                        ..val.clone()
+                    },
+                    &indent,
+                    1,
+                )))?
                 .execute(Print("\n\n"))?,
             Err(err) => builder.execute(Print(format!("{}- {:?}\n", &indent, err)))?,
         };
@@ -110,7 +217,7 @@ fn displayable_propreties(
                     level_indent,
                     &indent,
                     prop_decl.name,
-                    displayable_type(&obj_decl, &indent, level + 1)
+                    displayable_type(obj_decl, indent, level + 1)
                 )),
                 _ => output.push_str(&format!(
                     "{}{}{}: {}\n",
@@ -125,15 +232,122 @@ fn displayable_propreties(
     }
 }
 
-fn print_interfaces(parsed_schema: &Schema) -> Result<(), Box<dyn std::error::Error>> {
+fn print_shared_responses(parsed_schema: &Schema) -> Result<(), Box<dyn std::error::Error>> {
     let (mut out, indent, separator) = prepare();
-    let builder = section_decorator(&mut out, "Interfaces", &indent, &separator)?;
+    let builder = section_decorator(&mut out, "Responses", &indent, &separator)?;
+    let shared_responses = &parsed_schema.shared_responses;
+    let mut named: Vec<_> = shared_responses.named.iter().collect();
+    named.sort_by(|lhs, rhs| lhs.0.cmp(rhs.0));
+    for (name, response) in named {
+        builder.execute(Print(format!(
+            "{}${}: {}\n",
+            &indent,
+            name,
+            displayable_response(response, &indent, 1)
+        )))?;
+    }
+    if !shared_responses.defaults.is_empty() {
+        builder.execute(Print(format!(
+            "{}|- Defaults:\n{}",
+            indent,
+            displayable_responses(&shared_responses.defaults, None, &indent)
+        )))?;
+    }
+    builder
+        .execute(Print(separator))?
+        .execute(Print("\r\n"))
+        .map(|_| Ok(()))?
+}
+
+fn print_interfaces(
+    parsed_schema: &Schema,
+    version: Option<u32>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let title = match version {
+        Some(version) => format!("Interfaces (version {})", version),
+        None => "Interfaces".to_string(),
+    };
+    let interfaces: Vec<_> = parsed_schema.interfaces.iter().collect();
+    print_interfaces_section(parsed_schema, &title, &interfaces, version)
+}
+
+fn print_grouped_interfaces(
+    parsed_schema: &Schema,
+    version: Option<u32>,
+    grouping: Grouping,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut groups: Vec<(String, Vec<&Result<InterfaceDecl, InterfaceDeclError>>)> = Vec::new();
     for interface in &parsed_schema.interfaces {
+        let names = match (interface, &grouping) {
+            (Ok(val), Grouping::Group) => val.group.iter().cloned().collect(),
+            (Ok(val), Grouping::Tag) => val.tags.clone(),
+            (Err(_), _) => vec!["Invalid".to_string()],
+        };
+        let names = if names.is_empty() {
+            vec!["Other".to_string()]
+        } else {
+            names
+        };
+        for name in names {
+            match groups.iter_mut().find(|(group, _)| *group == name) {
+                Some((_, members)) => members.push(interface),
+                None => groups.push((name, vec![interface])),
+            }
+        }
+    }
+    for (name, interfaces) in groups {
+        let title = match version {
+            Some(version) => format!("Interfaces: {} (version {})", name, version),
+            None => format!("Interfaces: {}", name),
+        };
+        print_interfaces_section(parsed_schema, &title, &interfaces, version)?;
+    }
+    Ok(())
+}
+
+fn print_interfaces_section(
+    parsed_schema: &Schema,
+    title: &str,
+    interfaces: &[&Result<InterfaceDecl, InterfaceDeclError>],
+    version: Option<u32>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut out, indent, separator) = prepare();
+    let builder = section_decorator(&mut out, title, &indent, &separator)?;
+    for interface in interfaces {
         match interface {
-            Ok(val) => match &val.spec {
-                InterfaceSpec::Api(api) => print_api_spec(&val.ident, &api, builder, &indent)?,
-            },
-            Err(err) => builder.execute(Print(format!("{}- {:?}\n", &indent, err)))?,
+            Ok(val) => {
+                let val = match version {
+                    Some(version) => match val.for_version(version) {
+                        Some(val) => val,
+                        None => continue,
+                    },
+                    None => val.clone(),
+                };
+                let ident = match &parsed_schema.versioning {
+                    Some(versioning) => versioning.versioned_ident(&val.ident, version),
+                    None => val.ident.clone(),
+                };
+                match &val.spec {
+                    InterfaceSpec::Api(api) => print_api_spec(&ident, api, builder, &indent)?,
+                };
+                builder.execute(Print(format!(
+                    "{}|- Operation: {}\n",
+                    indent, val.operation_id
+                )))?;
+                if !val.tags.is_empty() {
+                    builder.execute(Print(format!(
+                        "{}|- Tags: {}\n",
+                        indent,
+                        val.tags.join(", ")
+                    )))?;
+                }
+                if version.is_none() {
+                    print_versions(&val.versions, builder, &indent)?;
+                }
+            }
+            Err(err) => {
+                builder.execute(Print(format!("{}- {:?}\n", &indent, err)))?;
+            }
         };
         builder.execute(Print(&separator))?.execute(Print("\n"))?;
     }
@@ -143,6 +357,33 @@ fn print_interfaces(parsed_schema: &Schema) -> Result<(), Box<dyn std::error::Er
         .map(|_| Ok(()))?
 }
 
+fn print_versions<'a>(
+    versions: &InterfaceVersions,
+    builder: &'a mut Stdout,
+    indent: &str,
+) -> Result<&'a mut Stdout, Box<dyn std::error::Error>> {
+    if versions.is_unrestricted() && versions.overrides.is_empty() {
+        return Ok(builder);
+    }
+    builder.execute(Print(format!("{}|- Versions: {}\n", indent, versions)))?;
+    let mut overrides: Vec<_> = versions.overrides.iter().collect();
+    overrides.sort_by_key(|(version, _)| **version);
+    for (version, api) in overrides {
+        builder.execute(Print(format!("{}{}Since {}:\n", indent, indent, version)))?;
+        if let Some(payload) = &api.payload {
+            print_payload(payload, builder, indent)?;
+        }
+        if let Some(responses) = &api.responses {
+            builder.execute(Print(format!(
+                "{}|- Responses:\n{}",
+                indent,
+                displayable_responses(responses, api.inferred_status_code.as_ref(), indent)
+            )))?;
+        }
+    }
+    Ok(builder)
+}
+
 fn print_api_spec<'a>(
     ident: &str,
     api: &ApiSpec,
@@ -151,13 +392,13 @@ fn print_api_spec<'a>(
 ) -> Result<&'a mut Stdout, Box<dyn std::error::Error>> {
     builder.execute(Print(format!("{}{} {}\n", &indent, api.method, ident)))?;
     if let Some(payload) = &api.payload {
-        print_payload(&payload, builder, &indent)?;
+        print_payload(payload, builder, indent)?;
     }
     if let Some(responses) = &api.responses {
         builder.execute(Print(format!(
             "{}|- Responses:\n{}",
             indent,
-            displayable_responses(&responses, &indent)
+            displayable_responses(responses, api.inferred_status_code.as_ref(), indent)
         )))?;
     }
     Ok(builder)
@@ -171,31 +412,51 @@ fn print_payload<'a>(
     match payload {
         HttpPayload::Query(query) => {
             let mut output = String::new();
-            displayable_propreties(query, &mut output, &indent, 1);
+            displayable_propreties(query, &mut output, indent, 1);
             Ok(builder.execute(Print(format!("{}|- Query:\n{}", indent, output)))?)
         }
         HttpPayload::Body(body) => {
             let mut output = String::new();
-            displayable_propreties(body, &mut output, &indent, 1);
+            displayable_propreties(body, &mut output, indent, 1);
             Ok(builder.execute(Print(format!("{}|- Body:\n{}", indent, output)))?)
         }
     }
 }
 
-fn displayable_responses(decl: &HashMap<StatusCode, TypeDecl>, indent: &str) -> String {
+fn displayable_responses(
+    decl: &HashMap<StatusCode, Option<DataTypeDecl>>,
+    inferred_status_code: Option<&StatusCode>,
+    indent: &str,
+) -> String {
     let mut output = String::new();
+    let mut decl: Vec<_> = decl.iter().collect();
+    decl.sort_by_key(|(status, _)| status.as_key());
     for (status, response) in decl {
+        let displayable_response = displayable_response(response, indent, 2);
+        let origin = if inferred_status_code == Some(status) {
+            " (inferred)"
+        } else {
+            ""
+        };
         output.push_str(&format!(
-            "{}{}{}: {}\n",
-            indent,
-            indent,
-            status,
-            displayable_type(response, indent, 2)
+            "{}{}{}{}: {}\n",
+            indent, indent, status, origin, displayable_response
         ));
     }
     output
 }
 
+fn displayable_response(response: &Option<DataTypeDecl>, indent: &str, level: usize) -> String {
+    match response {
+        Some(DataTypeDecl {
+            data_type: DataType::ObjectDecl(obj_decl),
+            ..
+        }) => displayable_type(obj_decl, indent, level),
+        Some(val) => val.to_string(),
+        None => "no content".to_string(),
+    }
+}
+
 fn prepare() -> (Stdout, String, String) {
     let separator = (0..80).map(|_| "-").collect::<String>();
     let indent = (0..4).map(|_| " ").collect::<String>();
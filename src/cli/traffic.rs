@@ -0,0 +1,27 @@
+use std::fs;
+
+use arc_isle::schema::Schema;
+use arc_isle::traffic::TrafficChecker;
+
+pub fn run_check_traffic(
+    parsed_schema: &Schema,
+    file_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let har = fs::read_to_string(file_path)?;
+    let checker = TrafficChecker {
+        schema: parsed_schema,
+    };
+    let report = checker.check_har(&har)?;
+    for issue in &report.issues {
+        println!("{}", issue);
+    }
+    println!(
+        "Checked {} requests, found {} issues",
+        report.entries,
+        report.issues.len()
+    );
+    if !report.issues.is_empty() {
+        return Err(format!("{} does not match the specification", file_path).into());
+    }
+    Ok(())
+}
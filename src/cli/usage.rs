@@ -0,0 +1,48 @@
+use arc_isle::parser;
+use arc_isle::schema::Schema;
+use arc_isle::usage::{prune, UsageAnalyzer};
+
+pub fn run_unused(
+    parsed_schema: &Schema,
+    parent_path: &str,
+    prune_path: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let analyzer = UsageAnalyzer {
+        schema: parsed_schema,
+    };
+    let report = analyzer.analyze();
+    if report.unused_types.is_empty() && report.dead_files.is_empty() {
+        println!("Every declaration is used");
+    }
+    if !report.unused_types.is_empty() {
+        println!("Unused types:");
+        for (name, location) in &report.unused_types {
+            println!("    {} at {}", name, location);
+        }
+    }
+    if !report.dead_files.is_empty() {
+        println!("Files contributing nothing:");
+        for file in &report.dead_files {
+            println!("    {}", file);
+        }
+    }
+    if let Some(prune_path) = prune_path {
+        prune(parsed_schema, parent_path, prune_path, &report)?;
+        // The trimmed spec has to stay valid on its own.
+        let pruned = parser::parse(prune_path)?;
+        if invalid_declarations(&pruned) > invalid_declarations(parsed_schema) {
+            return Err(format!(
+                "Pruned spec written to {} has invalid declarations",
+                prune_path
+            )
+            .into());
+        }
+        println!("Pruned spec written to {}", prune_path);
+    }
+    Ok(())
+}
+
+fn invalid_declarations(schema: &Schema) -> usize {
+    let types = schema.types.iter().filter(|val| val.is_err()).count();
+    types + schema.interfaces.iter().filter(|val| val.is_err()).count()
+}
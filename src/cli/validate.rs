@@ -0,0 +1,18 @@
+use std::fs;
+
+use arc_isle::schema::Schema;
+use arc_isle::validation::InstanceValidator;
+
+pub fn run_validate_instance(
+    parsed_schema: &Schema,
+    type_name: &str,
+    file_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let json = fs::read_to_string(file_path)?;
+    let validator = InstanceValidator {
+        schema: parsed_schema,
+    };
+    validator.validate_json(type_name, &json)?;
+    println!("{} is a valid {}", file_path, type_name);
+    Ok(())
+}
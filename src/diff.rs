@@ -0,0 +1,1061 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+use crate::schema::{
+    ApiSpec, DataType, DataTypeDecl, Host, HttpPayload, InterfaceDecl, InterfaceSpec,
+    InterfaceVersions, PropertyDecl, Schema, StatusCode, TypeDecl,
+};
+
+/// Compares two versions of a schema and classifies each change as breaking for clients or not.
+pub struct SchemaDiff<'a> {
+    pub old: &'a Schema,
+    pub new: &'a Schema,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Change {
+    /// Declaration the change belongs to, like `GET news/{entry_id} response 200` or `type user`.
+    pub subject: String,
+    pub kind: ChangeKind,
+    pub is_breaking: bool,
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.subject, self.kind)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ChangeKind {
+    HostAdded,
+    HostRemoved,
+    AddressChanged {
+        old: String,
+        new: String,
+    },
+    ValuesNarrowed {
+        variable: String,
+        removed: Vec<String>,
+    },
+    ValuesWidened {
+        variable: String,
+        added: Vec<String>,
+    },
+    VersioningChanged,
+    InterfaceAdded,
+    InterfaceRemoved,
+    VersionDropped(u32),
+    VersionsChanged {
+        old: String,
+        new: String,
+    },
+    PayloadChanged {
+        old: String,
+        new: String,
+    },
+    ResponseAdded,
+    ResponseRemoved,
+    ContentChanged {
+        old: String,
+        new: String,
+    },
+    PropertyAdded {
+        path: String,
+        is_required: bool,
+    },
+    PropertyRemoved(String),
+    MadeRequired(String),
+    MadeOptional(String),
+    TypeChanged {
+        path: String,
+        old: String,
+        new: String,
+    },
+    TypeAdded,
+    TypeRemoved,
+}
+
+impl Display for ChangeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeKind::HostAdded => write!(f, "host added."),
+            ChangeKind::HostRemoved => write!(f, "host removed."),
+            ChangeKind::AddressChanged { old, new } => {
+                write!(f, "address changed from {} to {}.", old, new)
+            }
+            ChangeKind::ValuesNarrowed { variable, removed } => write!(
+                f,
+                "values {} of variable `{}` removed.",
+                removed.join(", "),
+                variable
+            ),
+            ChangeKind::ValuesWidened { variable, added } => write!(
+                f,
+                "values {} of variable `{}` added.",
+                added.join(", "),
+                variable
+            ),
+            ChangeKind::VersioningChanged => write!(f, "way to pass the version changed."),
+            ChangeKind::InterfaceAdded => write!(f, "interface added."),
+            ChangeKind::InterfaceRemoved => write!(f, "interface removed."),
+            ChangeKind::VersionDropped(version) => {
+                write!(f, "interface removed from version {}.", version)
+            }
+            ChangeKind::VersionsChanged { old, new } => {
+                write!(f, "versions changed from {} to {}.", old, new)
+            }
+            ChangeKind::PayloadChanged { old, new } => {
+                write!(f, "payload changed from {} to {}.", old, new)
+            }
+            ChangeKind::ResponseAdded => write!(f, "response added."),
+            ChangeKind::ResponseRemoved => write!(f, "response removed."),
+            ChangeKind::ContentChanged { old, new } => {
+                write!(f, "content changed from {} to {}.", old, new)
+            }
+            ChangeKind::PropertyAdded { path, is_required } => {
+                let optionality = if *is_required { "required" } else { "optional" };
+                write!(f, "{} property `{}` added.", optionality, path)
+            }
+            ChangeKind::PropertyRemoved(path) => write!(f, "property `{}` removed.", path),
+            ChangeKind::MadeRequired(path) => write!(f, "property `{}` made required.", path),
+            ChangeKind::MadeOptional(path) => write!(f, "property `{}` made optional.", path),
+            ChangeKind::TypeChanged { path, old, new } if path.is_empty() => {
+                write!(f, "type changed from {} to {}.", old, new)
+            }
+            ChangeKind::TypeChanged { path, old, new } => write!(
+                f,
+                "type of property `{}` changed from {} to {}.",
+                path, old, new
+            ),
+            ChangeKind::TypeAdded => write!(f, "type added."),
+            ChangeKind::TypeRemoved => write!(f, "type removed."),
+        }
+    }
+}
+
+/// Where values of a declaration travel, which decides whether a change breaks clients:
+/// they send values in requests and rely on values in responses.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+struct Usage {
+    request: bool,
+    response: bool,
+}
+
+const REQUEST: Usage = Usage {
+    request: true,
+    response: false,
+};
+
+const RESPONSE: Usage = Usage {
+    request: false,
+    response: true,
+};
+
+impl Usage {
+    /// Whether a change, breaking requests and/or responses, breaks declarations with this usage.
+    fn breaks(&self, in_request: bool, in_response: bool) -> bool {
+        (self.request && in_request) || (self.response && in_response)
+    }
+}
+
+impl<'a> SchemaDiff<'a> {
+    pub fn changes(&self) -> Vec<Change> {
+        let mut changes = Vec::new();
+        self.compare_hosts(&mut changes);
+        if self.old.versioning != self.new.versioning {
+            changes.push(Change {
+                subject: "versioning".to_string(),
+                kind: ChangeKind::VersioningChanged,
+                is_breaking: true,
+            });
+        }
+        self.compare_interfaces(&mut changes);
+        self.compare_types(&mut changes);
+        changes
+    }
+
+    fn compare_hosts(&self, changes: &mut Vec<Change>) {
+        for old in &self.old.hosts {
+            let subject = format!("host {}", old.env);
+            let Some(new) = self.new.hosts.iter().find(|host| host.env == old.env) else {
+                changes.push(Change {
+                    subject,
+                    kind: ChangeKind::HostRemoved,
+                    is_breaking: true,
+                });
+                continue;
+            };
+            if old.address != new.address {
+                changes.push(Change {
+                    subject: subject.clone(),
+                    kind: ChangeKind::AddressChanged {
+                        old: old.address.clone(),
+                        new: new.address.clone(),
+                    },
+                    is_breaking: true,
+                });
+            }
+            compare_variables(&subject, old, new, changes);
+        }
+        for new in &self.new.hosts {
+            if !self.old.hosts.iter().any(|host| host.env == new.env) {
+                changes.push(Change {
+                    subject: format!("host {}", new.env),
+                    kind: ChangeKind::HostAdded,
+                    is_breaking: false,
+                });
+            }
+        }
+    }
+
+    fn compare_interfaces(&self, changes: &mut Vec<Change>) {
+        for old in self.old.interfaces.iter().flatten() {
+            let subject = route(old);
+            let Some(new) = find_interface(self.new, old) else {
+                changes.push(Change {
+                    subject,
+                    kind: ChangeKind::InterfaceRemoved,
+                    is_breaking: true,
+                });
+                continue;
+            };
+            compare_versions(&subject, &old.versions, &new.versions, changes);
+            let (InterfaceSpec::Api(old_api), InterfaceSpec::Api(new_api)) = (&old.spec, &new.spec);
+            self.compare_api(&subject, old_api, new_api, changes);
+            // Overrides are compared with what the other schema serves in the same version.
+            let mut versions: Vec<u32> = old
+                .versions
+                .overrides
+                .keys()
+                .chain(new.versions.overrides.keys())
+                .copied()
+                .collect();
+            versions.sort();
+            versions.dedup();
+            for version in versions {
+                let old_api = old.versions.override_for(version).unwrap_or(old_api);
+                let new_api = new.versions.override_for(version).unwrap_or(new_api);
+                let subject = format!("{} (v{})", subject, version);
+                self.compare_api(&subject, old_api, new_api, changes);
+            }
+        }
+        for new in self.new.interfaces.iter().flatten() {
+            if find_interface(self.old, new).is_none() {
+                changes.push(Change {
+                    subject: route(new),
+                    kind: ChangeKind::InterfaceAdded,
+                    is_breaking: false,
+                });
+            }
+        }
+    }
+
+    fn compare_api(&self, subject: &str, old: &ApiSpec, new: &ApiSpec, changes: &mut Vec<Change>) {
+        match (&old.payload, &new.payload) {
+            (Some(HttpPayload::Query(old)), Some(HttpPayload::Query(new))) => {
+                let subject = format!("{} query", subject);
+                compare_properties(&subject, "", old, new, REQUEST, changes);
+            }
+            (Some(HttpPayload::Body(old)), Some(HttpPayload::Body(new))) => {
+                let subject = format!("{} body", subject);
+                compare_properties(&subject, "", old, new, REQUEST, changes);
+            }
+            (None, None) => {}
+            (old, new) => {
+                // Declaring optional properties only doesn't affect existing clients.
+                let is_breaking = old.is_some() || new.as_ref().is_some_and(has_required);
+                changes.push(Change {
+                    subject: subject.to_string(),
+                    kind: ChangeKind::PayloadChanged {
+                        old: payload_name(old),
+                        new: payload_name(new),
+                    },
+                    is_breaking,
+                });
+            }
+        }
+        let no_responses = Default::default();
+        let old_responses = old.responses.as_ref().unwrap_or(&no_responses);
+        let new_responses = new.responses.as_ref().unwrap_or(&no_responses);
+        let mut codes: Vec<&StatusCode> = old_responses.keys().collect();
+        codes.sort_by_key(|code| code.as_key());
+        for code in codes {
+            let subject = format!("{} response {}", subject, code);
+            let old = &old_responses[code];
+            let Some(new) = new_responses.get(code) else {
+                changes.push(Change {
+                    subject,
+                    kind: ChangeKind::ResponseRemoved,
+                    is_breaking: true,
+                });
+                continue;
+            };
+            match (old, new) {
+                (Some(old), Some(new)) => compare_data_type(
+                    &subject,
+                    "",
+                    &old.data_type,
+                    &new.data_type,
+                    RESPONSE,
+                    changes,
+                ),
+                (None, None) => {}
+                (old, new) => changes.push(Change {
+                    subject,
+                    kind: ChangeKind::ContentChanged {
+                        old: content_name(old),
+                        new: content_name(new),
+                    },
+                    is_breaking: true,
+                }),
+            }
+        }
+        let mut codes: Vec<&StatusCode> = new_responses
+            .keys()
+            .filter(|code| !old_responses.contains_key(code))
+            .collect();
+        codes.sort_by_key(|code| code.as_key());
+        for code in codes {
+            changes.push(Change {
+                subject: format!("{} response {}", subject, code),
+                kind: ChangeKind::ResponseAdded,
+                is_breaking: false,
+            });
+        }
+    }
+
+    fn compare_types(&self, changes: &mut Vec<Change>) {
+        for old in self.old.types.iter().flatten() {
+            let name = old.qualified_name();
+            let subject = format!("type {}", name);
+            let usage = self.usage(old);
+            let new = self
+                .new
+                .types
+                .iter()
+                .flatten()
+                .find(|new| new.qualified_name() == name);
+            match new {
+                Some(new) => compare_properties(
+                    &subject,
+                    "",
+                    &old.property_decls,
+                    &new.property_decls,
+                    usage,
+                    changes,
+                ),
+                None => changes.push(Change {
+                    subject,
+                    kind: ChangeKind::TypeRemoved,
+                    is_breaking: usage.breaks(true, true),
+                }),
+            }
+        }
+        for new in self.new.types.iter().flatten() {
+            let name = new.qualified_name();
+            if !self
+                .old
+                .types
+                .iter()
+                .flatten()
+                .any(|old| old.qualified_name() == name)
+            {
+                changes.push(Change {
+                    subject: format!("type {}", name),
+                    kind: ChangeKind::TypeAdded,
+                    is_breaking: false,
+                });
+            }
+        }
+    }
+
+    /// How a type of the old schema is used by its interfaces, directly or through other types.
+    fn usage(&self, type_decl: &TypeDecl) -> Usage {
+        let mut usage = Usage::default();
+        for decl in self.old.interfaces.iter().flatten() {
+            let InterfaceSpec::Api(base) = &decl.spec;
+            for api in std::iter::once(base).chain(decl.versions.overrides.values()) {
+                if let Some(HttpPayload::Query(properties) | HttpPayload::Body(properties)) =
+                    &api.payload
+                {
+                    let mut visited = HashSet::new();
+                    if properties
+                        .iter()
+                        .any(|property| self.property_uses(property, type_decl, &mut visited))
+                    {
+                        usage.request = true;
+                    }
+                }
+                for data_type_decl in api.responses.iter().flat_map(|val| val.values()).flatten() {
+                    let mut visited = HashSet::new();
+                    if self.uses(&data_type_decl.data_type, type_decl, &mut visited) {
+                        usage.response = true;
+                    }
+                }
+            }
+        }
+        usage
+    }
+
+    fn property_uses(
+        &self,
+        property: &PropertyDecl,
+        type_decl: &TypeDecl,
+        visited: &mut HashSet<String>,
+    ) -> bool {
+        property
+            .data_type_decl
+            .as_ref()
+            .is_ok_and(|decl| self.uses(&decl.data_type, type_decl, visited))
+    }
+
+    fn uses(
+        &self,
+        data_type: &DataType,
+        type_decl: &TypeDecl,
+        visited: &mut HashSet<String>,
+    ) -> bool {
+        match data_type {
+            DataType::Primitive(_) | DataType::Format(_) | DataType::Any => false,
+            DataType::Array(val) | DataType::Dict(_, val) => self.uses(val, type_decl, visited),
+            DataType::ObjectDecl(decl) => decl
+                .property_decls
+                .iter()
+                .any(|property| self.property_uses(property, type_decl, visited)),
+            DataType::Object(name) => {
+                if *name == type_decl.name || *name == type_decl.qualified_name() {
+                    return true;
+                }
+                if !visited.insert(name.clone()) {
+                    return false;
+                }
+                let referenced = self.old.types.iter().flatten().find(|decl| {
+                    decl.qualified_name() == *name || (decl.name == *name && !name.contains('.'))
+                });
+                referenced.is_some_and(|decl| {
+                    decl.property_decls
+                        .iter()
+                        .any(|property| self.property_uses(property, type_decl, visited))
+                })
+            }
+        }
+    }
+}
+
+fn compare_variables(subject: &str, old: &Host, new: &Host, changes: &mut Vec<Change>) {
+    for old_variable in &old.variables {
+        let Some(new_variable) = new
+            .variables
+            .iter()
+            .find(|variable| variable.name == old_variable.name)
+        else {
+            continue;
+        };
+        let (Some(old_values), Some(new_values)) = (&old_variable.values, &new_variable.values)
+        else {
+            continue;
+        };
+        let removed: Vec<String> = old_values
+            .iter()
+            .filter(|value| !new_values.contains(value))
+            .cloned()
+            .collect();
+        let added: Vec<String> = new_values
+            .iter()
+            .filter(|value| !old_values.contains(value))
+            .cloned()
+            .collect();
+        let variable = old_variable.name.clone();
+        if !removed.is_empty() {
+            changes.push(Change {
+                subject: subject.to_string(),
+                kind: ChangeKind::ValuesNarrowed {
+                    variable: variable.clone(),
+                    removed,
+                },
+                is_breaking: true,
+            });
+        }
+        if !added.is_empty() {
+            changes.push(Change {
+                subject: subject.to_string(),
+                kind: ChangeKind::ValuesWidened { variable, added },
+                is_breaking: false,
+            });
+        }
+    }
+}
+
+fn compare_versions(
+    subject: &str,
+    old: &InterfaceVersions,
+    new: &InterfaceVersions,
+    changes: &mut Vec<Change>,
+) {
+    if old.listed == new.listed && old.since == new.since && old.removed_in == new.removed_in {
+        return;
+    }
+    // Versions beyond every mentioned one behave the same as the last of them.
+    let mentioned = [old, new]
+        .iter()
+        .flat_map(|versions| {
+            let listed = versions.listed.iter().flatten().copied();
+            listed.chain(versions.since).chain(versions.removed_in)
+        })
+        .max()
+        .unwrap_or(1);
+    let dropped: Vec<u32> = (1..=mentioned)
+        .filter(|version| old.contains(*version) && !new.contains(*version))
+        .collect();
+    for version in &dropped {
+        changes.push(Change {
+            subject: subject.to_string(),
+            kind: ChangeKind::VersionDropped(*version),
+            is_breaking: true,
+        });
+    }
+    if dropped.is_empty() {
+        changes.push(Change {
+            subject: subject.to_string(),
+            kind: ChangeKind::VersionsChanged {
+                old: old.to_string(),
+                new: new.to_string(),
+            },
+            is_breaking: false,
+        });
+    }
+}
+
+fn compare_properties(
+    subject: &str,
+    path: &str,
+    old: &[PropertyDecl],
+    new: &[PropertyDecl],
+    usage: Usage,
+    changes: &mut Vec<Change>,
+) {
+    let mut push = |kind, is_breaking| {
+        changes.push(Change {
+            subject: subject.to_string(),
+            kind,
+            is_breaking,
+        })
+    };
+    let mut nested = Vec::new();
+    for old_property in old {
+        let path = property_path(path, &old_property.name);
+        let Some(new_property) = new
+            .iter()
+            .find(|property| property.name == old_property.name)
+        else {
+            // Clients that keep sending a removed optional property aren't affected, it's ignored.
+            let was_required = old_property
+                .data_type_decl
+                .as_ref()
+                .is_ok_and(|decl| decl.is_required);
+            push(
+                ChangeKind::PropertyRemoved(path),
+                usage.breaks(was_required, true),
+            );
+            continue;
+        };
+        let (Ok(old_decl), Ok(new_decl)) =
+            (&old_property.data_type_decl, &new_property.data_type_decl)
+        else {
+            continue;
+        };
+        match (old_decl.is_required, new_decl.is_required) {
+            (false, true) => push(
+                ChangeKind::MadeRequired(path.clone()),
+                usage.breaks(true, false),
+            ),
+            (true, false) => push(
+                ChangeKind::MadeOptional(path.clone()),
+                usage.breaks(false, true),
+            ),
+            _ => {}
+        }
+        nested.push((path, old_decl, new_decl));
+    }
+    for new_property in new {
+        if old
+            .iter()
+            .any(|property| property.name == new_property.name)
+        {
+            continue;
+        }
+        let is_required = new_property
+            .data_type_decl
+            .as_ref()
+            .is_ok_and(|decl| decl.is_required);
+        push(
+            ChangeKind::PropertyAdded {
+                path: property_path(path, &new_property.name),
+                is_required,
+            },
+            is_required && usage.breaks(true, false),
+        );
+    }
+    for (path, old_decl, new_decl) in nested {
+        compare_data_type(
+            subject,
+            &path,
+            &old_decl.data_type,
+            &new_decl.data_type,
+            usage,
+            changes,
+        );
+    }
+}
+
+fn compare_data_type(
+    subject: &str,
+    path: &str,
+    old: &DataType,
+    new: &DataType,
+    usage: Usage,
+    changes: &mut Vec<Change>,
+) {
+    match (old, new) {
+        (DataType::ObjectDecl(old), DataType::ObjectDecl(new)) => compare_properties(
+            subject,
+            path,
+            &old.property_decls,
+            &new.property_decls,
+            usage,
+            changes,
+        ),
+        (DataType::Array(old), DataType::Array(new)) => {
+            compare_data_type(subject, &format!("{}[]", path), old, new, usage, changes)
+        }
+        (DataType::Dict(old_key, old), DataType::Dict(new_key, new)) if old_key == new_key => {
+            compare_data_type(subject, &format!("{}{{}}", path), old, new, usage, changes)
+        }
+        _ if old != new => changes.push(Change {
+            subject: subject.to_string(),
+            kind: ChangeKind::TypeChanged {
+                path: path.to_string(),
+                old: old.to_string(),
+                new: new.to_string(),
+            },
+            is_breaking: usage.breaks(true, true),
+        }),
+        _ => {}
+    }
+}
+
+fn property_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", parent, name)
+    }
+}
+
+fn route(decl: &InterfaceDecl) -> String {
+    let InterfaceSpec::Api(api) = &decl.spec;
+    format!("{} {}", api.method, decl.ident)
+}
+
+/// Route with parameter names erased, so that renaming a parameter keeps the interface the same.
+fn route_key(decl: &InterfaceDecl) -> String {
+    let segments: Vec<&str> = decl
+        .ident
+        .split('/')
+        .filter(|val| !val.is_empty())
+        .map(|segment| {
+            if segment.starts_with('{') {
+                "{}"
+            } else {
+                segment
+            }
+        })
+        .collect();
+    let InterfaceSpec::Api(api) = &decl.spec;
+    format!("{} {}", api.method, segments.join("/"))
+}
+
+fn find_interface<'s>(schema: &'s Schema, decl: &InterfaceDecl) -> Option<&'s InterfaceDecl> {
+    let key = route_key(decl);
+    schema
+        .interfaces
+        .iter()
+        .flatten()
+        .find(|other| route_key(other) == key)
+}
+
+fn has_required(payload: &HttpPayload) -> bool {
+    let (HttpPayload::Query(properties) | HttpPayload::Body(properties)) = payload;
+    properties.iter().any(|property| {
+        property
+            .data_type_decl
+            .as_ref()
+            .is_ok_and(|decl| decl.is_required)
+    })
+}
+
+fn payload_name(payload: &Option<HttpPayload>) -> String {
+    match payload {
+        Some(HttpPayload::Query(_)) => "query".to_string(),
+        Some(HttpPayload::Body(_)) => "body".to_string(),
+        None => "none".to_string(),
+    }
+}
+
+fn content_name(content: &Option<DataTypeDecl>) -> String {
+    match content {
+        Some(decl) => decl.data_type.to_string(),
+        None => "no content".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diff::{ChangeKind, SchemaDiff};
+    use crate::test_utils::{TempSpec, MAIN};
+
+    fn diff(old: &TempSpec, new: &TempSpec) -> Vec<(String, ChangeKind, bool)> {
+        let old = old.parse();
+        let new = new.parse();
+        SchemaDiff {
+            old: &old,
+            new: &new,
+        }
+        .changes()
+        .into_iter()
+        .map(|change| (change.subject, change.kind, change.is_breaking))
+        .collect()
+    }
+
+    /// Changes between two specs with `MAIN` as the root document, as types and interfaces.
+    fn changes(
+        name: &str,
+        old: (&str, &str),
+        new: (&str, &str),
+    ) -> Vec<(String, ChangeKind, bool)> {
+        diff(
+            &TempSpec::with_main(&format!("{}-old", name), old.0, old.1),
+            &TempSpec::with_main(&format!("{}-new", name), new.0, new.1),
+        )
+    }
+
+    fn change(subject: &str, kind: ChangeKind, is_breaking: bool) -> (String, ChangeKind, bool) {
+        (subject.to_string(), kind, is_breaking)
+    }
+
+    #[test]
+    fn test_type_removed() {
+        let changes = changes(
+            "diff-type-removed",
+            (
+                "user:\n  id: str\nlog:\n  text: str\ndraft:\n  text: str\n",
+                "declarations:
+  - path: users
+    method: get
+    response: user
+  - path: logs
+    method: get
+    response: log
+",
+            ),
+            (
+                "user:\n  id: str\n",
+                "declarations:
+  - path: users
+    method: get
+    response: user
+",
+            ),
+        );
+        assert_eq!(
+            changes,
+            vec![
+                change("GET logs", ChangeKind::InterfaceRemoved, true),
+                change("type types.log", ChangeKind::TypeRemoved, true),
+                change("type types.draft", ChangeKind::TypeRemoved, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_interface_removed() {
+        let changes = changes(
+            "diff-interface-removed",
+            (
+                "user:\n  id: str\n",
+                "declarations:
+  - path: users/{id}
+    method: get
+    response: user
+  - path: users/{id}
+    method: delete
+",
+            ),
+            (
+                "user:\n  id: str\n",
+                "declarations:
+  - path: users/{user_id}
+    method: get
+    response: user
+  - path: users
+    method: post
+    body:
+      name: str
+    response: user
+",
+            ),
+        );
+        assert_eq!(
+            changes,
+            vec![
+                change("DELETE users/{id}", ChangeKind::InterfaceRemoved, true),
+                change("POST users", ChangeKind::InterfaceAdded, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_property_type_changed() {
+        let interfaces = "declarations:
+  - path: users
+    method: post
+    body:
+      tags: array[str]
+    response: user
+";
+        let changes = changes(
+            "diff-type-changed",
+            (
+                "user:\n  id: str\n  age: int\ndraft:\n  text: str\n",
+                interfaces,
+            ),
+            (
+                "user:\n  id: str\n  age: str\ndraft:\n  text: array[str]\n",
+                &interfaces.replace("array[str]", "array[int]"),
+            ),
+        );
+        let type_changed = |path: &str, old: &str, new: &str| ChangeKind::TypeChanged {
+            path: path.to_string(),
+            old: old.to_string(),
+            new: new.to_string(),
+        };
+        assert_eq!(
+            changes,
+            vec![
+                change(
+                    "POST users body",
+                    type_changed("tags[]", "str", "int"),
+                    true
+                ),
+                change("type types.user", type_changed("age", "int", "str"), true),
+                change(
+                    "type types.draft",
+                    type_changed("text", "str", "array[str]"),
+                    false
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_optionality_changed() {
+        let changes = changes(
+            "diff-optionality",
+            (
+                "user:\n  id: str\n  name: str\n  email: str?\n",
+                "declarations:
+  - path: users
+    method: post
+    body:
+      name: str
+      age: int?
+    response: user
+",
+            ),
+            (
+                "user:\n  id: str\n  name: str?\n  email: str\n",
+                "declarations:
+  - path: users
+    method: post
+    body:
+      name: str?
+      age: int
+    response: user
+",
+            ),
+        );
+        let path = |val: &str| val.to_string();
+        assert_eq!(
+            changes,
+            vec![
+                change(
+                    "POST users body",
+                    ChangeKind::MadeOptional(path("name")),
+                    false
+                ),
+                change(
+                    "POST users body",
+                    ChangeKind::MadeRequired(path("age")),
+                    true
+                ),
+                change(
+                    "type types.user",
+                    ChangeKind::MadeOptional(path("name")),
+                    true
+                ),
+                change(
+                    "type types.user",
+                    ChangeKind::MadeRequired(path("email")),
+                    false
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_property_removed() {
+        let changes = changes(
+            "diff-property-removed",
+            (
+                "user:\n  id: str\n  name: str?\n",
+                "declarations:
+  - path: users
+    method: post
+    body:
+      name: str
+      age: int?
+      role: str
+    response: user
+",
+            ),
+            (
+                "user:\n  id: str\n",
+                "declarations:
+  - path: users
+    method: post
+    body:
+      name: str
+    response: user
+",
+            ),
+        );
+        let path = |val: &str| val.to_string();
+        assert_eq!(
+            changes,
+            vec![
+                change(
+                    "POST users body",
+                    ChangeKind::PropertyRemoved(path("age")),
+                    false
+                ),
+                change(
+                    "POST users body",
+                    ChangeKind::PropertyRemoved(path("role")),
+                    true
+                ),
+                change(
+                    "type types.user",
+                    ChangeKind::PropertyRemoved(path("name")),
+                    true
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_response_removed() {
+        let changes = changes(
+            "diff-response-removed",
+            (
+                "user:\n  id: str\n",
+                "declarations:
+  - path: users/{id}
+    method: delete
+    response:
+      204:
+      404: str
+",
+            ),
+            (
+                "user:\n  id: str\n",
+                "declarations:
+  - path: users/{id}
+    method: delete
+    response:
+      204:
+      409: str
+",
+            ),
+        );
+        assert_eq!(
+            changes,
+            vec![
+                change(
+                    "DELETE users/{id} response 404",
+                    ChangeKind::ResponseRemoved,
+                    true
+                ),
+                change(
+                    "DELETE users/{id} response 409",
+                    ChangeKind::ResponseAdded,
+                    false
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_values_narrowed() {
+        let main = |values: &str| {
+            MAIN.replace(
+                "  prod: https://acme.com",
+                &format!(
+                    "  prod: https://acme.com
+  regional:
+    address: https://{{region}}.acme.com
+    variables:
+      region:
+        values: [{}]
+        default: eu",
+                    values
+                ),
+            )
+        };
+        let spec = |name: &str, main: &str| {
+            TempSpec::new(
+                name,
+                &[
+                    ("main.yaml", main),
+                    ("types.yml", "user:\n  id: str\n"),
+                    ("interfaces.yml", "declarations: []\n"),
+                ],
+            )
+        };
+        let changes = diff(
+            &spec("diff-values-old", &main("eu, us, jp")),
+            &spec("diff-values-new", &main("eu, us, br")),
+        );
+        assert_eq!(
+            changes,
+            vec![
+                change(
+                    "host regional",
+                    ChangeKind::ValuesNarrowed {
+                        variable: "region".to_string(),
+                        removed: vec!["jp".to_string()],
+                    },
+                    true
+                ),
+                change(
+                    "host regional",
+                    ChangeKind::ValuesWidened {
+                        variable: "region".to_string(),
+                        added: vec!["br".to_string()],
+                    },
+                    false
+                ),
+            ]
+        );
+    }
+}
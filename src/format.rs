@@ -0,0 +1,804 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::parser::packages::MODULES_DIR;
+use crate::schema::Schema;
+
+const INDENT: usize = 2;
+const IMPORT_KEY: &str = "_import";
+
+/// Rewrites every file of the spec, except vendored packages, in the canonical format.
+/// Returns files which weren't formatted, and in check mode leaves them untouched.
+pub fn format_spec(
+    schema: &Schema,
+    root_path: &str,
+    check: bool,
+) -> Result<Vec<String>, FormatError> {
+    let mut files: Vec<&str> = schema
+        .imports
+        .edges
+        .values()
+        .flatten()
+        .map(|val| val.as_str())
+        .filter(|val| !val.starts_with(MODULES_DIR))
+        .collect();
+    files.push("main.yaml");
+    files.sort();
+    files.dedup();
+    let mut changed = Vec::new();
+    for file in files {
+        let path = Path::new(root_path).join(file);
+        let source = fs::read_to_string(&path)
+            .map_err(|err| FormatError::Unreadable(file.to_string(), err))?;
+        let formatted = format_source(&source)
+            .map_err(|line| FormatError::Indentation(file.to_string(), line))?;
+        if formatted == source {
+            continue;
+        }
+        if !check {
+            fs::write(&path, &formatted)
+                .map_err(|err| FormatError::Unreadable(file.to_string(), err))?;
+        }
+        changed.push(file.to_string());
+    }
+    Ok(changed)
+}
+
+/// Formats a single file. Comments and blank lines stay attached to the entry following them,
+/// several blank lines in a row collapse into one.
+/// Fails with the number of a line which indentation doesn't match the structure.
+fn format_source(source: &str) -> Result<String, usize> {
+    let lines = source
+        .lines()
+        .enumerate()
+        .map(|(index, raw)| Line::new(index + 1, raw))
+        .collect();
+    let mut parser = DocumentParser {
+        lines,
+        pos: 0,
+        pending: Vec::new(),
+    };
+    let root = match parser.peek() {
+        Some(line) => {
+            let indent = line.indent;
+            parser.node(indent)?
+        }
+        None => Node::Scalar(String::new()),
+    };
+    if let Some(line) = parser.peek() {
+        return Err(line.number);
+    }
+    let mut root = root;
+    normalize(&mut root);
+    let mut out = Vec::new();
+    match &root {
+        Node::Mapping(entries) | Node::Sequence(entries) => emit_entries(entries, 0, &mut out),
+        Node::Scalar(val) if !val.is_empty() => out.push(scalar(val)),
+        _ => {}
+    }
+    emit_comments(&parser.pending, 0, &mut out);
+    while out.last().is_some_and(|val| val.is_empty()) {
+        out.pop();
+    }
+    Ok(out.iter().map(|line| format!("{}\n", line)).collect())
+}
+
+struct Line {
+    number: usize,
+    indent: usize,
+    raw: String,
+    /// Content without indentation and trailing comment, empty for blank and comment lines.
+    text: String,
+    comment: Option<String>,
+}
+
+impl Line {
+    fn new(number: usize, raw: &str) -> Self {
+        let raw = raw.trim_end();
+        let content = raw.trim_start();
+        let (text, comment) = split_comment(content);
+        Line {
+            number,
+            indent: raw.len() - content.len(),
+            raw: raw.to_string(),
+            text: text.to_string(),
+            comment: comment.map(|val| val.to_string()),
+        }
+    }
+}
+
+enum Node {
+    Scalar(String),
+    /// Literal or folded scalar: header and lines relative to the entry.
+    Block(String, Vec<String>),
+    Mapping(Vec<Entry>),
+    Sequence(Vec<Entry>),
+}
+
+struct Entry {
+    /// Comment lines before the entry, blank lines are empty.
+    comments: Vec<String>,
+    /// Key of a mapping entry, sequence items have none.
+    key: Option<String>,
+    value: Node,
+    /// Comment at the end of the entry line.
+    comment: Option<String>,
+}
+
+struct DocumentParser {
+    lines: Vec<Line>,
+    pos: usize,
+    /// Comment and blank lines read ahead, which belong to the next entry.
+    pending: Vec<String>,
+}
+
+impl DocumentParser {
+    fn peek(&mut self) -> Option<&Line> {
+        while let Some(line) = self.lines.get(self.pos) {
+            if !line.text.is_empty() {
+                break;
+            }
+            self.pending.push(line.comment.clone().unwrap_or_default());
+            self.pos += 1;
+        }
+        self.lines.get(self.pos)
+    }
+
+    fn node(&mut self, indent: usize) -> Result<Node, usize> {
+        let Some(line) = self.peek() else {
+            return Ok(Node::Scalar(String::new()));
+        };
+        if is_item(&line.text) {
+            return self.sequence(indent);
+        }
+        if split_key(&line.text).is_some() {
+            return self.mapping(indent);
+        }
+        let text = line.text.clone();
+        if let Some(comment) = line.comment.clone() {
+            self.pending.push(comment);
+        }
+        self.pos += 1;
+        self.check_no_nested(indent)?;
+        Ok(Node::Scalar(text))
+    }
+
+    fn mapping(&mut self, indent: usize) -> Result<Node, usize> {
+        let mut entries = Vec::new();
+        while let Some(line) = self.peek() {
+            if line.indent < indent || (line.indent == indent && is_item(&line.text)) {
+                break;
+            }
+            if line.indent > indent {
+                return Err(line.number);
+            }
+            let Some((key, rest)) = split_key(&line.text) else {
+                return Err(line.number);
+            };
+            let comment = line.comment.clone();
+            self.pos += 1;
+            let comments = std::mem::take(&mut self.pending);
+            let value = self.value(indent, &rest, false)?;
+            entries.push(Entry {
+                comments,
+                key: Some(key),
+                value,
+                comment,
+            });
+        }
+        Ok(Node::Mapping(entries))
+    }
+
+    fn sequence(&mut self, indent: usize) -> Result<Node, usize> {
+        let mut entries = Vec::new();
+        while let Some(line) = self.peek() {
+            if line.indent < indent || (line.indent == indent && !is_item(&line.text)) {
+                break;
+            }
+            if line.indent > indent {
+                return Err(line.number);
+            }
+            let rest = line.text[1..].to_string();
+            let comment = line.comment.clone();
+            let item = rest.trim_start();
+            let comments = std::mem::take(&mut self.pending);
+            let is_nested =
+                is_item(item) || (split_key(item).is_some() && !item.starts_with(['{', '[']));
+            let (comment, value) = if is_nested {
+                // Content after the dash is parsed as if it started its own line.
+                let offset = indent + 1 + rest.len() - item.len();
+                let line = &mut self.lines[self.pos];
+                line.indent = offset;
+                line.text = item.to_string();
+                (None, self.node(offset)?)
+            } else {
+                let item = item.to_string();
+                self.pos += 1;
+                (comment, self.value(indent, &item, true)?)
+            };
+            entries.push(Entry {
+                comments,
+                key: None,
+                value,
+                comment,
+            });
+        }
+        Ok(Node::Sequence(entries))
+    }
+
+    /// Value of an entry which line is already consumed, given text after the key or dash.
+    fn value(&mut self, indent: usize, rest: &str, is_item: bool) -> Result<Node, usize> {
+        if rest.starts_with(['|', '>']) {
+            return Ok(self.block(indent, rest));
+        }
+        if !rest.is_empty() {
+            self.check_no_nested(indent)?;
+            return Ok(Node::Scalar(rest.to_string()));
+        }
+        match self.peek() {
+            Some(line) if line.indent > indent => {
+                let indent = line.indent;
+                self.node(indent)
+            }
+            // Sequences are allowed at the same indentation as their key.
+            Some(line) if line.indent == indent && !is_item && self::is_item(&line.text) => {
+                self.sequence(indent)
+            }
+            _ => Ok(Node::Scalar(String::new())),
+        }
+    }
+
+    fn block(&mut self, indent: usize, header: &str) -> Node {
+        let start = self.pos;
+        while self
+            .lines
+            .get(self.pos)
+            .is_some_and(|line| line.raw.is_empty() || line.indent > indent)
+        {
+            self.pos += 1;
+        }
+        while self.pos > start && self.lines[self.pos - 1].raw.is_empty() {
+            self.pos -= 1;
+        }
+        let lines = &self.lines[start..self.pos];
+        let base = lines
+            .iter()
+            .filter(|line| !line.raw.is_empty())
+            .map(|line| line.indent)
+            .min()
+            .unwrap_or(0);
+        let lines = lines
+            .iter()
+            .map(|line| line.raw.get(base..).unwrap_or_default().to_string())
+            .collect();
+        Node::Block(header.to_string(), lines)
+    }
+
+    /// Multi-line plain scalars aren't supported, so deeper lines after a scalar are misplaced.
+    fn check_no_nested(&mut self, indent: usize) -> Result<(), usize> {
+        match self.peek() {
+            Some(line) if line.indent > indent => Err(line.number),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn is_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Splits a line into content and trailing comment, ignoring `#` within quotes.
+pub(crate) fn split_comment(content: &str) -> (&str, Option<&str>) {
+    let mut quote = None;
+    let mut prev = ' ';
+    for (index, val) in content.char_indices() {
+        match quote {
+            Some(open) if val == open => quote = None,
+            Some(_) => {}
+            None if (val == '\'' || val == '"') && " [{,:".contains(prev) => quote = Some(val),
+            None if val == '#' && prev.is_whitespace() => {
+                return (content[..index].trim_end(), Some(&content[index..]));
+            }
+            None => {}
+        }
+        prev = val;
+    }
+    (content, None)
+}
+
+/// Splits `key: value` into the key and trimmed value, outside of quotes and flow collections.
+pub(crate) fn split_key(text: &str) -> Option<(String, String)> {
+    if text.starts_with(['{', '[']) {
+        return None;
+    }
+    let mut quote = None;
+    let mut depth = 0;
+    let mut prev = ' ';
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, val)) = chars.next() {
+        match quote {
+            Some(open) if val == open => quote = None,
+            Some(_) => {}
+            None if (val == '\'' || val == '"') && " [{,".contains(prev) => quote = Some(val),
+            None if val == '[' || val == '{' => depth += 1,
+            None if val == ']' || val == '}' => depth -= 1,
+            None if val == ':'
+                && depth == 0
+                && chars.peek().map_or(true, |(_, next)| *next == ' ') =>
+            {
+                let key = text[..index].trim_end().to_string();
+                return Some((key, text[index + 1..].trim().to_string()));
+            }
+            None => {}
+        }
+        prev = val;
+    }
+    None
+}
+
+/// Orders keys inside interfaces and sorts imports, down the tree.
+fn normalize(node: &mut Node) {
+    match node {
+        Node::Mapping(entries) => {
+            for entry in entries.iter_mut() {
+                if entry.key.as_deref() == Some(IMPORT_KEY) {
+                    sort_imports(&mut entry.value);
+                }
+                normalize(&mut entry.value);
+            }
+            let keys: Vec<&str> = entries
+                .iter()
+                .filter_map(|val| val.key.as_deref())
+                .collect();
+            let ranks: Vec<usize> = keys.iter().map(|val| interface_key_rank(val)).collect();
+            let is_interface = keys.contains(&"path") && keys.contains(&"method");
+            if is_interface && ranks.windows(2).any(|val| val[0] > val[1]) {
+                entries
+                    .sort_by_key(|val| interface_key_rank(val.key.as_deref().unwrap_or_default()));
+                // Blank lines separated keys in their previous order.
+                for entry in entries.iter_mut() {
+                    entry.comments.retain(|val| !val.is_empty());
+                }
+            }
+        }
+        Node::Sequence(entries) => {
+            for entry in entries.iter_mut() {
+                normalize(&mut entry.value);
+            }
+        }
+        Node::Scalar(_) | Node::Block(_, _) => {}
+    }
+}
+
+fn interface_key_rank(key: &str) -> usize {
+    match key {
+        "path" => 0,
+        "method" => 1,
+        "query" | "body" => 2,
+        "body_type" => 3,
+        "response" => 4,
+        _ => 5,
+    }
+}
+
+fn sort_imports(value: &mut Node) {
+    match value {
+        Node::Scalar(val) => {
+            if let Some(mut items) = flow_items(val) {
+                items.sort_by_key(|val| import_file(val));
+                *val = format!("[{}]", items.join(", "));
+            }
+        }
+        Node::Sequence(entries) => entries.sort_by_key(|entry| match &entry.value {
+            Node::Scalar(val) => import_file(val),
+            Node::Mapping(entries) => entries
+                .iter()
+                .find(|val| val.key.as_deref() == Some("file"))
+                .map(|val| match &val.value {
+                    Node::Scalar(val) => import_file(val),
+                    _ => String::new(),
+                })
+                .unwrap_or_default(),
+            _ => String::new(),
+        }),
+        _ => {}
+    }
+}
+
+/// File of a plain import, or of a selective one written inline as `{file: path, only: [...]}`.
+fn import_file(value: &str) -> String {
+    let file = match value.strip_prefix('{') {
+        Some(inline) => inline.split_once("file:").map_or("", |(_, rest)| {
+            rest.split([',', '}']).next().unwrap_or_default()
+        }),
+        None => value,
+    };
+    file.trim().trim_matches(['"', '\'']).to_string()
+}
+
+/// Items of a flow sequence of plain values, nested collections and quoted values aren't touched.
+fn flow_items(value: &str) -> Option<Vec<String>> {
+    let inner = value.strip_prefix('[')?.strip_suffix(']')?;
+    if inner.contains(['[', ']', '{', '}', '"', '\'']) {
+        return None;
+    }
+    Some(
+        inner
+            .split(',')
+            .map(|val| val.trim().to_string())
+            .filter(|val| !val.is_empty())
+            .collect(),
+    )
+}
+
+fn scalar(value: &str) -> String {
+    if let Some(items) = flow_items(value) {
+        return format!("[{}]", items.join(", "));
+    }
+    flow_mapping(value)
+        .or_else(|| quoted_type(value))
+        .or_else(|| normalized_type(value))
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// Flow mapping with values formatted like ones of block mappings, like `{name: str, tags: array[str]}`.
+fn flow_mapping(value: &str) -> Option<String> {
+    let inner = value.strip_prefix('{')?.strip_suffix('}')?;
+    let entries = split_flow(inner)?
+        .iter()
+        .map(|entry| {
+            let (key, value) = split_key(entry)?;
+            Some(match value.is_empty() {
+                true => format!("{}:", key),
+                false => format!("{}: {}", key, scalar(&value)),
+            })
+        })
+        .collect::<Option<Vec<String>>>()?;
+    Some(format!("{{{}}}", entries.join(", ")))
+}
+
+/// Splits contents of a flow collection at commas outside of quotes and nested collections.
+fn split_flow(inner: &str) -> Option<Vec<String>> {
+    let mut items = Vec::new();
+    let mut quote = None;
+    let mut depth = 0;
+    let mut start = 0;
+    let mut prev = ' ';
+    for (index, val) in inner.char_indices() {
+        match quote {
+            Some(open) if val == open => quote = None,
+            Some(_) => {}
+            None if (val == '\'' || val == '"') && " [{,:".contains(prev) => quote = Some(val),
+            None if val == '[' || val == '{' => depth += 1,
+            None if val == ']' || val == '}' => {
+                depth -= 1;
+                if depth < 0 {
+                    return None;
+                }
+            }
+            None if val == ',' && depth == 0 => {
+                items.push(inner[start..index].trim().to_string());
+                start = index + 1;
+            }
+            None => {}
+        }
+        prev = val;
+    }
+    if quote.is_some() || depth != 0 {
+        return None;
+    }
+    let last = inner[start..].trim();
+    if !last.is_empty() {
+        items.push(last.to_string());
+    }
+    Some(items)
+}
+
+/// Type string in quotes, normalized keeping the quotes, like `"array[str]"`.
+fn quoted_type(value: &str) -> Option<String> {
+    let quote = value
+        .chars()
+        .next()
+        .filter(|val| *val == '"' || *val == '\'')?;
+    let inner = value.strip_prefix(quote)?.strip_suffix(quote)?;
+    if inner.contains(quote) {
+        return None;
+    }
+    Some(format!("{}{}{}", quote, normalized_type(inner)?, quote))
+}
+
+/// Type string with a single space after commas and none around brackets, like `dict[str, int]`.
+/// Values with spaces elsewhere, like descriptions, aren't types.
+fn normalized_type(value: &str) -> Option<String> {
+    if !value.starts_with(|val: char| val.is_ascii_alphabetic()) || !value.contains('[') {
+        return None;
+    }
+    let chars: Vec<char> = value.chars().collect();
+    let mut depth = 0;
+    let mut result = String::new();
+    for (index, val) in chars.iter().enumerate() {
+        match val {
+            ' ' => {
+                let prev = chars[..index].iter().rev().find(|val| **val != ' ');
+                let next = chars[index + 1..].iter().find(|val| **val != ' ');
+                // Not before an opening bracket, `Stay [hungry]` is text.
+                let is_inside = prev.is_some_and(|val| "[,".contains(*val))
+                    || next.is_some_and(|val| "],".contains(*val));
+                if !is_inside {
+                    return None;
+                }
+            }
+            '[' => {
+                depth += 1;
+                result.push('[');
+            }
+            ']' => {
+                depth -= 1;
+                if depth < 0 {
+                    return None;
+                }
+                result.push(']');
+            }
+            ',' => result.push_str(", "),
+            val if val.is_ascii_alphanumeric() || "_.?".contains(*val) => result.push(*val),
+            _ => return None,
+        }
+    }
+    (depth == 0).then_some(result)
+}
+
+fn emit_entries(entries: &[Entry], indent: usize, out: &mut Vec<String>) {
+    let pad = " ".repeat(indent);
+    for (index, entry) in entries.iter().enumerate() {
+        // Nested blocks start right after their key.
+        let comments = if index == 0 && indent > 0 {
+            let start = entry
+                .comments
+                .iter()
+                .take_while(|val| val.is_empty())
+                .count();
+            &entry.comments[start..]
+        } else {
+            &entry.comments[..]
+        };
+        emit_comments(comments, indent, out);
+        let head = match &entry.key {
+            Some(key) => format!("{}{}:", pad, key),
+            None => format!("{}-", pad),
+        };
+        let comment = entry
+            .comment
+            .as_ref()
+            .map(|val| format!(" {}", val))
+            .unwrap_or_default();
+        match &entry.value {
+            Node::Scalar(val) if val.is_empty() => out.push(format!("{}{}", head, comment)),
+            Node::Scalar(val) => out.push(format!("{} {}{}", head, scalar(val), comment)),
+            Node::Block(header, lines) => {
+                out.push(format!("{} {}{}", head, header, comment));
+                for line in lines {
+                    if line.is_empty() {
+                        out.push(String::new());
+                    } else {
+                        out.push(format!("{}{}{}", pad, " ".repeat(INDENT), line));
+                    }
+                }
+            }
+            Node::Mapping(children) | Node::Sequence(children) if entry.key.is_some() => {
+                out.push(format!("{}{}", head, comment));
+                emit_entries(children, indent + INDENT, out);
+            }
+            Node::Mapping(children) | Node::Sequence(children) => {
+                // The first line of a nested item goes after the dash, so comments of the entry on it,
+                // like one moved there by reordering, go above the dash.
+                let start = out.len();
+                emit_entries(children, indent + INDENT, out);
+                for line in out[start..].iter_mut() {
+                    if line.is_empty() {
+                        continue;
+                    }
+                    if line.trim_start().starts_with('#') {
+                        *line = format!("{}{}", pad, line.trim_start());
+                        continue;
+                    }
+                    *line = format!("{}- {}", pad, &line[indent + INDENT..]);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+fn emit_comments(comments: &[String], indent: usize, out: &mut Vec<String>) {
+    for comment in comments {
+        if !comment.is_empty() {
+            out.push(format!("{}{}", " ".repeat(indent), comment));
+        } else if out.last().is_some_and(|val| !val.is_empty()) {
+            out.push(String::new());
+        }
+    }
+}
+
+pub enum FormatError {
+    Unreadable(String, io::Error),
+    Indentation(String, usize),
+}
+
+impl FormatError {
+    fn default_fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            FormatError::Unreadable(file, err) => write!(f, "Cannot format {}: {}", file, err),
+            FormatError::Indentation(file, line) => {
+                write!(
+                    f,
+                    "Cannot format {}: unexpected indentation at line {}",
+                    file, line
+                )
+            }
+        }
+    }
+}
+
+impl Error for FormatError {}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+impl Debug for FormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::{format_source, format_spec};
+    use crate::test_utils::TempSpec;
+
+    #[test]
+    fn test_format_source() {
+        let source = "\
+# Feed
+declarations:
+    - response: array[ news_entry ] # Newest first
+      method: get
+      path: news
+
+
+      query:
+          search: str?
+
+    # Creation
+    - method: post
+      path: news
+      body: dict[str,int]
+";
+        let expected = "\
+# Feed
+declarations:
+  - path: news
+    method: get
+    query:
+      search: str?
+    response: array[news_entry] # Newest first
+
+  # Creation
+  - path: news
+    method: post
+    body: dict[str, int]
+";
+        let formatted = format_source(source).unwrap();
+        assert_eq!(expected, formatted);
+        assert_eq!(expected, format_source(&formatted).unwrap());
+    }
+
+    #[test]
+    fn test_format_imports_and_values() {
+        let source = "\
+_import: [users.yml,feed.yml]
+types:
+  _import:
+  - users.yml
+  # Legacy
+  - file: archive.yml
+    only: [post]
+note:
+  description: Users list [draft] # not a type
+  text: |
+      First line
+
+        indented
+  link: 'https://acme.com/#top'
+";
+        let expected = "\
+_import: [feed.yml, users.yml]
+types:
+  _import:
+    # Legacy
+    - file: archive.yml
+      only: [post]
+    - users.yml
+note:
+  description: Users list [draft] # not a type
+  text: |
+    First line
+
+      indented
+  link: 'https://acme.com/#top'
+";
+        assert_eq!(expected, format_source(source).unwrap());
+        assert_eq!(Err(3), format_source("user:\n  id: str\n    name: str\n"));
+    }
+
+    #[test]
+    fn test_format_quoted_and_flow_types() {
+        let source = "\
+user:
+  tags: \"array[ str ]\"
+  aliases: 'dict[str,str]?'
+  profile: {name: str, age: int,scores: dict[str,array[ int ]]}
+  address: {street: str, note: 'a, b'}
+  motto: \"Stay [hungry]\"
+";
+        let expected = "\
+user:
+  tags: \"array[str]\"
+  aliases: 'dict[str, str]?'
+  profile: {name: str, age: int, scores: dict[str, array[int]]}
+  address: {street: str, note: 'a, b'}
+  motto: \"Stay [hungry]\"
+";
+        let formatted = format_source(source).unwrap();
+        assert_eq!(expected, formatted);
+        assert_eq!(formatted, format_source(&formatted).unwrap());
+    }
+
+    #[test]
+    fn test_format_moved_comments() {
+        let source = "\
+declarations:
+  - method: get
+    # Latest first
+    path: news
+";
+        let expected = "\
+declarations:
+  # Latest first
+  - path: news
+    method: get
+";
+        let formatted = format_source(source).unwrap();
+        assert_eq!(expected, formatted);
+        assert_eq!(formatted, format_source(&formatted).unwrap());
+    }
+
+    #[test]
+    fn test_format_spec() {
+        let spec = TempSpec::with_main(
+            "format",
+            "user:\n    tags: array[ str ]\n    scores: dict[str,int]?\n",
+            "declarations:\n- method: get\n  # Listing\n  path: users\n  response: array[user]\n",
+        );
+
+        let changed = format_spec(&spec.parse(), spec.path(), false).unwrap();
+
+        assert_eq!(vec!["interfaces.yml", "main.yaml", "types.yml"], changed);
+        assert_eq!(
+            "user:\n  tags: array[str]\n  scores: dict[str, int]?\n",
+            spec.read("types.yml")
+        );
+        assert_eq!(
+            "declarations:\n  # Listing\n  - path: users\n    method: get\n    response: array[user]\n",
+            spec.read("interfaces.yml")
+        );
+        assert!(format_spec(&spec.parse(), spec.path(), true)
+            .unwrap()
+            .is_empty());
+    }
+}
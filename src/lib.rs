@@ -1,3 +1,14 @@
+pub mod diff;
+pub mod format;
+pub mod lint;
+pub mod mock;
+pub mod modify;
 pub mod parser;
+pub mod sample;
 pub mod schema;
+pub mod traffic;
+pub mod usage;
+pub mod validation;
 
+#[cfg(test)]
+mod test_utils;
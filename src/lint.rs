@@ -0,0 +1,530 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::path::Path;
+
+use yaml_rust::Yaml;
+
+use crate::parser::utils::{read_yaml, ReadError};
+use crate::schema::{
+    DataType, HttpPayload, InterfaceSpec, Location, PropertyDecl, Schema, StatusCode,
+};
+
+/// Name of the config file next to the root document.
+pub const CONFIG_FILE: &str = ".arcisle.yml";
+
+/// Nesting of inline objects allowed by default, as recommended for readability.
+const DEFAULT_MAX_NESTING: usize = 3;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum LintRule {
+    SnakeCaseFields,
+    PathParamNames,
+    UntypedContainers,
+    MaxNesting,
+    ErrorResponses,
+    UnusedTypes,
+}
+
+impl LintRule {
+    pub const ALL: [LintRule; 6] = [
+        LintRule::SnakeCaseFields,
+        LintRule::PathParamNames,
+        LintRule::UntypedContainers,
+        LintRule::MaxNesting,
+        LintRule::ErrorResponses,
+        LintRule::UnusedTypes,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintRule::SnakeCaseFields => "snake_case_fields",
+            LintRule::PathParamNames => "path_param_names",
+            LintRule::UntypedContainers => "untyped_containers",
+            LintRule::MaxNesting => "max_nesting",
+            LintRule::ErrorResponses => "error_responses",
+            LintRule::UnusedTypes => "unused_types",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LintRule> {
+        LintRule::ALL.into_iter().find(|rule| rule.name() == name)
+    }
+}
+
+impl Display for LintRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LintLevel {
+    Off,
+    Warn,
+    Error,
+}
+
+impl LintLevel {
+    fn from_yaml(value: &Yaml) -> Option<LintLevel> {
+        match value {
+            Yaml::String(val) if val == "off" => Some(LintLevel::Off),
+            Yaml::String(val) if val == "warn" => Some(LintLevel::Warn),
+            Yaml::String(val) if val == "error" => Some(LintLevel::Error),
+            Yaml::Boolean(false) => Some(LintLevel::Off),
+            _ => None,
+        }
+    }
+}
+
+impl Display for LintLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintLevel::Off => f.write_str("off"),
+            LintLevel::Warn => f.write_str("warn"),
+            LintLevel::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// Levels of rules, read from the `lint` section of `.arcisle.yml`:
+///
+/// ```yaml
+/// lint:
+///   snake_case_fields: error
+///   unused_types: off
+///   max_nesting:
+///     level: warn
+///     max: 2
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct LintConfig {
+    pub levels: HashMap<LintRule, LintLevel>,
+    pub max_nesting: usize,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            levels: LintRule::ALL
+                .into_iter()
+                .map(|rule| (rule, LintLevel::Warn))
+                .collect(),
+            max_nesting: DEFAULT_MAX_NESTING,
+        }
+    }
+}
+
+impl LintConfig {
+    /// Reads the config next to the root document, falling back to defaults when there is none.
+    pub fn load(parent_path: &str) -> Result<LintConfig, LintConfigError> {
+        let file_path = Path::new(parent_path).join(CONFIG_FILE);
+        if !file_path.exists() {
+            return Ok(LintConfig::default());
+        }
+        let yaml = read_yaml(&file_path.to_string_lossy()).map_err(LintConfigError::Unreadable)?;
+        match yaml.first() {
+            Some(config) => LintConfig::from_yaml(&config["lint"]),
+            None => Ok(LintConfig::default()),
+        }
+    }
+
+    pub fn from_yaml(section: &Yaml) -> Result<LintConfig, LintConfigError> {
+        let mut config = LintConfig::default();
+        let hash = match section {
+            Yaml::BadValue | Yaml::Null => return Ok(config),
+            Yaml::Hash(hash) => hash,
+            _ => return Err(LintConfigError::InvalidSection),
+        };
+        for (name, value) in hash {
+            let name = name.as_str().unwrap_or_default();
+            let rule =
+                LintRule::from_name(name).ok_or(LintConfigError::UnknownRule(name.to_string()))?;
+            let level = match value {
+                Yaml::Hash(_) => &value["level"],
+                _ => value,
+            };
+            let level = match level {
+                Yaml::BadValue => LintLevel::Warn,
+                level => LintLevel::from_yaml(level)
+                    .ok_or(LintConfigError::InvalidLevel(name.to_string()))?,
+            };
+            config.levels.insert(rule, level);
+            if rule == LintRule::MaxNesting {
+                match &value["max"] {
+                    Yaml::BadValue => {}
+                    Yaml::Integer(max) if *max > 0 => config.max_nesting = *max as usize,
+                    _ => return Err(LintConfigError::InvalidOption(name.to_string())),
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    fn level(&self, rule: LintRule) -> LintLevel {
+        self.levels.get(&rule).copied().unwrap_or(LintLevel::Warn)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LintDiagnostic {
+    pub rule: LintRule,
+    pub level: LintLevel,
+    pub location: Location,
+    /// Declaration the diagnostic belongs to, like `type user` or `GET news`.
+    pub subject: String,
+    pub message: String,
+}
+
+impl Display for LintDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}] {} {}: {}",
+            self.level, self.rule, self.location, self.subject, self.message
+        )
+    }
+}
+
+/// Runs enabled rules over the parsed schema. A declaration can allow rules by listing them
+/// under the reserved `_allow` key.
+pub struct Linter<'a> {
+    pub schema: &'a Schema,
+    pub config: &'a LintConfig,
+}
+
+/// Diagnostics of a single declaration, before levels and suppressions are applied.
+struct Findings {
+    subject: String,
+    list: Vec<(LintRule, String)>,
+}
+
+impl Findings {
+    fn push(&mut self, rule: LintRule, message: String) {
+        self.list.push((rule, message));
+    }
+}
+
+impl<'a> Linter<'a> {
+    pub fn lint(&self) -> Vec<LintDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let used_types = self.schema.used_types();
+        for (index, type_decl) in self.schema.types.iter().enumerate() {
+            let Ok(type_decl) = type_decl else {
+                continue;
+            };
+            let mut findings = Findings {
+                subject: format!("type {}", type_decl.qualified_name()),
+                list: Vec::new(),
+            };
+            self.check_properties(&type_decl.property_decls, "", 1, &mut findings);
+            if !used_types.contains(&type_decl.qualified_name()) {
+                findings.push(
+                    LintRule::UnusedTypes,
+                    "type is not used by any interface.".to_string(),
+                );
+            }
+            let location = &self.schema.locations.types[index];
+            let allowed = self.schema.suppressions.types.get(index);
+            self.report(findings, location, allowed, &mut diagnostics);
+        }
+        for (index, decl) in self.schema.interfaces.iter().enumerate() {
+            let Ok(decl) = decl else {
+                continue;
+            };
+            let InterfaceSpec::Api(api) = &decl.spec;
+            let mut findings = Findings {
+                subject: format!("{} {}", api.method, decl.ident),
+                list: Vec::new(),
+            };
+            for param in &decl.params {
+                if !is_typed_param(param) {
+                    findings.push(
+                        LintRule::PathParamNames,
+                        format!(
+                            "parameter `{{{}}}` should name what it identifies, like `{{user_id}}`.",
+                            param
+                        ),
+                    );
+                }
+            }
+            if let Some(HttpPayload::Query(properties) | HttpPayload::Body(properties)) =
+                &api.payload
+            {
+                self.check_properties(properties, "", 1, &mut findings);
+            }
+            let mut responses: Vec<_> = api.responses.iter().flatten().collect();
+            responses.sort_by_key(|(code, _)| code.as_key());
+            for (code, data_type_decl) in &responses {
+                if let Some(data_type_decl) = data_type_decl {
+                    let path = format!("response {}", code);
+                    self.check_data_type(&data_type_decl.data_type, &path, 0, &mut findings);
+                }
+            }
+            if !responses.iter().any(|(code, _)| is_client_error(code)) {
+                findings.push(
+                    LintRule::ErrorResponses,
+                    "no 4xx response is declared.".to_string(),
+                );
+            }
+            let location = &self.schema.locations.interfaces[index];
+            let allowed = self.schema.suppressions.interfaces.get(index);
+            self.report(findings, location, allowed, &mut diagnostics);
+        }
+        diagnostics
+    }
+
+    fn report(
+        &self,
+        findings: Findings,
+        location: &Location,
+        allowed: Option<&Vec<String>>,
+        diagnostics: &mut Vec<LintDiagnostic>,
+    ) {
+        for (rule, message) in findings.list {
+            let level = self.config.level(rule);
+            let is_allowed =
+                allowed.is_some_and(|allowed| allowed.iter().any(|val| val == rule.name()));
+            if level == LintLevel::Off || is_allowed {
+                continue;
+            }
+            diagnostics.push(LintDiagnostic {
+                rule,
+                level,
+                location: location.clone(),
+                subject: findings.subject.clone(),
+                message,
+            });
+        }
+    }
+
+    fn check_properties(
+        &self,
+        properties: &[PropertyDecl],
+        parent: &str,
+        depth: usize,
+        findings: &mut Findings,
+    ) {
+        for property in properties {
+            let path = if parent.is_empty() {
+                property.name.clone()
+            } else {
+                format!("{}.{}", parent, property.name)
+            };
+            if !is_snake_case(&property.name) {
+                findings.push(
+                    LintRule::SnakeCaseFields,
+                    format!("property `{}` should be snake_case.", path),
+                );
+            }
+            if let Ok(data_type_decl) = &property.data_type_decl {
+                self.check_data_type(&data_type_decl.data_type, &path, depth, findings);
+            }
+        }
+    }
+
+    /// Checks a data type found at the path, inside objects nested `depth` levels deep.
+    fn check_data_type(
+        &self,
+        data_type: &DataType,
+        path: &str,
+        depth: usize,
+        findings: &mut Findings,
+    ) {
+        match data_type {
+            DataType::Array(val) if **val == DataType::Any => findings.push(
+                LintRule::UntypedContainers,
+                format!("`{}` should declare its elements, like `array[str]`.", path),
+            ),
+            DataType::Dict(_, val) if **val == DataType::Any => findings.push(
+                LintRule::UntypedContainers,
+                format!(
+                    "`{}` should declare its values, like `dict[str, int]`.",
+                    path
+                ),
+            ),
+            DataType::Array(val) | DataType::Dict(_, val) => {
+                self.check_data_type(val, path, depth, findings)
+            }
+            DataType::ObjectDecl(type_decl) => {
+                if depth + 1 > self.config.max_nesting {
+                    findings.push(
+                        LintRule::MaxNesting,
+                        format!(
+                            "`{}` nests objects deeper than {} levels, declare a separate type.",
+                            path, self.config.max_nesting
+                        ),
+                    );
+                }
+                self.check_properties(&type_decl.property_decls, path, depth + 1, findings);
+            }
+            DataType::Primitive(_) | DataType::Format(_) | DataType::Object(_) | DataType::Any => {}
+        }
+    }
+}
+
+fn is_snake_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && !name.ends_with('_')
+        && !name.contains("__")
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Parameters are expected to be named after the type they identify, like `{user_id}`.
+fn is_typed_param(param: &str) -> bool {
+    is_snake_case(param) && param.len() > "_id".len() && param.ends_with("_id")
+}
+
+fn is_client_error(code: &StatusCode) -> bool {
+    match code {
+        StatusCode::Fixed(code) => (400..500).contains(code),
+        StatusCode::Prefix(prefix) => *prefix == 4,
+        StatusCode::Default => false,
+    }
+}
+
+pub enum LintConfigError {
+    Unreadable(ReadError),
+    InvalidSection,
+    UnknownRule(String),
+    InvalidLevel(String),
+    InvalidOption(String),
+}
+
+impl LintConfigError {
+    fn default_fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            LintConfigError::Unreadable(err) => write!(f, "Cannot read {}: {}", CONFIG_FILE, err),
+            LintConfigError::InvalidSection => {
+                write!(f, "`lint` section of {} should be a hashmap.", CONFIG_FILE)
+            }
+            LintConfigError::UnknownRule(name) => write!(f, "Unknown lint rule `{}`.", name),
+            LintConfigError::InvalidLevel(name) => write!(
+                f,
+                "Level of lint rule `{}` should be one of `off`, `warn` or `error`.",
+                name
+            ),
+            LintConfigError::InvalidOption(name) => {
+                write!(f, "Options of lint rule `{}` are invalid.", name)
+            }
+        }
+    }
+}
+
+impl Error for LintConfigError {}
+
+impl Display for LintConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+impl Debug for LintConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lint::{LintConfig, LintLevel, LintRule, Linter, CONFIG_FILE};
+    use crate::test_utils::{TempSpec, MAIN};
+
+    const TYPES: &str = "
+user:
+  id: str
+  displayName: str
+  tags: array
+  settings:
+    flags:
+      beta: bool
+legacy:
+  _allow: unused_types
+  id: str
+draft:
+  id: str
+";
+
+    const INTERFACES: &str = "
+declarations:
+  - path: users/{id}
+    method: get
+    response:
+      200: user
+      404:
+  - path: users
+    method: get
+    _allow: [error_responses]
+    response: array[user]
+";
+
+    const CONFIG: &str = "
+lint:
+  snake_case_fields: error
+  path_param_names: off
+  max_nesting:
+    max: 2
+";
+
+    #[test]
+    fn test_lint() {
+        let spec = TempSpec::new(
+            "lint",
+            &[
+                ("main.yaml", MAIN),
+                ("types.yml", TYPES),
+                ("interfaces.yml", INTERFACES),
+                (CONFIG_FILE, CONFIG),
+            ],
+        );
+        let schema = spec.parse();
+        let config = LintConfig::load(spec.path()).unwrap();
+        let linter = Linter {
+            schema: &schema,
+            config: &config,
+        };
+
+        let diagnostics: Vec<_> = linter
+            .lint()
+            .into_iter()
+            .map(|diagnostic| (diagnostic.rule, diagnostic.level, diagnostic.subject))
+            .collect();
+
+        let subject = |val: &str| val.to_string();
+        assert_eq!(
+            vec![
+                (
+                    LintRule::SnakeCaseFields,
+                    LintLevel::Error,
+                    subject("type types.user")
+                ),
+                (
+                    LintRule::UntypedContainers,
+                    LintLevel::Warn,
+                    subject("type types.user")
+                ),
+                (
+                    LintRule::MaxNesting,
+                    LintLevel::Warn,
+                    subject("type types.user")
+                ),
+                (
+                    LintRule::UnusedTypes,
+                    LintLevel::Warn,
+                    subject("type types.draft")
+                ),
+            ],
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn test_config() {
+        let config = yaml_rust::YamlLoader::load_from_str("lint:\n  unknown_rule: warn").unwrap();
+        assert!(LintConfig::from_yaml(&config[0]["lint"]).is_err());
+        let config = yaml_rust::YamlLoader::load_from_str("lint:\n  unused_types: loud").unwrap();
+        assert!(LintConfig::from_yaml(&config[0]["lint"]).is_err());
+    }
+}
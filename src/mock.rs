@@ -0,0 +1,518 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use yaml_rust::yaml::Hash;
+use yaml_rust::Yaml;
+
+use crate::sample::{to_json, SampleGenerator};
+use crate::schema::{ApiSpec, HttpMethod, Schema, StatusCode};
+use crate::traffic::{response_decl, TrafficChecker, TrafficIssueKind};
+
+/// Header picking the response status code, like `404`, `4xx` or `default`.
+pub const STATUS_HEADER: &str = "x-mock-status";
+/// Query parameter doing the same as the header, for requests made from a browser's address bar.
+pub const STATUS_PARAMETER: &str = "mock_status";
+/// Larger request bodies are rejected with 413 without being read.
+pub const MAX_BODY_SIZE: usize = 1024 * 1024;
+/// A client which stops sending doesn't block the connections queued after it.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Request received by the mock server.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MockRequest {
+    pub method: String,
+    /// Path with query, as in the request line.
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+/// Serves declared interfaces with generated data. Requests are routed and their query and body validated
+/// the same way as recorded traffic, responses are generated from the type declared for the status code.
+pub struct MockServer<'a> {
+    pub schema: &'a Schema,
+}
+
+impl<'a> MockServer<'a> {
+    /// Listens on localhost, handling one connection at a time.
+    pub fn serve(&self, port: u16) -> Result<(), MockError> {
+        let listener = TcpListener::bind(("127.0.0.1", port)).map_err(MockError::Unavailable)?;
+        for stream in listener.incoming() {
+            // A failed connection doesn't affect the next ones.
+            let _ = stream.and_then(|stream| self.handle(stream));
+        }
+        Ok(())
+    }
+
+    fn handle(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(&stream);
+        let Some((mut request, length)) = read_request_head(&mut reader)? else {
+            return Ok(());
+        };
+        if length > MAX_BODY_SIZE {
+            let message = format!("Request body is larger than {} bytes.", MAX_BODY_SIZE);
+            return write_response(&stream, &request, &error(413, vec![message]));
+        }
+        request.body = read_body(&mut reader, length)?;
+        let response = self.respond(&request);
+        write_response(&stream, &request, &response)
+    }
+
+    pub fn respond(&self, request: &MockRequest) -> MockResponse {
+        let (path, query) = request
+            .target
+            .split_once('?')
+            .unwrap_or((&request.target, ""));
+        let mut requested_status = request
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(STATUS_HEADER))
+            .map(|(_, value)| value.trim().to_string());
+        let mut parameters = Vec::new();
+        for (name, value) in query_pairs(query) {
+            if name == STATUS_PARAMETER {
+                requested_status.get_or_insert(value);
+            } else {
+                parameters.push((name, value));
+            }
+        }
+        let har_request = har_request(request, path, &parameters);
+        let checker = TrafficChecker {
+            schema: self.schema,
+        };
+        let mut entry = Hash::new();
+        entry.insert(Yaml::String("request".to_string()), har_request.clone());
+        let issues = checker.check_entry(0, &Yaml::Hash(entry));
+        let is_undocumented = issues
+            .iter()
+            .any(|issue| issue.kind == TrafficIssueKind::UndocumentedEndpoint);
+        if is_undocumented && request.method.eq_ignore_ascii_case("options") {
+            return preflight(request);
+        }
+        let summary = format!("{} {}", request.method.to_uppercase(), path);
+        if is_undocumented {
+            return error(
+                404,
+                vec![format!("No interface is declared for {}.", summary)],
+            );
+        }
+        if !issues.is_empty() {
+            return error(400, issues.iter().map(|val| val.kind.to_string()).collect());
+        }
+        let Some(api) = checker.find_api(&request.method.to_uppercase(), path, &har_request) else {
+            return error(
+                404,
+                vec![format!("No interface is declared for {}.", summary)],
+            );
+        };
+        let Some(status) = status_code(&api, requested_status.as_deref()) else {
+            let requested = requested_status.unwrap_or_default();
+            return error(
+                400,
+                vec![format!(
+                    "Status code {} is not declared for {}.",
+                    requested, summary
+                )],
+            );
+        };
+        let has_content = api.method != HttpMethod::Head && !matches!(status, 204 | 304);
+        let body = match response_decl(&api, status) {
+            Some(Some(data_type_decl)) if has_content => {
+                // The same request gets the same data.
+                let mut generator = SampleGenerator::new(self.schema, seed(&summary, query));
+                Some(to_json(&generator.sample_data_type_decl(data_type_decl)))
+            }
+            _ => None,
+        };
+        MockResponse {
+            status,
+            headers: Vec::new(),
+            body,
+        }
+    }
+}
+
+/// Status code to respond with: the requested one, or the first declared success.
+/// Requested classes, like `4xx`, pick the first code declared in them.
+fn status_code(api: &ApiSpec, requested: Option<&str>) -> Option<u16> {
+    let declared: Vec<&StatusCode> = api.responses.iter().flat_map(|val| val.keys()).collect();
+    let mut fixed: Vec<u16> = declared
+        .iter()
+        .filter_map(|val| match val {
+            StatusCode::Fixed(code) => Some(*code),
+            _ => None,
+        })
+        .collect();
+    fixed.sort();
+    let in_class = |class: u16| {
+        let fixed = fixed.iter().find(|code| *code / 100 == class).copied();
+        fixed.or_else(|| {
+            declared
+                .contains(&&StatusCode::Prefix(class))
+                .then_some(class * 100)
+        })
+    };
+    match requested {
+        None => match &api.inferred_status_code {
+            Some(StatusCode::Fixed(code)) => Some(*code),
+            _ => in_class(2).or(fixed.first().copied()).or(Some(200)),
+        },
+        Some("default") => declared.contains(&&StatusCode::Default).then_some(500),
+        Some(requested) => match requested.to_lowercase().strip_suffix("xx") {
+            Some(class) => in_class(class.parse().ok()?),
+            None => {
+                let code = requested.parse().ok()?;
+                response_decl(api, code).map(|_| code)
+            }
+        },
+    }
+}
+
+/// Request in the shape of a HAR entry, checked by the traffic checker.
+fn har_request(request: &MockRequest, path: &str, parameters: &[(String, String)]) -> Yaml {
+    let pairs = |pairs: &[(String, String)]| {
+        let items = pairs
+            .iter()
+            .map(|(name, value)| {
+                let mut item = Hash::new();
+                item.insert(Yaml::String("name".to_string()), Yaml::String(name.clone()));
+                item.insert(
+                    Yaml::String("value".to_string()),
+                    Yaml::String(value.clone()),
+                );
+                Yaml::Hash(item)
+            })
+            .collect();
+        Yaml::Array(items)
+    };
+    let mime_type = request
+        .headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        .map_or("", |(_, value)| value.as_str());
+    let mut post_data = Hash::new();
+    post_data.insert(
+        Yaml::String("mimeType".to_string()),
+        Yaml::String(mime_type.to_string()),
+    );
+    post_data.insert(
+        Yaml::String("text".to_string()),
+        Yaml::String(request.body.clone()),
+    );
+    let mut result = Hash::new();
+    let fields = [
+        ("method", Yaml::String(request.method.clone())),
+        ("url", Yaml::String(path.to_string())),
+        ("headers", pairs(&request.headers)),
+        ("queryString", pairs(parameters)),
+        ("postData", Yaml::Hash(post_data)),
+    ];
+    for (key, value) in fields {
+        result.insert(Yaml::String(key.to_string()), value);
+    }
+    Yaml::Hash(result)
+}
+
+/// Decoded `name=value` pairs of a query string.
+fn query_pairs(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|val| !val.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decoded(name), percent_decoded(value))
+        })
+        .collect()
+}
+
+fn percent_decoded(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut result = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = value.get(i + 1..i + 3).filter(|_| bytes[i] == b'%');
+        match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+            Some(byte) => {
+                result.push(byte);
+                i += 3;
+            }
+            None => {
+                result.push(if bytes[i] == b'+' { b' ' } else { bytes[i] });
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&result).to_string()
+}
+
+/// FNV-1a of the request, seeding generated data.
+fn seed(summary: &str, query: &str) -> u64 {
+    summary
+        .bytes()
+        .chain(query.bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+fn error(status: u16, messages: Vec<String>) -> MockResponse {
+    let messages = messages.into_iter().map(Yaml::String).collect();
+    let mut body = Hash::new();
+    body.insert(Yaml::String("errors".to_string()), Yaml::Array(messages));
+    MockResponse {
+        status,
+        headers: Vec::new(),
+        body: Some(to_json(&Yaml::Hash(body))),
+    }
+}
+
+/// Answers CORS preflight requests of browsers, for interfaces which don't declare `options` themselves.
+fn preflight(request: &MockRequest) -> MockResponse {
+    let requested_headers = request
+        .headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("access-control-request-headers"))
+        .map_or("*".to_string(), |(_, value)| value.clone());
+    MockResponse {
+        status: 204,
+        headers: vec![
+            ("Access-Control-Allow-Methods".to_string(), "*".to_string()),
+            (
+                "Access-Control-Allow-Headers".to_string(),
+                requested_headers,
+            ),
+        ],
+        body: None,
+    }
+}
+
+/// Reads request line and headers, returned with the body length given in `Content-Length`.
+fn read_request_head(reader: &mut impl BufRead) -> io::Result<Option<(MockRequest, usize)>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Ok(None);
+    };
+    let (method, target) = (method.to_string(), target.to_string());
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    let length = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or(0);
+    let request = MockRequest {
+        method,
+        target,
+        headers,
+        body: String::new(),
+    };
+    Ok(Some((request, length)))
+}
+
+fn read_body(reader: &mut impl BufRead, length: usize) -> io::Result<String> {
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(String::from_utf8_lossy(&body).to_string())
+}
+
+fn write_response(
+    mut stream: &TcpStream,
+    request: &MockRequest,
+    response: &MockResponse,
+) -> io::Result<()> {
+    let body = response.body.as_deref().unwrap_or_default();
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\n",
+        response.status,
+        reason(response.status)
+    );
+    let mut headers = vec![
+        ("Access-Control-Allow-Origin", "*".to_string()),
+        ("Connection", "close".to_string()),
+        ("Content-Length", body.len().to_string()),
+    ];
+    if response.body.is_some() {
+        headers.push(("Content-Type", "application/json".to_string()));
+    }
+    headers.extend(
+        response
+            .headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone())),
+    );
+    for (name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    if !request.method.eq_ignore_ascii_case("head") {
+        stream.write_all(body.as_bytes())?;
+    }
+    stream.flush()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        413 => "Content Too Large",
+        422 => "Unprocessable Content",
+        500 => "Internal Server Error",
+        _ => "",
+    }
+}
+
+pub enum MockError {
+    Unavailable(io::Error),
+}
+
+impl MockError {
+    fn default_fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            MockError::Unavailable(err) => write!(f, "Cannot start mock server: {}", err),
+        }
+    }
+}
+
+impl Error for MockError {}
+
+impl Display for MockError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+impl Debug for MockError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+
+    use crate::mock::{MockRequest, MockServer, MAX_BODY_SIZE};
+    use crate::test_utils::TempSpec;
+    use crate::validation::InstanceValidator;
+
+    fn request(method: &str, target: &str, headers: &[(&str, &str)], body: &str) -> MockRequest {
+        MockRequest {
+            method: method.to_string(),
+            target: target.to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_respond() {
+        let schema = TempSpec::with_main(
+            "mock",
+            "news_entry:\n  id: uuid\n  title: str\n  link: url?\n",
+            "declarations:\n  - path: news\n    method: get\n    query:\n      page: int?\n    response: array[news_entry]\n  - path: news\n    method: post\n    body:\n      title: str\n    response:\n      201: news_entry\n      4xx:\n        reason: str\n      409:\n        reason: str\n",
+        )
+        .parse();
+        let server = MockServer { schema: &schema };
+        let validator = InstanceValidator { schema: &schema };
+
+        let response = server.respond(&request("GET", "/api/news?page=2", &[], ""));
+        assert_eq!(200, response.status);
+        let body = response.body.unwrap();
+        assert!(body.starts_with("[{\"id\":"));
+        let news = validator.validate_json("news_entry", &body[1..body.find('}').unwrap() + 1]);
+        assert!(news.is_ok());
+
+        let json = [("Content-Type", "application/json")];
+        let response = server.respond(&request("POST", "/news", &json, "{\"title\": \"Hi\"}"));
+        assert_eq!(201, response.status);
+        let escaped = r#"{"title": "a\/b \ud83d\ude00"}"#;
+        let response = server.respond(&request("POST", "/news", &json, escaped));
+        assert_eq!(201, response.status);
+        let response = server.respond(&request(
+            "POST",
+            "/news?mock_status=4xx",
+            &json,
+            "{\"title\": \"Hi\"}",
+        ));
+        assert_eq!(409, response.status);
+        let headers = [
+            ("Content-Type", "application/json"),
+            ("X-Mock-Status", "500"),
+        ];
+        let response = server.respond(&request("POST", "/news", &headers, "{\"title\": \"Hi\"}"));
+        assert_eq!(400, response.status);
+
+        let response = server.respond(&request("POST", "/news", &json, "{\"name\": \"Hi\"}"));
+        assert_eq!(400, response.status);
+        assert!(response
+            .body
+            .unwrap()
+            .contains("request body doesn't match"));
+        let response = server.respond(&request("GET", "/news?page=first", &[], ""));
+        assert_eq!(400, response.status);
+        let response = server.respond(&request("DELETE", "/news", &[], ""));
+        assert_eq!(404, response.status);
+        let response = server.respond(&request("OPTIONS", "/news", &[], ""));
+        assert_eq!(204, response.status);
+    }
+
+    #[test]
+    fn test_body_size() {
+        let schema = TempSpec::with_main(
+            "mock-body",
+            "",
+            "declarations:\n  - path: news\n    method: post\n    body:\n      title: str\n",
+        )
+        .parse();
+        let server = MockServer { schema: &schema };
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        // Only the headers are sent, the declared body is never read.
+        let head = format!(
+            "POST /news HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY_SIZE + 1
+        );
+        client.write_all(head.as_bytes()).unwrap();
+
+        let (stream, _) = listener.accept().unwrap();
+        server.handle(stream).unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 413 Content Too Large\r\n"));
+    }
+}
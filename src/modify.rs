@@ -0,0 +1,1226 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use yaml_rust::YamlLoader;
+
+use crate::format::{split_comment, split_key};
+use crate::parser;
+use crate::parser::imports::relative_to;
+use crate::parser::packages::MODULES_DIR;
+use crate::parser::utils::ALLOW_KEY;
+use crate::schema::{InterfaceDecl, InterfaceSpec, Schema, TypeDecl};
+use crate::usage::{indentation, remove_blocks, types_section};
+
+const INDENT: usize = 2;
+const MAIN_FILE: &str = "main.yaml";
+
+/// Contents of a spec file after an edit.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FileEdit {
+    pub file: String,
+    pub contents: String,
+}
+
+/// Interface to declare. Query and body are given as property names with type strings,
+/// response as a type string.
+pub struct NewInterface {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub body: Vec<(String, String)>,
+    pub response: Option<String>,
+}
+
+/// Edits declarations in the files owning them, found through import tracking.
+/// Lines an edit doesn't touch are kept as they are, together with comments.
+pub struct SpecEditor<'a> {
+    pub schema: &'a Schema,
+    pub root_path: &'a str,
+}
+
+/// Lines of a declaration within its file.
+struct Block {
+    file: String,
+    lines: Vec<String>,
+    start: usize,
+    end: usize,
+    /// Indentation of keys within the declaration.
+    indent: usize,
+}
+
+impl<'a> SpecEditor<'a> {
+    /// Declares a type with properties given as names and type strings, by default next to the last declared type.
+    pub fn add_type(
+        &self,
+        name: &str,
+        properties: &[(String, String)],
+        file: Option<&str>,
+    ) -> Result<FileEdit, ModifyError> {
+        let file = match file {
+            Some(file) => self.spec_file(file)?,
+            None => self
+                .schema
+                .locations
+                .types
+                .iter()
+                .rev()
+                .find(|val| !val.file.starts_with(MODULES_DIR))
+                .map(|val| val.file.clone())
+                .ok_or(ModifyError::NoDeclarations("types".to_string()))?,
+        };
+        let contents = self.read(&file)?;
+        let mut lines = file_lines(&contents);
+        let line_refs: Vec<&str> = lines.iter().map(|val| val.as_str()).collect();
+        let (section, level) = types_section(&line_refs, self.is_module(&file));
+        if find_key(&lines, section.start, section.end, level, name).is_some() {
+            return Err(ModifyError::DuplicateDeclaration(name.to_string()));
+        }
+        let pad = " ".repeat(level);
+        let mut block = vec![format!("{}{}:", pad, name)];
+        for (property, data_type) in properties {
+            block.push(format!(
+                "{}{}{}: {}",
+                pad,
+                " ".repeat(INDENT),
+                property,
+                data_type
+            ));
+        }
+        // Files left empty by pruning hold an empty hash.
+        if contents.trim() == "{}" {
+            lines.clear();
+        }
+        let at = match lines.iter().position(|val| val.trim_end() == "types:") {
+            Some(section) if level > 0 => block_end(&lines, section),
+            _ => trimmed_len(&lines, lines.len()),
+        };
+        lines.splice(at..at, block);
+        Ok(FileEdit {
+            file,
+            contents: joined(&lines),
+        })
+    }
+
+    pub fn remove_type(&self, name: &str) -> Result<FileEdit, ModifyError> {
+        let block = self.type_block(name)?;
+        let is_module = self.is_module(&block.file);
+        let contents = remove_blocks(&joined(&block.lines), &[plain_name(name)], is_module);
+        Ok(FileEdit {
+            file: block.file,
+            contents,
+        })
+    }
+
+    /// Renames the type together with every reference to it: in other types, payloads, responses and
+    /// named responses, across all files of the spec. Returns the edit of each changed file.
+    pub fn rename_type(&self, name: &str, new_name: &str) -> Result<Vec<FileEdit>, ModifyError> {
+        if !is_type_name(new_name) {
+            return Err(ModifyError::InvalidName(new_name.to_string()));
+        }
+        let (index, type_decl) = self.type_index(name)?;
+        let owner = &self.schema.locations.types[index].file;
+        if owner.starts_with(MODULES_DIR) {
+            return Err(ModifyError::Vendored(owner.clone()));
+        }
+        let is_taken = self
+            .schema
+            .types
+            .iter()
+            .flatten()
+            .any(|val| val.name == new_name && val.namespace == type_decl.namespace);
+        if is_taken {
+            return Err(ModifyError::DuplicateDeclaration(new_name.to_string()));
+        }
+        let rename = Rename {
+            name: &type_decl.name,
+            namespace: type_decl.namespace.as_deref(),
+            new_name,
+            namespaces: self
+                .schema
+                .types
+                .iter()
+                .flatten()
+                .filter(|val| val.name == type_decl.name)
+                .filter_map(|val| val.namespace.as_deref())
+                .collect(),
+        };
+        let type_files: Vec<&str> = self
+            .schema
+            .locations
+            .types
+            .iter()
+            .map(|val| val.file.as_str())
+            .collect();
+        let mut edits = Vec::new();
+        for file in self.spec_files() {
+            let contents = self.read(&file)?;
+            let kind = if file == MAIN_FILE {
+                FileKind::Main
+            } else if self.is_module(&file) {
+                FileKind::Module
+            } else if type_files.contains(&file.as_str()) {
+                FileKind::Types
+            } else {
+                FileKind::Interfaces
+            };
+            let namespace = self
+                .schema
+                .imports
+                .namespaces
+                .get(&file)
+                .map(|val| val.as_str());
+            let renamed = rename.apply_to(&contents, kind, &file == owner, namespace);
+            let renamed = rename.renamed_selections(&renamed, &file, owner);
+            if renamed != contents {
+                edits.push(FileEdit {
+                    file,
+                    contents: renamed,
+                });
+            }
+        }
+        Ok(edits)
+    }
+
+    pub fn add_property(
+        &self,
+        type_name: &str,
+        name: &str,
+        data_type: &str,
+    ) -> Result<FileEdit, ModifyError> {
+        let mut block = self.type_block(type_name)?;
+        if find_key(&block.lines, block.start, block.end, block.indent, name).is_some() {
+            return Err(ModifyError::DuplicateDeclaration(format!(
+                "{}.{}",
+                type_name, name
+            )));
+        }
+        let line = format!("{}{}: {}", " ".repeat(block.indent), name, data_type);
+        block.lines.insert(block.end, line);
+        Ok(block.into_edit())
+    }
+
+    pub fn remove_property(&self, type_name: &str, name: &str) -> Result<FileEdit, ModifyError> {
+        let mut block = self.type_block(type_name)?;
+        let Some(start) = find_key(&block.lines, block.start, block.end, block.indent, name) else {
+            return Err(ModifyError::UnknownProperty(
+                type_name.to_string(),
+                name.to_string(),
+            ));
+        };
+        let end = block_end(&block.lines, start);
+        block.lines.drain(start..end);
+        Ok(block.into_edit())
+    }
+
+    /// Declares an interface after the last one of the file, by default of the file declaring the last interface.
+    pub fn add_interface(
+        &self,
+        interface: &NewInterface,
+        file: Option<&str>,
+    ) -> Result<FileEdit, ModifyError> {
+        let subject = format!("{} {}", interface.method.to_uppercase(), interface.path);
+        if self
+            .find_interface(&interface.method, &interface.path)
+            .is_some()
+        {
+            return Err(ModifyError::DuplicateDeclaration(subject));
+        }
+        let file = match file {
+            Some(file) => self.spec_file(file)?,
+            None => self
+                .schema
+                .locations
+                .interfaces
+                .iter()
+                .rev()
+                .find(|val| !val.file.starts_with(MODULES_DIR))
+                .map(|val| val.file.clone())
+                .ok_or(ModifyError::NoDeclarations("interfaces".to_string()))?,
+        };
+        let contents = self.read(&file)?;
+        let mut lines = file_lines(&contents);
+        let items = interface_items(&lines);
+        let Some((last, indent)) = items.last() else {
+            return Err(ModifyError::NoDeclarations(file));
+        };
+        let at = block_end(&lines, *last);
+        let pad = " ".repeat(indentation(&lines[*last]));
+        let content_pad = " ".repeat(*indent);
+        let mut block = vec![
+            format!("{}- path: {}", pad, interface.path),
+            format!("{}method: {}", content_pad, interface.method),
+        ];
+        let payloads = [("query", &interface.query), ("body", &interface.body)];
+        for (key, properties) in payloads.into_iter().filter(|(_, val)| !val.is_empty()) {
+            block.push(format!("{}{}:", content_pad, key));
+            for (name, data_type) in properties {
+                block.push(format!(
+                    "{}{}{}: {}",
+                    content_pad,
+                    " ".repeat(INDENT),
+                    name,
+                    data_type
+                ));
+            }
+        }
+        if let Some(response) = &interface.response {
+            block.push(format!("{}response: {}", content_pad, response));
+        }
+        lines.splice(at..at, block);
+        Ok(FileEdit {
+            file,
+            contents: joined(&lines),
+        })
+    }
+
+    pub fn remove_interface(&self, method: &str, path: &str) -> Result<FileEdit, ModifyError> {
+        let mut block = self.interface_block(method, path)?;
+        block.lines.drain(block.start..block.end);
+        Ok(block.into_edit())
+    }
+
+    /// Changes the key of a declared response. An inferred code is made explicit,
+    /// moving the response under the new code.
+    pub fn change_response_code(
+        &self,
+        method: &str,
+        path: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<FileEdit, ModifyError> {
+        let decl = self.find_interface(method, path).ok_or_else(|| {
+            ModifyError::UnknownInterface(format!("{} {}", method.to_uppercase(), path))
+        })?;
+        let InterfaceSpec::Api(api) = &decl.spec;
+        let unknown_code = || ModifyError::UnknownResponseCode(from.to_string());
+        let mut block = self.interface_block(method, path)?;
+        let response = find_key(
+            &block.lines,
+            block.start,
+            block.end,
+            block.indent,
+            "response",
+        );
+        let Some(inferred) = &api.inferred_status_code else {
+            let response = response.ok_or_else(unknown_code)?;
+            let end = block_end(&block.lines, response);
+            let child = child_indentation(&block.lines, response, end);
+            let line =
+                find_key(&block.lines, response + 1, end, child, from).ok_or_else(unknown_code)?;
+            let rest = block.lines[line][child..]
+                .split_once(':')
+                .map_or(String::new(), |(_, rest)| rest.to_string());
+            block.lines[line] = format!("{}{}:{}", " ".repeat(child), to, rest);
+            return Ok(block.into_edit());
+        };
+        if inferred.as_key() != from {
+            return Err(unknown_code());
+        }
+        let pad = " ".repeat(block.indent);
+        let code_pad = " ".repeat(block.indent + INDENT);
+        let Some(response) = response else {
+            let lines = [format!("{}response:", pad), format!("{}{}:", code_pad, to)];
+            block.lines.splice(block.end..block.end, lines);
+            return Ok(block.into_edit());
+        };
+        let value = split_value(&block.lines[response]).1.to_string();
+        if !value.is_empty() {
+            block.lines[response] = format!("{}response:", pad);
+            block
+                .lines
+                .insert(response + 1, format!("{}{}: {}", code_pad, to, value));
+            return Ok(block.into_edit());
+        }
+        // Inline response declaration moves one level deeper, under the code.
+        let end = block_end(&block.lines, response);
+        let shift = child_indentation(&block.lines, response, end) - block.indent;
+        for line in &mut block.lines[response + 1..end] {
+            if !line.trim().is_empty() {
+                *line = format!("{}{}", " ".repeat(shift), line);
+            }
+        }
+        let code_line = format!("{}{}:", " ".repeat(block.indent + shift), to);
+        block.lines.insert(response + 1, code_line);
+        Ok(block.into_edit())
+    }
+
+    /// Writes the edits and parses the spec again. Previous contents of all files are restored if the spec
+    /// doesn't parse anymore, or the edits introduced new declaration errors.
+    pub fn apply(&self, edits: &[FileEdit]) -> Result<Schema, ModifyError> {
+        let mut originals = Vec::new();
+        for edit in edits {
+            originals.push(FileEdit {
+                file: edit.file.clone(),
+                contents: self.read(&edit.file)?,
+            });
+        }
+        self.write(edits)?;
+        let new_errors = match parser::parse(self.root_path) {
+            Ok(schema) => {
+                let mut known = declaration_errors(self.schema);
+                let new_errors: Vec<String> = declaration_errors(&schema)
+                    .into_iter()
+                    .filter(|err| match known.iter().position(|val| val == err) {
+                        Some(index) => {
+                            known.remove(index);
+                            false
+                        }
+                        None => true,
+                    })
+                    .collect();
+                if new_errors.is_empty() {
+                    return Ok(schema);
+                }
+                new_errors
+            }
+            Err(err) => vec![err.to_string()],
+        };
+        self.write(&originals)?;
+        Err(ModifyError::Invalid(new_errors))
+    }
+
+    fn write(&self, edits: &[FileEdit]) -> Result<(), ModifyError> {
+        for edit in edits {
+            fs::write(Path::new(self.root_path).join(&edit.file), &edit.contents)
+                .map_err(|err| ModifyError::Unreadable(edit.file.clone(), err))?;
+        }
+        Ok(())
+    }
+
+    /// Root document and imported files, except vendored packages.
+    fn spec_files(&self) -> Vec<String> {
+        let mut files: Vec<String> = self
+            .schema
+            .imports
+            .edges
+            .values()
+            .flatten()
+            .filter(|val| !val.starts_with(MODULES_DIR))
+            .cloned()
+            .collect();
+        files.push(MAIN_FILE.to_string());
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    fn read(&self, file: &str) -> Result<String, ModifyError> {
+        fs::read_to_string(Path::new(self.root_path).join(file))
+            .map_err(|err| ModifyError::Unreadable(file.to_string(), err))
+    }
+
+    fn is_module(&self, file: &str) -> bool {
+        self.schema.imports.modules.contains(file)
+    }
+
+    /// File imported by the spec which may be edited.
+    fn spec_file(&self, file: &str) -> Result<String, ModifyError> {
+        let file = file.trim_start_matches("./");
+        if file.starts_with(MODULES_DIR) {
+            return Err(ModifyError::Vendored(file.to_string()));
+        }
+        let is_imported = self
+            .schema
+            .imports
+            .edges
+            .values()
+            .flatten()
+            .any(|val| val == file);
+        if !is_imported {
+            return Err(ModifyError::UnknownFile(file.to_string()));
+        }
+        Ok(file.to_string())
+    }
+
+    fn type_index(&self, name: &str) -> Result<(usize, &TypeDecl), ModifyError> {
+        let found: Vec<(usize, &TypeDecl)> = self
+            .schema
+            .types
+            .iter()
+            .enumerate()
+            .filter_map(|(index, val)| Some((index, val.as_ref().ok()?)))
+            .filter(|(_, val)| match name.contains('.') {
+                true => val.qualified_name() == name,
+                false => val.name == name,
+            })
+            .collect();
+        match found.as_slice() {
+            [] => Err(ModifyError::UnknownType(name.to_string())),
+            [found] => Ok(*found),
+            _ => Err(ModifyError::AmbiguousType(
+                found.iter().map(|(_, val)| val.qualified_name()).collect(),
+            )),
+        }
+    }
+
+    fn type_block(&self, name: &str) -> Result<Block, ModifyError> {
+        let (index, _) = self.type_index(name)?;
+        let file = self.schema.locations.types[index].file.clone();
+        if file.starts_with(MODULES_DIR) {
+            return Err(ModifyError::Vendored(file));
+        }
+        let lines = file_lines(&self.read(&file)?);
+        let line_refs: Vec<&str> = lines.iter().map(|val| val.as_str()).collect();
+        let (section, level) = types_section(&line_refs, self.is_module(&file));
+        let start = find_key(&lines, section.start, section.end, level, plain_name(name))
+            .ok_or_else(|| ModifyError::UnknownType(name.to_string()))?;
+        if !split_value(&lines[start]).1.is_empty() {
+            return Err(ModifyError::NotAnObject(name.to_string()));
+        }
+        let end = block_end(&lines, start);
+        let indent = child_indentation(&lines, start, end);
+        Ok(Block {
+            file,
+            lines,
+            start,
+            end,
+            indent,
+        })
+    }
+
+    fn find_interface(&self, method: &str, path: &str) -> Option<&InterfaceDecl> {
+        self.schema
+            .interfaces
+            .iter()
+            .flatten()
+            .find(|decl| is_interface(decl, method, path))
+    }
+
+    fn interface_block(&self, method: &str, path: &str) -> Result<Block, ModifyError> {
+        let unknown =
+            || ModifyError::UnknownInterface(format!("{} {}", method.to_uppercase(), path));
+        let index = self
+            .schema
+            .interfaces
+            .iter()
+            .position(|val| {
+                val.as_ref()
+                    .is_ok_and(|decl| is_interface(decl, method, path))
+            })
+            .ok_or_else(unknown)?;
+        let file = self.schema.locations.interfaces[index].file.clone();
+        if file.starts_with(MODULES_DIR) {
+            return Err(ModifyError::Vendored(file));
+        }
+        let lines = file_lines(&self.read(&file)?);
+        let (start, indent) = interface_items(&lines)
+            .into_iter()
+            .find(|(start, indent)| {
+                let end = block_end(&lines, *start);
+                let value = |key| {
+                    find_key(&lines, *start, end, *indent, key)
+                        .map(|line| unquoted(split_value(&lines[line]).1))
+                };
+                value("method").is_some_and(|val| val.eq_ignore_ascii_case(method))
+                    && value("path")
+                        .is_some_and(|val| val.trim_matches('/') == path.trim_matches('/'))
+            })
+            .ok_or_else(unknown)?;
+        let end = block_end(&lines, start);
+        Ok(Block {
+            file,
+            lines,
+            start,
+            end,
+            indent,
+        })
+    }
+}
+
+impl Block {
+    fn into_edit(self) -> FileEdit {
+        FileEdit {
+            file: self.file,
+            contents: joined(&self.lines),
+        }
+    }
+}
+
+/// Role of a file in the spec, which tells where type strings are written in it.
+#[derive(Clone, Copy, PartialEq)]
+enum FileKind {
+    Main,
+    Module,
+    Types,
+    Interfaces,
+}
+
+struct Rename<'a> {
+    name: &'a str,
+    namespace: Option<&'a str>,
+    new_name: &'a str,
+    /// Namespaces declaring a type with the same name.
+    namespaces: Vec<&'a str>,
+}
+
+impl<'a> Rename<'a> {
+    /// Renames references in type strings of the file, and the declaration if the file owns it.
+    /// Only values within declarations are touched, so paths, tags and property names stay as they are.
+    fn apply_to(
+        &self,
+        contents: &str,
+        kind: FileKind,
+        is_owner: bool,
+        from: Option<&str>,
+    ) -> String {
+        let mut ancestors: Vec<(usize, String)> = Vec::new();
+        let mut result: String = contents
+            .lines()
+            .map(|line| {
+                format!(
+                    "{}\n",
+                    self.renamed_line(line, &mut ancestors, kind, is_owner, from)
+                )
+            })
+            .collect();
+        if !contents.ends_with('\n') {
+            result.pop();
+        }
+        result
+    }
+
+    fn renamed_line(
+        &self,
+        line: &str,
+        ancestors: &mut Vec<(usize, String)>,
+        kind: FileKind,
+        is_owner: bool,
+        from: Option<&str>,
+    ) -> String {
+        let mut offset = indentation(line);
+        let (mut text, _) = split_comment(line.trim_start());
+        if text.is_empty() {
+            return line.to_string();
+        }
+        // List items open a level of their own, with content after the dash.
+        while text == "-" || text.starts_with("- ") {
+            ancestors.retain(|(indent, _)| *indent < offset);
+            ancestors.push((offset, "-".to_string()));
+            let rest = text[1..].trim_start();
+            offset += text.len() - rest.len();
+            text = rest;
+        }
+        ancestors.retain(|(indent, _)| *indent < offset);
+        let Some((key, value)) = split_key(text) else {
+            return line.to_string();
+        };
+        let keys: Vec<&str> = ancestors.iter().map(|(_, key)| key.as_str()).collect();
+        let mut renamed = line.to_string();
+        if !value.is_empty() && is_type_position(kind, &keys, &key) {
+            let value_end = offset + text.len();
+            let value_start = value_end - value.len();
+            renamed = format!(
+                "{}{}{}",
+                &line[..value_start],
+                self.renamed_references(&value, from),
+                &line[value_end..]
+            );
+        }
+        let is_declaration = match kind {
+            FileKind::Types => keys.is_empty(),
+            FileKind::Module => keys == ["types"],
+            FileKind::Main | FileKind::Interfaces => false,
+        };
+        if is_owner && is_declaration && key == self.name {
+            renamed = format!(
+                "{}{}{}",
+                &renamed[..offset],
+                self.new_name,
+                &renamed[offset + key.len()..]
+            );
+        }
+        ancestors.push((offset, key));
+        renamed
+    }
+
+    /// Renames the type in `only` lists of selective imports taking it from the owner file.
+    fn renamed_selections(&self, contents: &str, importer: &str, owner: &str) -> String {
+        let takes_owner = |file: &str| relative_to(importer, unquoted(file)) == owner;
+        let mut lines = file_lines(contents);
+        for index in 0..lines.len() {
+            let (text, _) = split_comment(lines[index].trim_start());
+            // A flow hash keeps the whole import on its line, like `- {file: common.yml, only: [money]}`.
+            if text.contains('{') {
+                lines[index] = self.renamed_flow_selections(&lines[index], &takes_owner);
+                continue;
+            }
+            let indent = content_indentation(&lines[index]);
+            if split_value(&lines[index]).0 != "only"
+                || !item_file(&lines, index, indent).is_some_and(takes_owner)
+            {
+                continue;
+            }
+            let colon = lines[index].find(':').unwrap_or_default() + 1;
+            lines[index] = self.renamed_content(&lines[index], colon);
+            // Names listed one per line, the dash may be as deep as the key.
+            for line in lines.iter_mut().skip(index + 1) {
+                let content = line.trim_start();
+                if content.is_empty() || content.starts_with('#') {
+                    continue;
+                }
+                let line_indent = indentation(line);
+                if line_indent < indent || (line_indent == indent && !content.starts_with("- ")) {
+                    break;
+                }
+                *line = self.renamed_content(line, line_indent);
+            }
+        }
+        if lines == file_lines(contents) {
+            return contents.to_string();
+        }
+        let mut result = joined(&lines);
+        if !contents.ends_with('\n') {
+            result.pop();
+        }
+        result
+    }
+
+    fn renamed_flow_selections(&self, line: &str, takes_owner: &impl Fn(&str) -> bool) -> String {
+        let mut result = String::new();
+        let mut rest = line;
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            let (hash, after) = rest[start..].split_at(len + 1);
+            result.push_str(&rest[..start]);
+            let documents = YamlLoader::load_from_str(hash).unwrap_or_default();
+            let import = documents.first();
+            let is_selection = import.is_some_and(|val| {
+                val["file"].as_str().is_some_and(takes_owner) && val["only"].as_vec().is_some()
+            });
+            match hash.find("only") {
+                Some(index) if is_selection => result.push_str(&format!(
+                    "{}{}",
+                    &hash[..index],
+                    self.renamed_names(&hash[index..])
+                )),
+                _ => result.push_str(hash),
+            }
+            rest = after;
+        }
+        result.push_str(rest);
+        result
+    }
+
+    /// Renames names in the line from the position on, leaving its trailing comment as it is.
+    fn renamed_content(&self, line: &str, start: usize) -> String {
+        let (content, _) = split_comment(&line[start..]);
+        let end = start + content.len();
+        format!(
+            "{}{}{}",
+            &line[..start],
+            self.renamed_names(&line[start..end]),
+            &line[end..]
+        )
+    }
+
+    /// Replaces whole plain names, as declarations are named in `only` lists.
+    fn renamed_names(&self, text: &str) -> String {
+        let mut result = String::new();
+        let mut token = String::new();
+        for val in text.chars().map(Some).chain([None]) {
+            if let Some(val) = val.filter(|val| val.is_alphanumeric() || *val == '_' || *val == '.')
+            {
+                token.push(val);
+                continue;
+            }
+            result.push_str(if token == self.name {
+                self.new_name
+            } else {
+                &token
+            });
+            token.clear();
+            result.extend(val);
+        }
+        result
+    }
+
+    /// Whether a plain reference made from the namespace resolves to the renamed type.
+    fn resolves_plain(&self, from: Option<&str>) -> bool {
+        match self.namespace {
+            None => self.namespaces.is_empty(),
+            Some(namespace) => {
+                from == Some(namespace)
+                    || (from.map_or(true, |val| !self.namespaces.contains(&val))
+                        && self.namespaces == [namespace])
+            }
+        }
+    }
+
+    /// Replaces whole names only, so renaming `user` leaves `user_profile` intact.
+    fn renamed_references(&self, value: &str, from: Option<&str>) -> String {
+        // Named responses are referenced with `$`, they aren't types.
+        if value.starts_with('$') {
+            return value.to_string();
+        }
+        let qualified = self.namespace.map(|val| format!("{}.{}", val, self.name));
+        let is_plain = self.resolves_plain(from);
+        let mut result = String::new();
+        let mut token = String::new();
+        for val in value.chars().map(Some).chain([None]) {
+            if let Some(val) = val.filter(|val| val.is_alphanumeric() || *val == '_' || *val == '.')
+            {
+                token.push(val);
+                continue;
+            }
+            if token == self.name && is_plain {
+                result.push_str(self.new_name);
+            } else if qualified.as_ref() == Some(&token) {
+                result.push_str(&format!(
+                    "{}.{}",
+                    self.namespace.unwrap_or_default(),
+                    self.new_name
+                ));
+            } else {
+                result.push_str(&token);
+            }
+            token.clear();
+            result.extend(val);
+        }
+        result
+    }
+}
+
+/// Whether the value of the key is a type string: within type declarations, payloads and responses of
+/// interfaces, or named responses of the root document.
+fn is_type_position(kind: FileKind, keys: &[&str], key: &str) -> bool {
+    let mut path = keys.iter().copied().chain([key]);
+    if path
+        .clone()
+        .any(|val| ["_import", "_namespace", ALLOW_KEY].contains(&val))
+    {
+        return false;
+    }
+    match kind {
+        FileKind::Main => matches!(keys.first(), Some(&"responses" | &"default_responses")),
+        FileKind::Types => true,
+        FileKind::Module if keys.first() == Some(&"types") => true,
+        FileKind::Module | FileKind::Interfaces => {
+            keys.contains(&"-") && path.any(|val| matches!(val, "query" | "body" | "response"))
+        }
+    }
+}
+
+/// Indentation of the line's content, after the dash of a list item.
+fn content_indentation(line: &str) -> usize {
+    match line.trim_start().strip_prefix("- ") {
+        Some(rest) => line.len() - rest.trim_start().len(),
+        None => indentation(line),
+    }
+}
+
+/// Value of `file` in the block hash having the key on the line, for imports written over several lines.
+fn item_file(lines: &[String], index: usize, indent: usize) -> Option<&str> {
+    let is_key = |line: &str| {
+        let content = line.trim_start();
+        !content.is_empty() && !content.starts_with('#') && content_indentation(line) == indent
+    };
+    // Keys before it up to the dash opening the hash, then keys after it.
+    for line in lines[..=index].iter().rev() {
+        if is_key(line) {
+            let (key, value) = split_value(line);
+            if key == "file" {
+                return Some(unquoted(value));
+            }
+            if line.trim_start().starts_with("- ") {
+                break;
+            }
+        } else if !line.trim().is_empty() && indentation(line) < indent {
+            break;
+        }
+    }
+    for line in &lines[index + 1..] {
+        let content = line.trim_start();
+        if content.is_empty() || content.starts_with('#') || indentation(line) > indent {
+            continue;
+        }
+        if indentation(line) < indent {
+            break;
+        }
+        let (key, value) = split_value(line);
+        if is_key(line) && key == "file" {
+            return Some(unquoted(value));
+        }
+    }
+    None
+}
+
+fn is_interface(decl: &InterfaceDecl, method: &str, path: &str) -> bool {
+    let InterfaceSpec::Api(api) = &decl.spec;
+    api.method.to_string().eq_ignore_ascii_case(method)
+        && decl.ident.trim_matches('/') == path.trim_matches('/')
+}
+
+/// Type names are plain identifiers, a namespace or container syntax would be read as part of the reference.
+fn is_type_name(name: &str) -> bool {
+    name.starts_with(|val: char| val.is_alphabetic() || val == '_')
+        && name.chars().all(|val| val.is_alphanumeric() || val == '_')
+}
+
+fn plain_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+fn file_lines(contents: &str) -> Vec<String> {
+    contents.lines().map(|val| val.to_string()).collect()
+}
+
+fn joined(lines: &[String]) -> String {
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// Key and value of a `key: value` line, a list item's dash is skipped. Quotes and trailing comment are dropped.
+fn split_value(line: &str) -> (&str, &str) {
+    let text = line.trim_start();
+    let text = text.strip_prefix("- ").unwrap_or(text).trim_start();
+    let Some((key, value)) = text.split_once(':') else {
+        return ("", "");
+    };
+    let value = match value.find(" #") {
+        Some(index) => &value[..index],
+        None => value,
+    };
+    (unquoted(key), value.trim())
+}
+
+fn unquoted(value: &str) -> &str {
+    value.trim().trim_matches(['"', '\''])
+}
+
+/// Line of the key within lines `start..end`, at the given indentation.
+/// The first line of a list item is indented by its content, after the dash.
+fn find_key(lines: &[String], start: usize, end: usize, indent: usize, key: &str) -> Option<usize> {
+    (start..end).find(|index| {
+        let line = &lines[*index];
+        let content = line.trim_start();
+        let content_indent = match content.strip_prefix("- ") {
+            Some(rest) => line.len() - rest.trim_start().len(),
+            None => indentation(line),
+        };
+        content_indent == indent && !content.starts_with('#') && split_value(line).0 == key
+    })
+}
+
+/// End of the block started by the line: lines indented deeper, without blank lines after it.
+fn block_end(lines: &[String], start: usize) -> usize {
+    let indent = indentation(&lines[start]);
+    let mut end = start + 1;
+    while end < lines.len() && (lines[end].trim().is_empty() || indentation(&lines[end]) > indent) {
+        end += 1;
+    }
+    trimmed_len(lines, end).max(start + 1)
+}
+
+fn trimmed_len(lines: &[String], mut end: usize) -> usize {
+    while end > 0 && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+    end
+}
+
+/// Indentation of lines nested in the block, one level deeper if it has none yet.
+fn child_indentation(lines: &[String], start: usize, end: usize) -> usize {
+    lines[start + 1..end]
+        .iter()
+        .find(|val| !val.trim().is_empty() && !val.trim_start().starts_with('#'))
+        .map_or(indentation(&lines[start]) + INDENT, |val| indentation(val))
+}
+
+/// List items declaring interfaces, with indentation of their keys.
+fn interface_items(lines: &[String]) -> Vec<(usize, usize)> {
+    let items: Vec<(usize, usize)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let rest = line.trim_start().strip_prefix("- ")?;
+            let (key, _) = split_value(line);
+            matches!(key, "path" | "method").then(|| (index, line.len() - rest.trim_start().len()))
+        })
+        .collect();
+    let Some(indent) = items
+        .iter()
+        .map(|(index, _)| indentation(&lines[*index]))
+        .min()
+    else {
+        return items;
+    };
+    items
+        .into_iter()
+        .filter(|(index, _)| indentation(&lines[*index]) == indent)
+        .collect()
+}
+
+/// Errors of individual declarations, which a spec may have before an edit.
+fn declaration_errors(schema: &Schema) -> Vec<String> {
+    let mut result = Vec::new();
+    for type_decl in &schema.types {
+        match type_decl {
+            Ok(type_decl) => {
+                for property in &type_decl.property_decls {
+                    if let Err(err) = &property.data_type_decl {
+                        result.push(format!("{}.{}: {}", type_decl.name, property.name, err));
+                    }
+                }
+            }
+            Err(err) => result.push(err.to_string()),
+        }
+    }
+    for decl in schema
+        .interfaces
+        .iter()
+        .filter_map(|val| val.as_ref().err())
+    {
+        result.push(decl.to_string());
+    }
+    result
+}
+
+pub enum ModifyError {
+    UnknownType(String),
+    AmbiguousType(Vec<String>),
+    UnknownProperty(String, String),
+    UnknownInterface(String),
+    UnknownResponseCode(String),
+    UnknownFile(String),
+    DuplicateDeclaration(String),
+    InvalidName(String),
+    NotAnObject(String),
+    Vendored(String),
+    NoDeclarations(String),
+    Unreadable(String, io::Error),
+    Invalid(Vec<String>),
+}
+
+impl ModifyError {
+    fn default_fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            ModifyError::UnknownType(name) => write!(f, "Type {} is not declared.", name),
+            ModifyError::AmbiguousType(candidates) => write!(
+                f,
+                "Several types are declared under this name, qualify it: {}.",
+                candidates.join(", ")
+            ),
+            ModifyError::UnknownProperty(type_name, name) => {
+                write!(f, "Type {} has no property {}.", type_name, name)
+            }
+            ModifyError::UnknownInterface(interface) => {
+                write!(f, "Interface {} is not declared.", interface)
+            }
+            ModifyError::UnknownResponseCode(code) => {
+                write!(f, "Interface has no response with code {}.", code)
+            }
+            ModifyError::UnknownFile(file) => {
+                write!(f, "File {} is not imported by the spec.", file)
+            }
+            ModifyError::DuplicateDeclaration(name) => write!(f, "{} is already declared.", name),
+            ModifyError::InvalidName(name) => write!(
+                f,
+                "{} is not a valid type name, only letters, digits and '_' are allowed.",
+                name
+            ),
+            ModifyError::NotAnObject(name) => {
+                write!(f, "Type {} is an alias, it has no properties.", name)
+            }
+            ModifyError::Vendored(file) => {
+                write!(
+                    f,
+                    "File {} belongs to a vendored package and cannot be edited.",
+                    file
+                )
+            }
+            ModifyError::NoDeclarations(place) => {
+                write!(
+                    f,
+                    "No declarations in {} to add the new one next to.",
+                    place
+                )
+            }
+            ModifyError::Unreadable(file, err) => write!(f, "Cannot edit {}: {}", file, err),
+            ModifyError::Invalid(errors) => {
+                write!(
+                    f,
+                    "Edit was reverted, it breaks the spec:\n{}",
+                    errors.join("\n")
+                )
+            }
+        }
+    }
+}
+
+impl Error for ModifyError {}
+
+impl Display for ModifyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+impl Debug for ModifyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modify::{ModifyError, NewInterface, SpecEditor};
+    use crate::test_utils::{TempSpec, MAIN};
+
+    #[test]
+    fn test_edits() {
+        let spec = TempSpec::with_main(
+            "modify",
+            "# Accounts\nuser:\n  id: str # Opaque\n\npost:\n  id: str\n",
+            "declarations:\n  - path: users\n    method: get\n    response:\n      items: array[user]\n",
+        );
+        let schema = spec.parse();
+        let editor = SpecEditor {
+            schema: &schema,
+            root_path: spec.path(),
+        };
+
+        let edit = editor.add_property("user", "name", "str?").unwrap();
+        assert_eq!(
+            "# Accounts\nuser:\n  id: str # Opaque\n  name: str?\n\npost:\n  id: str\n",
+            edit.contents
+        );
+        let edit = editor
+            .change_response_code("get", "users", "200", "206")
+            .unwrap();
+        assert_eq!(
+            "declarations:\n  - path: users\n    method: get\n    response:\n      206:\n        items: array[user]\n",
+            edit.contents
+        );
+        let interface = NewInterface {
+            method: "post".to_string(),
+            path: "posts".to_string(),
+            query: Vec::new(),
+            body: vec![("title".to_string(), "str".to_string())],
+            response: Some("post".to_string()),
+        };
+        let edit = editor.add_interface(&interface, None).unwrap();
+        editor.apply(&[edit]).unwrap();
+        assert_eq!(
+            "declarations:\n  - path: users\n    method: get\n    response:\n      items: array[user]\n  - path: posts\n    method: post\n    body:\n      title: str\n    response: post\n",
+            spec.read("interfaces.yml")
+        );
+
+        let edit = editor.remove_type("post").unwrap();
+        let Err(ModifyError::Invalid(errors)) = editor.apply(&[edit]) else {
+            panic!("removing a referenced type must be reverted");
+        };
+        assert_eq!(
+            vec!["Unknown type post in interface (#0) output status code 201 in property at 0"],
+            errors
+        );
+        assert_eq!(
+            "# Accounts\nuser:\n  id: str # Opaque\n\npost:\n  id: str\n",
+            spec.read("types.yml")
+        );
+    }
+
+    #[test]
+    fn test_rename_type() {
+        let main = format!(
+            "{}responses:\n  owner: user\ndefault_responses:\n  4xx: user\n",
+            MAIN
+        );
+        let spec = TempSpec::new(
+            "rename",
+            &[
+                ("main.yaml", &main),
+                (
+                    "types.yml",
+                    "user:\n  id: str\n  user: str\nuser_profile:\n  owner: user # Author\n  friends: dict[str, array[user]]?\n",
+                ),
+                (
+                    "interfaces.yml",
+                    "declarations:\n  - path: users/{user}\n    method: get\n    tags: [user]\n    response:\n      200: user\n      404: $owner\n  - path: profiles\n    method: post\n    body:\n      user: user_profile\n",
+                ),
+            ],
+        );
+        let schema = spec.parse();
+        let editor = SpecEditor {
+            schema: &schema,
+            root_path: spec.path(),
+        };
+
+        for name in ["user account", "shared.account", "array[account]", ""] {
+            assert!(matches!(
+                editor.rename_type("user", name),
+                Err(ModifyError::InvalidName(_))
+            ));
+        }
+        let edits = editor.rename_type("user", "account").unwrap();
+        editor.apply(&edits).unwrap();
+
+        let files: Vec<&str> = edits.iter().map(|val| val.file.as_str()).collect();
+        assert_eq!(vec!["interfaces.yml", "main.yaml", "types.yml"], files);
+        assert_eq!(
+            "account:\n  id: str\n  user: str\nuser_profile:\n  owner: account # Author\n  friends: dict[str, array[account]]?\n",
+            spec.read("types.yml")
+        );
+        assert_eq!(
+            "declarations:\n  - path: users/{user}\n    method: get\n    tags: [user]\n    response:\n      200: account\n      404: $owner\n  - path: profiles\n    method: post\n    body:\n      user: user_profile\n",
+            spec.read("interfaces.yml")
+        );
+        assert!(spec
+            .read("main.yaml")
+            .ends_with("responses:\n  owner: account\ndefault_responses:\n  4xx: account\n"));
+    }
+
+    #[test]
+    fn test_rename_selected_type() {
+        let main = MAIN.replace(
+            "_import: types.yml",
+            "_import:\n    - file: common.yml\n      only: [money] # Prices\n    - billing.yml\n    - shipping.yml",
+        );
+        let spec = TempSpec::new(
+            "rename-selected",
+            &[
+                ("main.yaml", &main),
+                ("common.yml", "money:\n  amount: int\naddress:\n  city: str\n"),
+                (
+                    "billing.yml",
+                    "_import: {file: common.yml, only: [money, address]}\ninvoice:\n  total: money\n",
+                ),
+                (
+                    "shipping.yml",
+                    "_import:\n  - only:\n      - address\n      - money # Totals\n    file: common.yml\nparcel:\n  value: money\n",
+                ),
+                (
+                    "interfaces.yml",
+                    "declarations:\n  - path: prices\n    method: get\n    response: money\n",
+                ),
+            ],
+        );
+        let schema = spec.parse();
+        let editor = SpecEditor {
+            schema: &schema,
+            root_path: spec.path(),
+        };
+
+        let edits = editor.rename_type("money", "cash").unwrap();
+        editor.apply(&edits).unwrap();
+
+        assert!(spec
+            .read("main.yaml")
+            .contains("    - file: common.yml\n      only: [cash] # Prices\n"));
+        assert_eq!(
+            "_import: {file: common.yml, only: [cash, address]}\ninvoice:\n  total: cash\n",
+            spec.read("billing.yml")
+        );
+        assert_eq!(
+            "_import:\n  - only:\n      - address\n      - cash # Totals\n    file: common.yml\nparcel:\n  value: cash\n",
+            spec.read("shipping.yml")
+        );
+        assert_eq!(
+            "cash:\n  amount: int\naddress:\n  city: str\n",
+            spec.read("common.yml")
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use yaml_rust::{Yaml};
 use std::fmt::{Debug, Display, Formatter};
 use crate::parser::utils::{as_str_or, YamlHash};
-use crate::schema::{Host, Hosts};
+use crate::schema::{Host, HostVariable, Hosts, InterfaceDecl, InterfaceDeclError};
 
 pub struct HostsParser<'a> {
     pub main: &'a Yaml
@@ -22,18 +22,129 @@ impl<'a> HostsParser<'a> {
 
     fn host(&self, key: &Yaml, value: &Yaml) -> Result<Host, HostsError> {
         let env = as_str_or(key, HostsError::MissingEnv)?;
+        if let Yaml::String(address) = value {
+            return Ok(Host::new(env, address.to_string()));
+        }
+        if value.as_hash().is_none() {
+            return Err(HostsError::MissingAddress(env));
+        }
         let address = as_str_or(
-            value,
+            &value["address"],
             HostsError::MissingAddress(String::from(&env))
         )?;
-        Ok(Host { env, address })
+        let mut host = Host::new(env, address);
+        host.description = self.optional_str(&value["description"], &host.env)?;
+        host.variables = self.variables(&value["variables"], &host.env)?;
+        host.interfaces = self.optional_list(&value["interfaces"], &host.env, |item| {
+            item.as_str().map(|val| val.to_string())
+        })?;
+        host.versions = self.optional_list(&value["versions"], &host.env, |item| {
+            item.as_i64()
+                .filter(|val| *val > 0)
+                .and_then(|val| u32::try_from(val).ok())
+        })?;
+        for name in host.template_variables() {
+            if !host.variables.iter().any(|variable| variable.name == name) {
+                return Err(HostsError::UndeclaredVariable(host.env, name));
+            }
+        }
+        Ok(host)
+    }
+
+    fn variables(&self, raw: &Yaml, env: &str) -> Result<Vec<HostVariable>, HostsError> {
+        let raw_variables = match raw {
+            Yaml::BadValue => return Ok(Vec::new()),
+            Yaml::Hash(val) => val,
+            _ => return Err(HostsError::InvalidHost(env.to_string())),
+        };
+        let mut variables = Vec::new();
+        for (key, value) in raw_variables {
+            let name = as_str_or(key, HostsError::InvalidHost(env.to_string()))?;
+            let invalid_variable = || HostsError::InvalidVariable(env.to_string(), name.clone());
+            let variable = match value {
+                Yaml::Null => HostVariable { name: name.clone(), values: None, default: None, description: None },
+                Yaml::Hash(_) => {
+                    let values = self.optional_list(&value["values"], env, |item| match item {
+                        Yaml::String(val) => Some(val.to_string()),
+                        Yaml::Integer(val) => Some(val.to_string()),
+                        _ => None,
+                    }).map_err(|_| invalid_variable())?;
+                    let default = match &value["default"] {
+                        Yaml::BadValue => None,
+                        Yaml::String(val) => Some(val.to_string()),
+                        Yaml::Integer(val) => Some(val.to_string()),
+                        _ => return Err(invalid_variable()),
+                    };
+                    let description = self.optional_str(&value["description"], env)?;
+                    HostVariable { name: name.clone(), values, default, description }
+                },
+                _ => return Err(invalid_variable()),
+            };
+            if let (Some(values), Some(default)) = (&variable.values, &variable.default) {
+                if !values.contains(default) {
+                    return Err(HostsError::InvalidDefault(env.to_string(), name));
+                }
+            }
+            variables.push(variable);
+        }
+        Ok(variables)
+    }
+
+    fn optional_str(&self, raw: &Yaml, env: &str) -> Result<Option<String>, HostsError> {
+        match raw {
+            Yaml::BadValue => Ok(None),
+            Yaml::String(val) => Ok(Some(val.to_string())),
+            _ => Err(HostsError::InvalidHost(env.to_string())),
+        }
+    }
+
+    fn optional_list<T>(
+        &self,
+        raw: &Yaml,
+        env: &str,
+        item: impl Fn(&Yaml) -> Option<T>,
+    ) -> Result<Option<Vec<T>>, HostsError> {
+        match raw {
+            Yaml::BadValue => Ok(None),
+            Yaml::Array(raw_items) => raw_items
+                .iter()
+                .map(|raw_item| item(raw_item).ok_or(HostsError::InvalidHost(env.to_string())))
+                .collect::<Result<Vec<T>, _>>()
+                .map(Some),
+            _ => Err(HostsError::InvalidHost(env.to_string())),
+        }
+    }
+}
+
+/// Checks that every interface deployed to a host is declared.
+pub fn check_deployments(
+    hosts: &Hosts,
+    interfaces: &[Result<InterfaceDecl, InterfaceDeclError>]
+) -> Result<(), HostsError> {
+    for host in hosts {
+        let Some(deployed) = &host.interfaces else {
+            continue;
+        };
+        for ident in deployed {
+            let is_declared = interfaces.iter()
+                .any(|interface| matches!(interface, Ok(val) if &val.ident == ident));
+            if !is_declared {
+                return Err(HostsError::UnknownInterface(host.env.clone(), ident.clone()));
+            }
+        }
     }
+    Ok(())
 }
 
 pub enum HostsError {
     NotFound,
     MissingEnv,
-    MissingAddress(String)
+    MissingAddress(String),
+    InvalidHost(String),
+    InvalidVariable(String, String),
+    InvalidDefault(String, String),
+    UndeclaredVariable(String, String),
+    UnknownInterface(String, String)
 }
 
 impl HostsError {
@@ -45,6 +156,16 @@ impl HostsError {
                 write!(f, "Missing environment key for host."),
             HostsError::MissingAddress(env) =>
                 write!(f, "Missing address value for environment: {}", env),
+            HostsError::InvalidHost(env) =>
+                write!(f, "Invalid host declaration for environment: {}", env),
+            HostsError::InvalidVariable(env, name) =>
+                write!(f, "Invalid variable '{}' for environment: {}", name, env),
+            HostsError::InvalidDefault(env, name) =>
+                write!(f, "Default of variable '{}' is not among its values for environment: {}", name, env),
+            HostsError::UndeclaredVariable(env, name) =>
+                write!(f, "Variable '{}' is used in address, but not declared for environment: {}", name, env),
+            HostsError::UnknownInterface(env, ident) =>
+                write!(f, "Interface '{}' deployed to environment {} is not declared", ident, env),
         }
     }
 }
@@ -63,3 +184,85 @@ impl Debug for HostsError {
         self.default_fmt(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use yaml_rust::YamlLoader;
+    use crate::parser::hosts::{HostsError, HostsParser};
+    use crate::schema::{Host, HostVariable};
+
+    fn parse(source: &str) -> Result<Vec<Host>, HostsError> {
+        let yaml = YamlLoader::load_from_str(source).unwrap();
+        HostsParser { main: &yaml[0] }.parse()
+    }
+
+    #[test]
+    fn test_plain_address() {
+        let hosts = parse("hosts:\n  prod: https://api.acme.com").unwrap();
+        assert_eq!(hosts, vec![Host::new("prod".to_string(), "https://api.acme.com".to_string())]);
+    }
+
+    #[test]
+    fn test_templated_address() {
+        let source = "
+hosts:
+  regional:
+    address: https://{region}.api.acme.com/{base}
+    description: Regional deployments
+    variables:
+      region:
+        values: [eu, us]
+        default: eu
+      base:
+        default: v1
+    interfaces: [news]
+    versions: [2]
+";
+        let hosts = parse(source).unwrap();
+        let host = &hosts[0];
+        assert_eq!(host.description, Some("Regional deployments".to_string()));
+        assert_eq!(host.variables[0], HostVariable {
+            name: "region".to_string(),
+            values: Some(vec!["eu".to_string(), "us".to_string()]),
+            default: Some("eu".to_string()),
+            description: None,
+        });
+        assert_eq!(host.template_variables(), vec!["region".to_string(), "base".to_string()]);
+        assert_eq!(host.resolve_address(&HashMap::new()), Ok("https://eu.api.acme.com/v1".to_string()));
+        let values = HashMap::from([("region".to_string(), "us".to_string())]);
+        assert_eq!(host.resolve_address(&values), Ok("https://us.api.acme.com/v1".to_string()));
+        let values = HashMap::from([("region".to_string(), "jp".to_string())]);
+        assert_eq!(host.resolve_address(&values), Err("region".to_string()));
+        assert!(host.deploys("news", Some(2)));
+        assert!(!host.deploys("news", Some(1)));
+        assert!(!host.deploys("users", None));
+    }
+
+    #[test]
+    fn test_undeclared_variable() {
+        let source = "
+hosts:
+  regional:
+    address: https://{region}.api.acme.com
+";
+        assert!(matches!(
+            parse(source),
+            Err(HostsError::UndeclaredVariable(env, name)) if env == "regional" && name == "region"
+        ));
+    }
+
+    #[test]
+    fn test_default_not_allowed() {
+        let source = "
+hosts:
+  regional:
+    address: https://{region}.api.acme.com
+    variables:
+      region:
+        values: [eu, us]
+        default: jp
+";
+        assert!(matches!(parse(source), Err(HostsError::InvalidDefault(_, name)) if name == "region"));
+    }
+}
@@ -1,67 +1,310 @@
+use crate::parser::packages::{MODULES_DIR, PACKAGE_MANIFEST};
 use crate::parser::utils::{as_str_or, read_yaml, YamlHash};
-use crate::schema::ImportError;
+use crate::schema::{ImportError, ImportGraph, Package};
+use std::collections::HashSet;
 use std::fmt::{Debug, Display, Formatter};
+use std::path::{Component, Path, PathBuf};
 use yaml_rust::Yaml;
 
 #[derive(Debug)]
 pub struct SourceImport {
-    pub key: String,
+    /// Path of the imported file, relative to the root document directory.
+    pub path: String,
+    /// Namespace of declarations in the file: its `_namespace` value, or the file name without extension.
+    pub namespace: String,
+    /// Names of declarations to take from the file, `None` if all of them.
+    pub only: Option<Vec<String>>,
     pub imported_source: Yaml,
 }
 
-pub fn detect(
-    source: &YamlHash,
-    parent_path: &str,
-) -> Vec<Result<Yaml, ImportError>> {
-    let import_key = Yaml::String("_import".to_string());
-    let is_import = source.contains_key(&import_key);
-    if !is_import {
-        return Vec::new();
-    }
-    let mut found_imports = Vec::new();
-    match &source[&import_key] {
-        Yaml::String(file_path) => {
-            let file_path = parent_path.to_string() + "/" + &file_path;
-            match read_yaml(&file_path) {
-                Ok(imported_yaml) => {
-                    for e in imported_yaml {
-                        found_imports.push(Ok(e));
+#[derive(Debug)]
+pub struct FailedImport {
+    /// Path of the file containing failed import statement.
+    pub importer: String,
+    pub error: ImportError,
+}
+
+pub type ImportResults = Vec<Result<SourceImport, FailedImport>>;
+
+/// Loads imports transitively, resolving each path relative to the file importing it.
+/// Every file is loaded once per resolver, in order of first import.
+pub struct ImportResolver<'a> {
+    pub root_path: &'a str,
+    /// Vendored packages, which can be imported from as `@package/path`.
+    pub packages: &'a [Package],
+    pub graph: &'a mut ImportGraph,
+    pub loaded: HashSet<String>,
+}
+
+impl<'a> ImportResolver<'a> {
+    pub fn new(root_path: &'a str, packages: &'a [Package], graph: &'a mut ImportGraph) -> Self {
+        Self {
+            root_path,
+            packages,
+            graph,
+            loaded: HashSet::new(),
+        }
+    }
+
+    pub fn resolve(&mut self, source: &YamlHash, importer: &str) -> ImportResults {
+        let mut found_imports = Vec::new();
+        let mut chain = vec![importer.to_string()];
+        self.resolve_hash(source, &mut chain, &mut found_imports);
+        found_imports
+    }
+
+    fn resolve_hash(
+        &mut self,
+        source: &YamlHash,
+        chain: &mut Vec<String>,
+        found_imports: &mut ImportResults,
+    ) {
+        let import_key = Yaml::String("_import".to_string());
+        let Some(raw_import) = source.get(&import_key) else {
+            return;
+        };
+        let importer = chain.last().cloned().unwrap_or_default();
+        let file_paths = match raw_import {
+            Yaml::Array(file_paths) => file_paths.iter().map(import_spec).collect(),
+            other => vec![import_spec(other)],
+        };
+        for file_path in file_paths {
+            let expanded = file_path.and_then(|(file_path, only)| {
+                self.expand(&importer, &file_path)
+                    .map(|paths| (paths, only))
+            });
+            match expanded {
+                Ok((paths, only)) => {
+                    for path in paths {
+                        self.resolve_file(&path, &only, chain, found_imports);
                     }
                 }
-                Err(err) => found_imports.push(Err(ImportError::IOError(err))),
+                Err(error) => found_imports.push(Err(FailedImport {
+                    importer: importer.clone(),
+                    error,
+                })),
             }
         }
-        Yaml::Array(file_paths) => {
-            for file_path in file_paths {
-                match as_str_or(&file_path, ImportError::InvalidImportValue) {
-                    Ok(file_path) => {
-                        let file_path = parent_path.to_string() + "/" + &file_path;
-                        match read_yaml(&file_path) {
-                            Ok(imported_yaml) => {
-                                for e in imported_yaml {
-                                    found_imports.push(Ok(e));
-                                }
-                            }
-                            Err(err) => found_imports.push(Err(ImportError::IOError(err))),
-                        }
-                    }
-                    Err(err) => found_imports.push(Err(err)),
+    }
+
+    /// Expands import path into files it refers to. Patterns and directories match files in sorted
+    /// order, except the importing file itself.
+    fn expand(&self, importer: &str, file_path: &str) -> Result<Vec<String>, ImportError> {
+        let path = match file_path.strip_prefix('@') {
+            Some(package_path) => self.package_path(package_path)?,
+            None => relative_to(importer, file_path),
+        };
+        let root = Path::new(self.root_path);
+        let pattern = if file_path.contains(['*', '?', '[']) {
+            path.clone()
+        } else if root.join(&path).is_dir() {
+            format!("{}/**/*.y*ml", path.trim_end_matches('/'))
+        } else {
+            return Ok(vec![path]);
+        };
+        let full_pattern = root.join(&pattern);
+        let entries = glob::glob(&full_pattern.to_string_lossy())
+            .map_err(|_| ImportError::InvalidImportValue)?;
+        let mut paths: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.is_file())
+            .filter(|entry| {
+                matches!(
+                    entry.extension().and_then(|val| val.to_str()),
+                    Some("yml") | Some("yaml")
+                )
+            })
+            .filter_map(|entry| {
+                entry
+                    .strip_prefix(root)
+                    .ok()
+                    .map(|val| val.to_string_lossy().replace('\\', "/"))
+            })
+            .filter(|val| val != importer)
+            .collect();
+        if paths.is_empty() {
+            return Err(ImportError::NoMatches(file_path.to_string()));
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Path of a file in a vendored package, given as `package/path` relative to the package directory.
+    fn package_path(&self, package_path: &str) -> Result<String, ImportError> {
+        let (name, file_path) = package_path.split_once('/').unwrap_or((package_path, ""));
+        if !self.packages.iter().any(|package| package.name == name) {
+            return Err(ImportError::UnknownPackage(name.to_string()));
+        }
+        let directory = format!("{}/{}", MODULES_DIR, name);
+        let path = relative_to(&format!("{}/{}", directory, PACKAGE_MANIFEST), file_path);
+        // Compared by components, `isle_modules/common-evil` isn't inside `isle_modules/common`.
+        if !Path::new(&path).starts_with(&directory) {
+            return Err(ImportError::InvalidImportValue);
+        }
+        Ok(path)
+    }
+
+    fn resolve_file(
+        &mut self,
+        path: &str,
+        only: &Option<Vec<String>>,
+        chain: &mut Vec<String>,
+        found_imports: &mut ImportResults,
+    ) {
+        let importer = chain.last().cloned().unwrap_or_default();
+        self.graph.add_edge(&importer, path);
+        if chain.iter().any(|val| val == path) {
+            let mut cycle = chain.clone();
+            cycle.push(path.to_string());
+            found_imports.push(Err(FailedImport {
+                importer,
+                error: ImportError::Cycle(cycle),
+            }));
+            return;
+        }
+        if !self.loaded.insert(path.to_string()) {
+            for source in found_imports.iter_mut().flatten() {
+                if source.path == path {
+                    source.only = merged_selection(&source.only, only);
                 }
             }
+            return;
+        }
+        let imported_yaml = match read_yaml(&Path::new(self.root_path).join(path).to_string_lossy())
+        {
+            Ok(val) => val,
+            Err(err) => {
+                found_imports.push(Err(FailedImport {
+                    importer,
+                    error: ImportError::IOError(path.to_string(), err),
+                }));
+                return;
+            }
+        };
+        chain.push(path.to_string());
+        for e in imported_yaml {
+            let nested: Vec<YamlHash> = match &e {
+                Yaml::Hash(val) => vec![val.clone()],
+                Yaml::Array(items) => items
+                    .iter()
+                    .filter_map(|item| item.as_hash())
+                    .filter(|item| item.contains_key(&Yaml::String("_import".to_string())))
+                    .cloned()
+                    .collect(),
+                _ => Vec::new(),
+            };
+            let namespace = match &e["_namespace"] {
+                Yaml::String(val) => val.to_string(),
+                _ => package_name(path).unwrap_or_else(|| file_stem(path)),
+            };
+            self.graph
+                .namespaces
+                .insert(path.to_string(), namespace.clone());
+            found_imports.push(Ok(SourceImport {
+                path: path.to_string(),
+                namespace,
+                only: only.clone(),
+                imported_source: e,
+            }));
+            for hash in nested {
+                self.resolve_hash(&hash, chain, found_imports);
+            }
         }
-        _ => found_imports.push(Err(ImportError::InvalidImportValue)),
+        chain.pop();
     }
-    found_imports
+}
+
+/// Reads an import entry: either a path, or a hash with `file` and `only` list of declarations to take.
+fn import_spec(raw: &Yaml) -> Result<(String, Option<Vec<String>>), ImportError> {
+    match raw {
+        Yaml::String(file_path) => Ok((file_path.to_string(), None)),
+        Yaml::Hash(_) => {
+            let file_path = as_str_or(&raw["file"], ImportError::InvalidImportValue)?;
+            let only = match &raw["only"] {
+                Yaml::BadValue => None,
+                Yaml::Array(names) => Some(
+                    names
+                        .iter()
+                        .map(|name| as_str_or(name, ImportError::InvalidImportValue))
+                        .collect::<Result<Vec<_>, _>>()?,
+                ),
+                _ => return Err(ImportError::InvalidImportValue),
+            };
+            Ok((file_path, only))
+        }
+        _ => Err(ImportError::InvalidImportValue),
+    }
+}
+
+/// Selection of a file imported several times: everything any of the imports selects.
+fn merged_selection(lhs: &Option<Vec<String>>, rhs: &Option<Vec<String>>) -> Option<Vec<String>> {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => {
+            let mut merged = lhs.clone();
+            merged.extend(rhs.iter().filter(|name| !lhs.contains(name)).cloned());
+            Some(merged)
+        }
+        _ => None,
+    }
+}
+
+/// Name of the vendored package containing the file, if any.
+fn package_name(path: &str) -> Option<String> {
+    let mut components = path.split('/');
+    match (components.next(), components.next(), components.next()) {
+        (Some(MODULES_DIR), Some(name), Some(_)) => Some(name.to_string()),
+        _ => None,
+    }
+}
+
+fn file_stem(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .map(|val| val.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Joins import path with directory of importing file, normalizing `.` and `..` segments.
+pub(crate) fn relative_to(importer: &str, file_path: &str) -> String {
+    let directory = Path::new(importer).parent().unwrap_or(Path::new(""));
+    let mut normalized = PathBuf::new();
+    for component in directory.join(file_path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized.to_string_lossy().replace('\\', "/")
 }
 
 impl ImportError {
     fn default_fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         match self {
-            ImportError::IOError(err) => {
-                write!(f, "I/O error while loading imports: {}", err.to_string())
+            ImportError::IOError(path, err) => {
+                write!(f, "Failed to read imported file {}: {}", path, err)
             }
             ImportError::InvalidInputSource => write!(f, "Input source should be a hashmap"),
             ImportError::InvalidImportValue => write!(f, "Import statement should be string"),
+            ImportError::Cycle(chain) => write!(f, "Import cycle: {}", chain.join(" -> ")),
+            ImportError::NoMatches(pattern) => write!(f, "No files match import {}", pattern),
+            ImportError::UnknownPackage(name) => write!(
+                f,
+                "Package {} is not declared in 'packages' of the root document",
+                name
+            ),
+            ImportError::InvalidModule(path) => write!(
+                f,
+                "{} is not a module, which may only have `_import`, `_namespace`, `types` and `interfaces`",
+                path
+            ),
+            ImportError::UnknownSelection(path, name) => {
+                write!(f, "Imported `{}` is not declared in {}", name, path)
+            }
         }
     }
 }
@@ -79,3 +322,230 @@ impl Debug for ImportError {
         self.default_fmt(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use yaml_rust::YamlLoader;
+
+    use crate::parser::imports::{relative_to, ImportResolver};
+    use crate::schema::{ImportError, ImportGraph, Package};
+    use crate::test_utils::TempSpec;
+
+    fn write_files(name: &str, files: &[(&str, &str)]) -> TempSpec {
+        TempSpec::new(&format!("imports-{}", name), files)
+    }
+
+    #[test]
+    fn test_relative_to() {
+        assert_eq!("types.yml", relative_to("main.yaml", "types.yml"));
+        assert_eq!("types/user.yml", relative_to("types/all.yml", "user.yml"));
+        assert_eq!("common.yml", relative_to("types/all.yml", "../common.yml"));
+        assert_eq!(
+            "types/user.yml",
+            relative_to("main.yaml", "./types/user.yml")
+        );
+    }
+
+    #[test]
+    fn test_transitive_imports() {
+        let spec = write_files(
+            "transitive",
+            &[
+                (
+                    "types/all.yml",
+                    "_import: [user.yml, ../common.yml]\npost:\n  id: str\n",
+                ),
+                (
+                    "types/user.yml",
+                    "_import: ../common.yml\nuser:\n  id: str\n",
+                ),
+                ("common.yml", "money:\n  amount: int\n"),
+            ],
+        );
+        let main = YamlLoader::load_from_str("_import: types/all.yml").unwrap();
+        let mut graph = ImportGraph::default();
+        let mut resolver = ImportResolver::new(spec.path(), &[], &mut graph);
+
+        let imports = resolver.resolve(main[0].as_hash().unwrap(), "main.yaml");
+
+        let paths: Vec<_> = imports
+            .iter()
+            .map(|val| val.as_ref().unwrap().path.clone())
+            .collect();
+        assert_eq!(vec!["types/all.yml", "types/user.yml", "common.yml"], paths);
+        assert_eq!(
+            vec!["types/user.yml".to_string(), "common.yml".to_string()],
+            graph.edges["types/all.yml"]
+        );
+        assert_eq!(
+            vec!["common.yml".to_string()],
+            graph.edges["types/user.yml"]
+        );
+    }
+
+    #[test]
+    fn test_import_cycle() {
+        let spec = write_files(
+            "cycle",
+            &[
+                ("a.yml", "_import: b.yml\na:\n  id: str\n"),
+                ("b.yml", "_import: a.yml\nb:\n  id: str\n"),
+            ],
+        );
+        let main = YamlLoader::load_from_str("_import: a.yml").unwrap();
+        let mut graph = ImportGraph::default();
+        let mut resolver = ImportResolver::new(spec.path(), &[], &mut graph);
+
+        let imports = resolver.resolve(main[0].as_hash().unwrap(), "main.yaml");
+
+        assert_eq!(3, imports.len());
+        let failure = imports[2].as_ref().unwrap_err();
+        assert_eq!("b.yml", failure.importer);
+        assert_eq!(
+            ImportError::Cycle(vec![
+                "main.yaml".to_string(),
+                "a.yml".to_string(),
+                "b.yml".to_string(),
+                "a.yml".to_string()
+            ]),
+            failure.error
+        );
+    }
+
+    #[test]
+    fn test_pattern_and_directory_imports() {
+        let spec = write_files(
+            "patterns",
+            &[
+                ("types/all.yml", "_import: '*.yml'\n"),
+                ("types/user.yml", "user:\n  id: str\n"),
+                ("types/billing/invoice.yml", "invoice:\n  id: str\n"),
+                ("types/billing/money.yaml", "money:\n  amount: int\n"),
+                ("types/billing/notes.txt", "not an import"),
+                ("types/account.yml", "account:\n  id: str\n"),
+            ],
+        );
+        let main =
+            YamlLoader::load_from_str("_import: [types/all.yml, types/billing, types/**/*.yml]")
+                .unwrap();
+        let mut graph = ImportGraph::default();
+        let mut resolver = ImportResolver::new(spec.path(), &[], &mut graph);
+
+        let imports = resolver.resolve(main[0].as_hash().unwrap(), "main.yaml");
+
+        let paths: Vec<_> = imports
+            .iter()
+            .map(|val| val.as_ref().unwrap().path.clone())
+            .collect();
+        assert_eq!(
+            vec![
+                "types/all.yml",
+                "types/account.yml",
+                "types/user.yml",
+                "types/billing/invoice.yml",
+                "types/billing/money.yaml",
+            ],
+            paths
+        );
+    }
+
+    #[test]
+    fn test_pattern_without_matches() {
+        let spec = write_files("no-matches", &[("types/user.yml", "user:\n  id: str\n")]);
+        let main = YamlLoader::load_from_str("_import: models/*.yml").unwrap();
+        let mut graph = ImportGraph::default();
+        let mut resolver = ImportResolver::new(spec.path(), &[], &mut graph);
+
+        let imports = resolver.resolve(main[0].as_hash().unwrap(), "main.yaml");
+
+        assert_eq!(
+            ImportError::NoMatches("models/*.yml".to_string()),
+            imports[0].as_ref().unwrap_err().error
+        );
+    }
+
+    #[test]
+    fn test_selective_imports_and_namespaces() {
+        let spec = write_files(
+            "selective",
+            &[
+                (
+                    "billing.yml",
+                    "_namespace: payments\n_import: {file: common.yml, only: [money]}\n",
+                ),
+                (
+                    "shipping.yml",
+                    "_import:\n  - file: common.yml\n    only: [address]\n",
+                ),
+                (
+                    "common.yml",
+                    "money:\n  amount: int\naddress:\n  city: str\nphone:\n  number: str\n",
+                ),
+            ],
+        );
+        let main = YamlLoader::load_from_str("_import: [billing.yml, shipping.yml]").unwrap();
+        let mut graph = ImportGraph::default();
+        let mut resolver = ImportResolver::new(spec.path(), &[], &mut graph);
+
+        let imports = resolver.resolve(main[0].as_hash().unwrap(), "main.yaml");
+
+        let common = imports[1].as_ref().unwrap();
+        assert_eq!("common.yml", common.path);
+        assert_eq!("common", common.namespace);
+        assert_eq!(
+            Some(vec!["money".to_string(), "address".to_string()]),
+            common.only
+        );
+        assert_eq!("payments", imports[0].as_ref().unwrap().namespace);
+        assert_eq!("shipping", imports[2].as_ref().unwrap().namespace);
+    }
+
+    #[test]
+    fn test_package_imports() {
+        let spec = write_files(
+            "package",
+            &[
+                ("isle_modules/common/package.yml", "version: 1.0.0\n"),
+                (
+                    "isle_modules/common/money.yml",
+                    "_import: errors/error.yml\nmoney:\n  amount: int\n",
+                ),
+                (
+                    "isle_modules/common/errors/error.yml",
+                    "error:\n  reason: str\n",
+                ),
+                ("isle_modules/common-evil/x.yml", "x:\n  id: str\n"),
+            ],
+        );
+        let main = YamlLoader::load_from_str(
+            "_import: ['@common/money.yml', '@common/../../secrets.yml', '@common/../common-evil/x.yml', '@billing/x.yml']",
+        )
+        .unwrap();
+        let packages = vec![Package {
+            name: "common".to_string(),
+            version: "1.0.0".to_string(),
+            checksum: String::new(),
+        }];
+        let mut graph = ImportGraph::default();
+        let mut resolver = ImportResolver::new(spec.path(), &packages, &mut graph);
+
+        let imports = resolver.resolve(main[0].as_hash().unwrap(), "main.yaml");
+
+        let money = imports[0].as_ref().unwrap();
+        assert_eq!("isle_modules/common/money.yml", money.path);
+        assert_eq!("common", money.namespace);
+        let error = imports[1].as_ref().unwrap();
+        assert_eq!("isle_modules/common/errors/error.yml", error.path);
+        assert_eq!("common", error.namespace);
+        for index in [2, 3] {
+            assert_eq!(
+                ImportError::InvalidImportValue,
+                imports[index].as_ref().unwrap_err().error
+            );
+        }
+        assert_eq!(
+            ImportError::UnknownPackage("billing".to_string()),
+            imports[4].as_ref().unwrap_err().error
+        );
+    }
+}
@@ -3,204 +3,268 @@ use std::collections::HashMap;
 use yaml_rust::Yaml;
 
 use crate::schema::{
-    ApiSpec, HttpMethod, HttpPayload, HttpResponses, ImportError, InterfaceDecl,
-    InterfaceDeclError, InterfaceDeclResults, InterfaceSpec, StatusCode, TypeDecl, TypeDeclError,
-    TypeUsageMeta,
+    ApiSpec, DataTypeDecl, ExtensionMethods, HttpMethod, HttpPayload, HttpResponses, InterfaceDecl,
+    InterfaceDeclError, InterfaceDeclResults, InterfaceSpec, InterfaceVersions, Location,
+    PayloadKind, SchemaWarning, SharedResponses, StatusCode, StatusCodeInference, TypeUsageMeta,
 };
 
-use super::{imports::detect, types::{TypeDeclSource, TypeParser}, utils::YamlHash};
+use super::{
+    imports::ImportResults,
+    responses::{insert_response, shadowed_status_codes, status_code_from, ResponseParser},
+    types::{TypeDeclSource, TypeParser},
+    utils::{allowed_lints, YamlHash},
+};
 
 pub struct InterfacesParser<'a> {
-    pub parent_path: &'a str,
     pub types_usage: &'a mut HashMap<String, TypeUsageMeta>,
-    pub types: &'a Vec<Result<TypeDecl, TypeDeclError>>,
+    pub extension_methods: &'a ExtensionMethods,
+    pub status_code_inference: &'a StatusCodeInference,
+    pub shared_responses: &'a SharedResponses,
+    pub warnings: &'a mut Vec<SchemaWarning>,
+    pub locations: &'a mut Vec<Location>,
+    pub suppressions: &'a mut Vec<Vec<String>>,
 }
 
 impl<'a> InterfacesParser<'a> {
-    pub fn parse(&mut self, main: Yaml) -> Result<InterfaceDeclResults, InterfaceDeclError> {
-        let mut sources = Vec::new();
-        let inner: Option<&YamlHash> = main.as_hash();
-        let inner = inner.ok_or(InterfaceDeclError::ImportFailure(
-            ImportError::InvalidInputSource,
-        ))?;
-        let imports = detect(inner, self.parent_path);
-        for i in imports {
-            sources.push(i);
-        }
-        sources.insert(0, Ok(main));
+    pub fn parse(&mut self, sources: ImportResults) -> InterfaceDeclResults {
         let mut results = Vec::new();
         let mut interface_parser = InterfaceParser {
-            types_usage: &mut self.types_usage,
-            types: &self.types,
+            types_usage: self.types_usage,
+            extension_methods: self.extension_methods,
+            status_code_inference: self.status_code_inference,
+            shared_responses: self.shared_responses,
+            warnings: Vec::new(),
         };
         for source in sources {
             match source {
                 Ok(source) => {
-                    let raw = from_file(&source).unwrap();
-                    for item in raw {
-                        match item {
+                    let raw = from_file(&source.imported_source).unwrap_or_else(|_| {
+                        vec![Err(InterfaceDeclError::InvalidInterfaceDeclaration)]
+                    });
+                    for (index, item) in raw.into_iter().enumerate() {
+                        let (decl, allowed) = match item {
                             Ok(item) => {
                                 if item.contains_key(&key_from("_import")) {
                                     continue;
                                 }
-                                let decl = interface_parser.parse(&item);
-                                results.push(decl);
+                                (interface_parser.parse(&item), allowed_lints(&item))
                             }
-                            Err(err) => results.push(Err(err)),
-                        }
+                            Err(err) => (Err(err), Vec::new()),
+                        };
+                        self.suppressions.push(allowed);
+                        self.locations.push(Location {
+                            file: source.path.clone(),
+                            index,
+                        });
+                        results.push(decl);
                     }
                 }
-                Err(err) => results.push(Err(InterfaceDeclError::ImportFailure(err.clone()))),
+                Err(failure) => {
+                    self.locations.push(Location {
+                        file: failure.importer,
+                        index: 0,
+                    });
+                    self.suppressions.push(Vec::new());
+                    results.push(Err(InterfaceDeclError::ImportFailure(failure.error)))
+                }
             }
         }
-        Ok(results)
+        self.warnings.extend(interface_parser.warnings);
+        results
     }
 }
 
 struct InterfaceParser<'a> {
     types_usage: &'a mut HashMap<String, TypeUsageMeta>,
-    types: &'a Vec<Result<TypeDecl, TypeDeclError>>,
+    extension_methods: &'a ExtensionMethods,
+    status_code_inference: &'a StatusCodeInference,
+    shared_responses: &'a SharedResponses,
+    warnings: Vec<SchemaWarning>,
 }
 
 impl<'a> InterfaceParser<'a> {
     fn parse(&mut self, hash: &YamlHash) -> Result<InterfaceDecl, InterfaceDeclError> {
         let ident = get_ident(hash)?;
         let params = get_params(&ident)?;
-        let method = get_method(hash)?;
-        let payload = self.get_payload(&method, &hash)?;
-        let responses = self.get_response(&hash)?;
+        let method = get_method(hash, self.extension_methods)?;
+        let is_async = get_is_async(hash)?;
+        let payload = self.get_payload(&method, hash)?;
+        let (responses, inferred_status_code) =
+            self.get_response(&ident, &method, is_async, hash)?;
         let api_spec = ApiSpec {
             method,
             payload,
             responses,
+            inferred_status_code,
         };
+        let versions = self.get_versions(&ident, is_async, &api_spec, hash)?;
+        let operation_id = get_operation_id(hash, &api_spec.method, &ident)?;
+        let tags = get_tags(hash)?;
+        let group = get_group(hash)?;
         let spec = InterfaceSpec::Api(api_spec);
         let decl = InterfaceDecl {
             ident,
             params,
+            operation_id,
+            tags,
+            group,
             spec,
+            versions,
         };
         Ok(decl)
     }
 
-    fn get_response(&mut self, hash: &YamlHash) -> Result<HttpResponses, InterfaceDeclError> {
+    fn get_versions(
+        &mut self,
+        ident: &str,
+        is_async: bool,
+        api_spec: &ApiSpec,
+        hash: &YamlHash,
+    ) -> Result<InterfaceVersions, InterfaceDeclError> {
+        let listed = match hash.get(&key_from("versions")) {
+            None => None,
+            Some(Yaml::Array(raw)) if !raw.is_empty() => Some(
+                raw.iter()
+                    .map(version_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            Some(_) => return Err(InterfaceDeclError::InvalidVersions),
+        };
+        let since = hash.get(&key_from("since")).map(version_from).transpose()?;
+        let removed_in = hash
+            .get(&key_from("removed_in"))
+            .map(version_from)
+            .transpose()?;
+        if listed.is_some() && (since.is_some() || removed_in.is_some()) {
+            return Err(InterfaceDeclError::InvalidVersions);
+        }
+        if let (Some(since), Some(removed_in)) = (since, removed_in) {
+            if since >= removed_in {
+                return Err(InterfaceDeclError::InvalidVersions);
+            }
+        }
+        let mut versions = InterfaceVersions {
+            listed,
+            since,
+            removed_in,
+            overrides: HashMap::new(),
+        };
+        let raw_overrides = match hash.get(&key_from("overrides")) {
+            None => return Ok(versions),
+            Some(Yaml::Hash(val)) => val,
+            Some(_) => return Err(InterfaceDeclError::InvalidVersions),
+        };
+        let mut sorted_overrides = Vec::new();
+        for (key, value) in raw_overrides {
+            let version = version_from(key)?;
+            if !versions.contains(version) {
+                return Err(InterfaceDeclError::InvalidVersions);
+            }
+            let value = value.as_hash().ok_or(InterfaceDeclError::InvalidVersions)?;
+            sorted_overrides.push((version, value));
+        }
+        sorted_overrides.sort_by_key(|(version, _)| *version);
+        let mut api = api_spec.clone();
+        for (version, value) in sorted_overrides {
+            if value.contains_key(&key_from("query")) || value.contains_key(&key_from("body")) {
+                api.payload = self.get_payload(&api.method, value)?;
+            }
+            if value.contains_key(&key_from("response")) {
+                let method = api.method.clone();
+                (api.responses, api.inferred_status_code) =
+                    self.get_response(ident, &method, is_async, value)?;
+            }
+            versions.overrides.insert(version, api.clone());
+        }
+        Ok(versions)
+    }
+
+    fn get_response(
+        &mut self,
+        ident: &str,
+        method: &HttpMethod,
+        is_async: bool,
+        hash: &YamlHash,
+    ) -> Result<(HttpResponses, Option<StatusCode>), InterfaceDeclError> {
         let response_key = key_from("response");
         if !hash.contains_key(&response_key) {
-            return Ok(None);
+            return Ok((self.with_default_responses(None), None));
         }
         match &hash[&response_key] {
-            Yaml::Hash(val) => self.responses_from(val),
-            Yaml::String(name) => {
-                let type_decl = self
-                    .types
-                    .iter()
-                    .find(|e| e.as_ref().map(|val| val.name == *name).unwrap_or(false));
-                match type_decl {
-                    Some(type_decl) => match type_decl {
-                        Ok(val) => {
-                            let type_decl = TypeDecl {
-                                name: name.clone(),
-                                property_decls: val.property_decls.clone(),
-                            };
-                            Ok(Some(HashMap::from([(StatusCode::Fixed(200), type_decl)])))
-                        }
-                        Err(_) => Err(InterfaceDeclError::TypeNotFound(name.to_string())),
-                    },
-                    None => Err(InterfaceDeclError::TypeNotFound(name.clone())),
-                }
+            Yaml::Hash(val) if self.has_custom_response_codes(val) => {
+                let responses = self.custom_responses(val)?;
+                let location = format!("{} {}", method, ident);
+                self.warnings
+                    .extend(shadowed_status_codes(&location, &responses));
+                Ok((self.with_default_responses(Some(responses)), None))
+            }
+            other => {
+                let has_content = self.response_parser().has_content(other);
+                let status_code = self
+                    .status_code_inference
+                    .infer(method, has_content, is_async);
+                let value = self.response_data_type_decl(&status_code, other)?;
+                let single_response = HashMap::from([(status_code.clone(), value)]);
+                let responses = self.with_default_responses(Some(single_response));
+                Ok((responses, Some(status_code)))
             }
-            _ => Err(InterfaceDeclError::InvalidResponseDeclaration),
         }
     }
 
-    fn responses_from(&mut self, hash: &YamlHash) -> Result<HttpResponses, InterfaceDeclError> {
-        if self.has_custom_response_codes(hash) {
-            return self.custom_responses(hash);
+    fn with_default_responses(&self, responses: HttpResponses) -> HttpResponses {
+        let defaults = &self.shared_responses.defaults;
+        if defaults.is_empty() {
+            return responses;
+        }
+        let mut responses = responses.unwrap_or_default();
+        for (status_code, response) in defaults {
+            responses
+                .entry(status_code.clone())
+                .or_insert_with(|| response.clone());
         }
-        let status_code = StatusCode::Fixed(200);
-        let value = self.parse_response(&status_code, hash)?;
-        let single_response = HashMap::from([(status_code, value)]);
-        Ok(Some(single_response))
+        Some(responses)
     }
 
+    /// Keys starting with a digit are taken for status codes even if invalid, so that they get reported,
+    /// otherwise the hash is a map of responses only if all keys are status codes, like a lone `default`.
     fn has_custom_response_codes(&self, hash: &YamlHash) -> bool {
-        hash.keys()
-            .find(|key| {
-                key.as_str().map_or(false, |key| {
-                    key.chars().next().map_or(false, |x| x.is_digit(10))
-                })
-            })
-            .is_some()
+        let looks_like_code = |key: &Yaml| match key {
+            Yaml::Integer(_) => true,
+            Yaml::String(key) => key.chars().next().is_some_and(|x| x.is_ascii_digit()),
+            _ => false,
+        };
+        hash.keys().any(looks_like_code)
+            || (!hash.is_empty() && hash.keys().all(|key| status_code_from(key).is_ok()))
     }
 
-    fn custom_responses(&mut self, hash: &YamlHash) -> Result<HttpResponses, InterfaceDeclError> {
+    fn custom_responses(
+        &mut self,
+        hash: &YamlHash,
+    ) -> Result<HashMap<StatusCode, Option<DataTypeDecl>>, InterfaceDeclError> {
         let mut responses = HashMap::new();
         for (key, value) in hash {
-            let key = match key {
-                Yaml::String(val) => Ok(val.to_string()),
-                Yaml::Integer(val) => Ok(val.to_string()),
-                _ => Err(InterfaceDeclError::InvalidKey),
-            }?;
-            let fixed_code: Result<u16, _> = key.parse();
-            let status_code = match fixed_code {
-                Ok(code) => StatusCode::Fixed(code),
-                Err(_) => self.as_status_code_pattern(&key)?,
-            };
-            let type_decl = self.response_type_decl(value)?;
-            responses.insert(status_code, type_decl);
-        }
-        Ok(Some(responses))
-    }
-
-    fn as_status_code_pattern(&self, key: &str) -> Result<StatusCode, InterfaceDeclError> {
-        let first = key.chars().next();
-        let val = first.ok_or(InterfaceDeclError::InvalidStatusCode)?;
-        let num = val
-            .to_digit(10)
-            .ok_or(InterfaceDeclError::InvalidStatusCode)?;
-        let num: u16 = num
-            .try_into()
-            .map_err(|_| InterfaceDeclError::InvalidStatusCode)?;
-        Ok(StatusCode::Prefix(num))
-    }
-
-    fn response_type_decl(&mut self, hash: &Yaml) -> Result<TypeDecl, InterfaceDeclError> {
-        match hash {
-            Yaml::Hash(val) => self.parse_response(&StatusCode::Fixed(200), val),
-            Yaml::String(name) => {
-                let type_decl = self
-                    .types
-                    .iter()
-                    .find(|e| e.as_ref().map(|val| val.name == *name).unwrap_or(false));
-                match type_decl {
-                    Some(type_decl) => match type_decl {
-                        Ok(val) => Ok(TypeDecl {
-                            name: name.clone(),
-                            property_decls: val.property_decls.clone(),
-                        }),
-                        Err(_) => Err(InterfaceDeclError::TypeNotFound(name.to_string())),
-                    },
-                    None => Err(InterfaceDeclError::TypeNotFound(name.clone())),
-                }
-            }
-            _ => Err(InterfaceDeclError::InvalidResponseDeclaration),
+            let status_code = status_code_from(key)?;
+            let data_type_decl = self.response_data_type_decl(&status_code, value)?;
+            insert_response(&mut responses, status_code, data_type_decl)?;
         }
+        Ok(responses)
     }
 
-    fn parse_response(
+    fn response_data_type_decl(
         &mut self,
         key: &StatusCode,
-        hash: &YamlHash,
-    ) -> Result<TypeDecl, InterfaceDeclError> {
-        let mut parser = TypeParser {
-            key: &key.to_string(),
-            value: hash,
-            types_usage: &mut self.types_usage,
-            source: TypeDeclSource::InterfaceOutput(0, key.clone())
-        };
-        parser
-            .parse()
-            .map_err(|_| InterfaceDeclError::InvalidResponseTypeDeclaration)
+        value: &Yaml,
+    ) -> Result<Option<DataTypeDecl>, InterfaceDeclError> {
+        let source = TypeDeclSource::InterfaceOutput(0, key.clone());
+        self.response_parser()
+            .parse(&key.to_string(), value, source)
+    }
+
+    fn response_parser(&mut self) -> ResponseParser<'_> {
+        ResponseParser {
+            types_usage: self.types_usage,
+            shared_responses: self.shared_responses,
+        }
     }
 
     fn get_payload(
@@ -208,28 +272,17 @@ impl<'a> InterfaceParser<'a> {
         method: &HttpMethod,
         hash: &YamlHash,
     ) -> Result<Option<HttpPayload>, InterfaceDeclError> {
-        match method {
-            HttpMethod::Get | HttpMethod::Head => {
-                if hash.contains_key(&key_from("body")) {
-                    return Err(InterfaceDeclError::BodyNotAllowed);
-                }
-                self.get_query_if_has(hash)
-            }
-            HttpMethod::Post | HttpMethod::Put | HttpMethod::Patch => {
-                if hash.contains_key(&key_from("query")) {
-                    return Err(InterfaceDeclError::QueryNotAllowed);
-                }
-                self.get_body_if_has(hash)
-            }
-            HttpMethod::Delete => {
-                if hash.contains_key(&key_from("query")) {
-                    return Err(InterfaceDeclError::QueryNotAllowed);
-                }
-                if hash.contains_key(&key_from("body")) {
-                    return Err(InterfaceDeclError::BodyNotAllowed);
-                }
-                Ok(None)
-            }
+        let payload_kind = self.extension_methods.payload_kind(method);
+        if payload_kind != Some(PayloadKind::Body) && hash.contains_key(&key_from("body")) {
+            return Err(InterfaceDeclError::BodyNotAllowed);
+        }
+        if payload_kind != Some(PayloadKind::Query) && hash.contains_key(&key_from("query")) {
+            return Err(InterfaceDeclError::QueryNotAllowed);
+        }
+        match payload_kind {
+            Some(PayloadKind::Query) => self.get_query_if_has(hash),
+            Some(PayloadKind::Body) => self.get_body_if_has(hash),
+            None => Ok(None),
         }
     }
 
@@ -245,10 +298,10 @@ impl<'a> InterfaceParser<'a> {
             .as_hash()
             .ok_or(InterfaceDeclError::InvalidQuery)?;
         let mut parser = TypeParser {
-            key: &query_key.as_str().unwrap(),
+            key: query_key.as_str().unwrap(),
             value: raw_query,
-            types_usage: &mut self.types_usage,
-            source: TypeDeclSource::InterfaceInput(0)
+            types_usage: self.types_usage,
+            source: TypeDeclSource::InterfaceInput(0),
         };
         let query = parser
             .parse()
@@ -269,10 +322,10 @@ impl<'a> InterfaceParser<'a> {
             .as_hash()
             .ok_or(InterfaceDeclError::InvalidBody)?;
         let mut parser = TypeParser {
-            key: &body_key.as_str().unwrap(),
+            key: body_key.as_str().unwrap(),
             value: raw_body,
-            types_usage: &mut self.types_usage,
-            source: TypeDeclSource::InterfaceInput(0)
+            types_usage: self.types_usage,
+            source: TypeDeclSource::InterfaceInput(0),
         };
         let body = parser
             .parse()
@@ -284,7 +337,7 @@ impl<'a> InterfaceParser<'a> {
 
 fn from_file(source: &Yaml) -> Result<Vec<Result<YamlHash, InterfaceDeclError>>, String> {
     if let Some(source) = source.as_vec() {
-        return Ok(source.iter().map(|item| read_decl(item)).collect());
+        return Ok(source.iter().map(read_decl).collect());
     }
     if let Some(source) = source.as_hash() {
         return Ok(from_hash(source));
@@ -294,12 +347,13 @@ fn from_file(source: &Yaml) -> Result<Vec<Result<YamlHash, InterfaceDeclError>>,
 
 fn from_hash(source: &YamlHash) -> Vec<Result<YamlHash, InterfaceDeclError>> {
     let key = Yaml::from_str("declarations");
-    source[&key]
-        .as_vec()
-        .unwrap()
+    let Some(declarations) = source[&key].as_vec() else {
+        return Vec::new();
+    };
+    declarations
         .iter()
-        .map(|item| read_decl(item))
-        .filter(|item| is_import(item))
+        .map(read_decl)
+        .filter(is_import)
         .collect()
 }
 
@@ -349,21 +403,108 @@ fn get_params(ident: &str) -> Result<Vec<String>, InterfaceDeclError> {
     Ok(params)
 }
 
-fn get_method(hash: &YamlHash) -> Result<HttpMethod, InterfaceDeclError> {
+fn get_method(
+    hash: &YamlHash,
+    extension_methods: &ExtensionMethods,
+) -> Result<HttpMethod, InterfaceDeclError> {
     let raw_method = hash[&Yaml::from_str("method")]
         .as_str()
         .ok_or(InterfaceDeclError::InvalidMethod)?;
-    match raw_method {
-        "get" => Ok(HttpMethod::Get),
-        "post" => Ok(HttpMethod::Post),
-        "put" => Ok(HttpMethod::Put),
-        "delete" => Ok(HttpMethod::Delete),
-        "head" => Ok(HttpMethod::Head),
-        "patch" => Ok(HttpMethod::Patch),
-        /*"options" => Ok(HttpMethod::Options),
-        "trace" => Ok(HttpMethod::Trace),
-        "connect" => Ok(HttpMethod::Connect),*/
-        _ => Err(InterfaceDeclError::InvalidMethod),
+    parse_method(raw_method, extension_methods)
+}
+
+pub(super) fn parse_method(
+    raw_method: &str,
+    extension_methods: &ExtensionMethods,
+) -> Result<HttpMethod, InterfaceDeclError> {
+    let name = raw_method.to_uppercase();
+    if let Some(method) = HttpMethod::standard().find(|method| method.to_string() == name) {
+        return Ok(method.clone());
+    }
+    if extension_methods.payload_kinds.contains_key(&name) {
+        return Ok(HttpMethod::Extension(name));
+    }
+    Err(InterfaceDeclError::InvalidMethod)
+}
+
+fn version_from(raw: &Yaml) -> Result<u32, InterfaceDeclError> {
+    raw.as_i64()
+        .filter(|val| *val > 0)
+        .and_then(|val| u32::try_from(val).ok())
+        .ok_or(InterfaceDeclError::InvalidVersions)
+}
+
+fn get_is_async(hash: &YamlHash) -> Result<bool, InterfaceDeclError> {
+    match hash.get(&key_from("async")) {
+        None => Ok(false),
+        Some(Yaml::Boolean(val)) => Ok(*val),
+        Some(_) => Err(InterfaceDeclError::InvalidAsync),
+    }
+}
+
+fn get_operation_id(
+    hash: &YamlHash,
+    method: &HttpMethod,
+    ident: &str,
+) -> Result<String, InterfaceDeclError> {
+    match hash.get(&key_from("id")) {
+        None => Ok(derive_operation_id(method, ident)),
+        Some(Yaml::String(val)) if is_operation_id(val) => Ok(val.to_string()),
+        Some(_) => Err(InterfaceDeclError::InvalidOperationId),
+    }
+}
+
+fn is_operation_id(val: &str) -> bool {
+    val.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && val.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Derives operation name from method and path, e.g. `GET news/{entry_id}` becomes `get_news_by_entry_id`.
+pub fn derive_operation_id(method: &HttpMethod, ident: &str) -> String {
+    let mut parts = vec![method.to_string().to_lowercase()];
+    for segment in ident.split('/').filter(|segment| !segment.is_empty()) {
+        let segment = match segment
+            .strip_prefix('{')
+            .and_then(|val| val.strip_suffix('}'))
+        {
+            Some(param) => format!("by_{}", param),
+            None => segment.to_string(),
+        };
+        let segment: String = segment
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        parts.push(segment);
+    }
+    parts.join("_")
+}
+
+fn get_tags(hash: &YamlHash) -> Result<Vec<String>, InterfaceDeclError> {
+    match hash.get(&key_from("tags")) {
+        None => Ok(Vec::new()),
+        Some(Yaml::Array(raw)) => raw
+            .iter()
+            .map(|tag| {
+                tag.as_str()
+                    .map(|tag| tag.to_string())
+                    .ok_or(InterfaceDeclError::InvalidTags)
+            })
+            .collect(),
+        Some(_) => Err(InterfaceDeclError::InvalidTags),
+    }
+}
+
+fn get_group(hash: &YamlHash) -> Result<Option<String>, InterfaceDeclError> {
+    match hash.get(&key_from("group")) {
+        None => Ok(None),
+        Some(Yaml::String(val)) => Ok(Some(val.to_string())),
+        Some(_) => Err(InterfaceDeclError::InvalidGroup),
     }
 }
 
@@ -380,7 +521,11 @@ mod tests {
 
     use crate::{
         parser::interfaces::InterfaceParser,
-        schema::{ApiSpec, HttpMethod, InterfaceDecl, InterfaceSpec, PropertyDecl},
+        schema::{
+            ApiSpec, DataType, DataTypeDecl, ExtensionMethods, HttpMethod, InterfaceDecl,
+            InterfaceDeclError, InterfaceSpec, InterfaceVersions, PayloadKind, Primitive,
+            PropertyDecl, SchemaWarning, SharedResponses, StatusCode, StatusCodeInference,
+        },
     };
 
     #[test]
@@ -390,7 +535,10 @@ mod tests {
         hash.insert(Yaml::from_str("method"), Yaml::from_str("get"));
         let mut parser = InterfaceParser {
             types_usage: &mut HashMap::new(),
-            types: &Vec::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
         };
 
         let result = parser.parse(&hash);
@@ -399,11 +547,16 @@ mod tests {
             Ok(InterfaceDecl {
                 ident: "news".to_string(),
                 params: vec![],
+                operation_id: "get_news".to_string(),
+                tags: vec![],
+                group: None,
                 spec: InterfaceSpec::Api(ApiSpec {
                     method: HttpMethod::Get,
                     payload: None,
                     responses: None,
+                    inferred_status_code: None,
                 }),
+                versions: InterfaceVersions::default(),
             }),
             result
         );
@@ -420,7 +573,10 @@ mod tests {
         hash.insert(Yaml::from_str("query"), Yaml::Hash(query));
         let mut parser = InterfaceParser {
             types_usage: &mut HashMap::new(),
-            types: &Vec::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
         };
 
         let result = parser.parse(&hash);
@@ -429,6 +585,9 @@ mod tests {
             Ok(InterfaceDecl {
                 ident: "news".to_string(),
                 params: vec![],
+                operation_id: "get_news".to_string(),
+                tags: vec![],
+                group: None,
                 spec: InterfaceSpec::Api(ApiSpec {
                     method: HttpMethod::Get,
                     payload: Some(super::HttpPayload::Query(vec![
@@ -452,7 +611,9 @@ mod tests {
                         }
                     ])),
                     responses: None,
+                    inferred_status_code: None,
                 }),
+                versions: InterfaceVersions::default(),
             }),
             result
         );
@@ -468,7 +629,10 @@ mod tests {
         hash.insert(Yaml::from_str("body"), Yaml::Hash(body));
         let mut parser = InterfaceParser {
             types_usage: &mut HashMap::new(),
-            types: &Vec::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
         };
 
         let result = parser.parse(&hash);
@@ -486,7 +650,10 @@ mod tests {
         hash.insert(Yaml::from_str("method"), Yaml::from_str("post"));
         let mut parser = InterfaceParser {
             types_usage: &mut HashMap::new(),
-            types: &Vec::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
         };
 
         let result = parser.parse(&hash);
@@ -495,11 +662,16 @@ mod tests {
             Ok(InterfaceDecl {
                 ident: "news/post".to_string(),
                 params: vec![],
+                operation_id: "post_news_post".to_string(),
+                tags: vec![],
+                group: None,
                 spec: InterfaceSpec::Api(ApiSpec {
                     method: HttpMethod::Post,
                     payload: None,
                     responses: None,
+                    inferred_status_code: None,
                 }),
+                versions: InterfaceVersions::default(),
             }),
             result
         );
@@ -515,7 +687,10 @@ mod tests {
         hash.insert(Yaml::from_str("body"), Yaml::Hash(body));
         let mut parser = InterfaceParser {
             types_usage: &mut HashMap::new(),
-            types: &Vec::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
         };
 
         let result = parser.parse(&hash);
@@ -524,6 +699,9 @@ mod tests {
             Ok(InterfaceDecl {
                 ident: "news/post".to_string(),
                 params: vec![],
+                operation_id: "post_news_post".to_string(),
+                tags: vec![],
+                group: None,
                 spec: InterfaceSpec::Api(ApiSpec {
                     method: HttpMethod::Post,
                     payload: Some(super::HttpPayload::Body(vec![PropertyDecl {
@@ -536,7 +714,9 @@ mod tests {
                         })
                     }])),
                     responses: None,
+                    inferred_status_code: None,
                 }),
+                versions: InterfaceVersions::default(),
             }),
             result
         );
@@ -553,7 +733,10 @@ mod tests {
         hash.insert(Yaml::from_str("query"), Yaml::Hash(query));
         let mut parser = InterfaceParser {
             types_usage: &mut HashMap::new(),
-            types: &Vec::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
         };
 
         let result = parser.parse(&hash);
@@ -571,7 +754,10 @@ mod tests {
         hash.insert(Yaml::from_str("method"), Yaml::from_str("put"));
         let mut parser = InterfaceParser {
             types_usage: &mut HashMap::new(),
-            types: &Vec::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
         };
 
         let result = parser.parse(&hash);
@@ -580,11 +766,16 @@ mod tests {
             Ok(InterfaceDecl {
                 ident: "news/post".to_string(),
                 params: vec![],
+                operation_id: "put_news_post".to_string(),
+                tags: vec![],
+                group: None,
                 spec: InterfaceSpec::Api(ApiSpec {
                     method: HttpMethod::Put,
                     payload: None,
                     responses: None,
+                    inferred_status_code: None,
                 }),
+                versions: InterfaceVersions::default(),
             }),
             result
         );
@@ -600,7 +791,10 @@ mod tests {
         hash.insert(Yaml::from_str("method"), Yaml::from_str("delete"));
         let mut parser = InterfaceParser {
             types_usage: &mut HashMap::new(),
-            types: &Vec::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
         };
 
         let result = parser.parse(&hash);
@@ -609,11 +803,16 @@ mod tests {
             Ok(InterfaceDecl {
                 ident: "news/post/{post_id}".to_string(),
                 params: vec!["post_id".to_string()],
+                operation_id: "delete_news_post_by_post_id".to_string(),
+                tags: vec![],
+                group: None,
                 spec: InterfaceSpec::Api(ApiSpec {
                     method: HttpMethod::Delete,
                     payload: None,
                     responses: None,
+                    inferred_status_code: None,
                 }),
+                versions: InterfaceVersions::default(),
             }),
             result
         );
@@ -633,7 +832,10 @@ mod tests {
         hash.insert(Yaml::from_str("query"), Yaml::Hash(query));
         let mut parser = InterfaceParser {
             types_usage: &mut HashMap::new(),
-            types: &Vec::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
         };
 
         let result = parser.parse(&hash);
@@ -657,7 +859,10 @@ mod tests {
         hash.insert(Yaml::from_str("body"), Yaml::Hash(body));
         let mut parser = InterfaceParser {
             types_usage: &mut HashMap::new(),
-            types: &Vec::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
         };
 
         let result = parser.parse(&hash);
@@ -667,4 +872,534 @@ mod tests {
             result
         );
     }
+
+    #[test]
+    fn get_with_array_response() {
+        let mut hash = Hash::new();
+        hash.insert(Yaml::from_str("path"), Yaml::from_str("news"));
+        hash.insert(Yaml::from_str("method"), Yaml::from_str("get"));
+        hash.insert(
+            Yaml::from_str("response"),
+            Yaml::from_str("array[news_entry]"),
+        );
+        let mut parser = InterfaceParser {
+            types_usage: &mut HashMap::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
+        };
+
+        let result = parser.parse(&hash);
+
+        assert_eq!(
+            Ok(InterfaceDecl {
+                ident: "news".to_string(),
+                params: vec![],
+                operation_id: "get_news".to_string(),
+                tags: vec![],
+                group: None,
+                spec: InterfaceSpec::Api(ApiSpec {
+                    method: HttpMethod::Get,
+                    payload: None,
+                    responses: Some(HashMap::from([(
+                        StatusCode::Fixed(200),
+                        Some(DataTypeDecl {
+                            data_type: DataType::Array(Box::new(DataType::Object(
+                                "news_entry".to_string()
+                            ))),
+                            is_required: true
+                        })
+                    )])),
+                    inferred_status_code: Some(StatusCode::Fixed(200)),
+                }),
+                versions: InterfaceVersions::default(),
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn delete_with_no_content_response() {
+        let mut hash = Hash::new();
+        hash.insert(
+            Yaml::from_str("path"),
+            Yaml::from_str("news/post/{post_id}"),
+        );
+        hash.insert(Yaml::from_str("method"), Yaml::from_str("delete"));
+        let mut response = Hash::new();
+        response.insert(Yaml::Integer(204), Yaml::Null);
+        response.insert(Yaml::from_str("4xx"), Yaml::from_str("str"));
+        hash.insert(Yaml::from_str("response"), Yaml::Hash(response));
+        let mut parser = InterfaceParser {
+            types_usage: &mut HashMap::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
+        };
+
+        let result = parser.parse(&hash);
+
+        assert_eq!(
+            Ok(InterfaceDecl {
+                ident: "news/post/{post_id}".to_string(),
+                params: vec!["post_id".to_string()],
+                operation_id: "delete_news_post_by_post_id".to_string(),
+                tags: vec![],
+                group: None,
+                spec: InterfaceSpec::Api(ApiSpec {
+                    method: HttpMethod::Delete,
+                    payload: None,
+                    responses: Some(HashMap::from([
+                        (StatusCode::Fixed(204), None),
+                        (
+                            StatusCode::Prefix(4),
+                            Some(DataTypeDecl {
+                                data_type: DataType::Primitive(Primitive::Str),
+                                is_required: true
+                            })
+                        ),
+                    ])),
+                    inferred_status_code: None,
+                }),
+                versions: InterfaceVersions::default(),
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn post_with_response_infers_created() {
+        let mut hash = Hash::new();
+        hash.insert(Yaml::from_str("path"), Yaml::from_str("news"));
+        hash.insert(Yaml::from_str("method"), Yaml::from_str("post"));
+        hash.insert(Yaml::from_str("response"), Yaml::from_str("news_entry"));
+        let mut parser = InterfaceParser {
+            types_usage: &mut HashMap::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
+        };
+
+        let result = parser.parse(&hash);
+
+        assert_eq!(
+            Ok(InterfaceDecl {
+                ident: "news".to_string(),
+                params: vec![],
+                operation_id: "post_news".to_string(),
+                tags: vec![],
+                group: None,
+                spec: InterfaceSpec::Api(ApiSpec {
+                    method: HttpMethod::Post,
+                    payload: None,
+                    responses: Some(HashMap::from([(
+                        StatusCode::Fixed(201),
+                        Some(DataTypeDecl {
+                            data_type: DataType::Object("news_entry".to_string()),
+                            is_required: true
+                        })
+                    )])),
+                    inferred_status_code: Some(StatusCode::Fixed(201)),
+                }),
+                versions: InterfaceVersions::default(),
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn delete_without_content_infers_no_content() {
+        let mut hash = Hash::new();
+        hash.insert(
+            Yaml::from_str("path"),
+            Yaml::from_str("news/post/{post_id}"),
+        );
+        hash.insert(Yaml::from_str("method"), Yaml::from_str("delete"));
+        hash.insert(Yaml::from_str("response"), Yaml::Null);
+        let mut parser = InterfaceParser {
+            types_usage: &mut HashMap::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
+        };
+
+        let result = parser.parse(&hash);
+
+        assert_eq!(
+            Ok(InterfaceDecl {
+                ident: "news/post/{post_id}".to_string(),
+                params: vec!["post_id".to_string()],
+                operation_id: "delete_news_post_by_post_id".to_string(),
+                tags: vec![],
+                group: None,
+                spec: InterfaceSpec::Api(ApiSpec {
+                    method: HttpMethod::Delete,
+                    payload: None,
+                    responses: Some(HashMap::from([(StatusCode::Fixed(204), None)])),
+                    inferred_status_code: Some(StatusCode::Fixed(204)),
+                }),
+                versions: InterfaceVersions::default(),
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn async_post_infers_accepted() {
+        let mut hash = Hash::new();
+        hash.insert(Yaml::from_str("path"), Yaml::from_str("reports"));
+        hash.insert(Yaml::from_str("method"), Yaml::from_str("post"));
+        hash.insert(Yaml::from_str("async"), Yaml::Boolean(true));
+        hash.insert(Yaml::from_str("response"), Yaml::from_str("job"));
+        let mut parser = InterfaceParser {
+            types_usage: &mut HashMap::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
+        };
+
+        let result = parser.parse(&hash);
+
+        let InterfaceSpec::Api(api) = result.unwrap().spec;
+        assert_eq!(Some(StatusCode::Fixed(202)), api.inferred_status_code);
+    }
+
+    #[test]
+    fn named_responses_and_defaults() {
+        let mut hash = Hash::new();
+        hash.insert(Yaml::from_str("path"), Yaml::from_str("news"));
+        hash.insert(Yaml::from_str("method"), Yaml::from_str("get"));
+        let mut response = Hash::new();
+        response.insert(Yaml::Integer(200), Yaml::from_str("array[str]"));
+        response.insert(Yaml::from_str("4xx"), Yaml::from_str("$validation_error"));
+        hash.insert(Yaml::from_str("response"), Yaml::Hash(response));
+        let validation_error = Some(DataTypeDecl {
+            data_type: DataType::Object("error".to_string()),
+            is_required: true,
+        });
+        let shared_responses = SharedResponses {
+            named: HashMap::from([("validation_error".to_string(), validation_error.clone())]),
+            defaults: HashMap::from([
+                (StatusCode::Prefix(4), None),
+                (StatusCode::Prefix(5), validation_error.clone()),
+            ]),
+        };
+        let mut parser = InterfaceParser {
+            types_usage: &mut HashMap::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &shared_responses,
+            warnings: Vec::new(),
+        };
+
+        let result = parser.parse(&hash);
+
+        let InterfaceSpec::Api(api) = result.unwrap().spec;
+        assert_eq!(
+            Some(HashMap::from([
+                (
+                    StatusCode::Fixed(200),
+                    Some(DataTypeDecl {
+                        data_type: DataType::Array(Box::new(DataType::Primitive(Primitive::Str))),
+                        is_required: true
+                    })
+                ),
+                (StatusCode::Prefix(4), validation_error.clone()),
+                (StatusCode::Prefix(5), validation_error),
+            ])),
+            api.responses
+        );
+    }
+
+    #[test]
+    fn unknown_named_response() {
+        let mut hash = Hash::new();
+        hash.insert(Yaml::from_str("path"), Yaml::from_str("news"));
+        hash.insert(Yaml::from_str("method"), Yaml::from_str("get"));
+        hash.insert(Yaml::from_str("response"), Yaml::from_str("$missing"));
+        let mut parser = InterfaceParser {
+            types_usage: &mut HashMap::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
+        };
+
+        let result = parser.parse(&hash);
+
+        assert_eq!(
+            Err(crate::schema::InterfaceDeclError::ResponseNotFound(
+                "missing".to_string()
+            )),
+            result
+        );
+    }
+
+    fn parse_responses(
+        response: Hash,
+    ) -> (
+        Result<InterfaceDecl, InterfaceDeclError>,
+        Vec<SchemaWarning>,
+    ) {
+        let mut hash = Hash::new();
+        hash.insert(Yaml::from_str("path"), Yaml::from_str("news"));
+        hash.insert(Yaml::from_str("method"), Yaml::from_str("get"));
+        hash.insert(Yaml::from_str("response"), Yaml::Hash(response));
+        let mut parser = InterfaceParser {
+            types_usage: &mut HashMap::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
+        };
+        let result = parser.parse(&hash);
+        (result, parser.warnings)
+    }
+
+    #[test]
+    fn invalid_status_codes() {
+        for key in [
+            Yaml::from_str("4ab"),
+            Yaml::from_str("9xx"),
+            Yaml::String("42".to_string()),
+            Yaml::Integer(999),
+            Yaml::Integer(42),
+        ] {
+            let mut response = Hash::new();
+            response.insert(Yaml::Integer(200), Yaml::from_str("str"));
+            response.insert(key, Yaml::from_str("str"));
+
+            let (result, _) = parse_responses(response);
+
+            assert_eq!(Err(InterfaceDeclError::InvalidStatusCode), result);
+        }
+    }
+
+    #[test]
+    fn duplicate_status_code() {
+        let mut response = Hash::new();
+        response.insert(Yaml::Integer(200), Yaml::from_str("str"));
+        response.insert(Yaml::String("200".to_string()), Yaml::from_str("int"));
+
+        let (result, _) = parse_responses(response);
+
+        assert_eq!(
+            Err(InterfaceDeclError::DuplicateStatusCode(StatusCode::Fixed(
+                200
+            ))),
+            result
+        );
+    }
+
+    #[test]
+    fn default_and_shadowed_status_codes() {
+        let mut response = Hash::new();
+        response.insert(Yaml::Integer(200), Yaml::from_str("str"));
+        response.insert(Yaml::Integer(404), Yaml::Null);
+        response.insert(Yaml::from_str("4XX"), Yaml::from_str("str"));
+        response.insert(Yaml::from_str("default"), Yaml::Null);
+
+        let (result, warnings) = parse_responses(response);
+
+        let InterfaceSpec::Api(api) = result.unwrap().spec;
+        let mut codes: Vec<_> = api.responses.unwrap().into_keys().collect();
+        codes.sort_by_key(|code| code.as_key());
+        assert_eq!(
+            vec![
+                StatusCode::Fixed(200),
+                StatusCode::Fixed(404),
+                StatusCode::Prefix(4),
+                StatusCode::Default
+            ],
+            codes
+        );
+        assert_eq!(
+            vec![SchemaWarning::ShadowedStatusCode {
+                location: "GET news".to_string(),
+                fixed: StatusCode::Fixed(404),
+                pattern: StatusCode::Prefix(4),
+            }],
+            warnings
+        );
+    }
+
+    #[test]
+    fn default_only_response() {
+        let mut response = Hash::new();
+        response.insert(Yaml::from_str("default"), Yaml::from_str("str"));
+
+        let (result, _) = parse_responses(response);
+
+        let InterfaceSpec::Api(api) = result.unwrap().spec;
+        assert_eq!(
+            Some(HashMap::from([(
+                StatusCode::Default,
+                Some(DataTypeDecl {
+                    data_type: DataType::Primitive(Primitive::Str),
+                    is_required: true
+                })
+            )])),
+            api.responses
+        );
+    }
+
+    #[test]
+    fn case_insensitive_methods() {
+        for (raw_method, method) in [
+            ("GET", HttpMethod::Get),
+            ("Options", HttpMethod::Options),
+            ("trace", HttpMethod::Trace),
+            ("CONNECT", HttpMethod::Connect),
+        ] {
+            let mut hash = Hash::new();
+            hash.insert(Yaml::from_str("path"), Yaml::from_str("news"));
+            hash.insert(Yaml::from_str("method"), Yaml::from_str(raw_method));
+            let mut parser = InterfaceParser {
+                types_usage: &mut HashMap::new(),
+                extension_methods: &ExtensionMethods::default(),
+                status_code_inference: &StatusCodeInference::default(),
+                shared_responses: &SharedResponses::default(),
+                warnings: Vec::new(),
+            };
+
+            let result = parser.parse(&hash);
+
+            let InterfaceSpec::Api(api) = result.unwrap().spec;
+            assert_eq!(method, api.method);
+        }
+    }
+
+    #[test]
+    fn extension_method_requires_opt_in() {
+        let mut hash = Hash::new();
+        hash.insert(Yaml::from_str("path"), Yaml::from_str("files/{file_id}"));
+        hash.insert(Yaml::from_str("method"), Yaml::from_str("propfind"));
+        let mut body = Hash::new();
+        body.insert(Yaml::from_str("depth"), Yaml::from_str("int"));
+        hash.insert(Yaml::from_str("body"), Yaml::Hash(body));
+        let extension_methods = ExtensionMethods {
+            payload_kinds: HashMap::from([("PROPFIND".to_string(), Some(PayloadKind::Body))]),
+        };
+        let mut parser = InterfaceParser {
+            types_usage: &mut HashMap::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
+        };
+
+        let rejected = parser.parse(&hash);
+        parser.extension_methods = &extension_methods;
+        let accepted = parser.parse(&hash);
+
+        assert_eq!(Err(InterfaceDeclError::InvalidMethod), rejected);
+        let InterfaceSpec::Api(api) = accepted.unwrap().spec;
+        assert_eq!(HttpMethod::Extension("PROPFIND".to_string()), api.method);
+        assert!(matches!(api.payload, Some(super::HttpPayload::Body(_))));
+    }
+
+    #[test]
+    fn versions_with_overrides() {
+        let mut hash = Hash::new();
+        hash.insert(Yaml::from_str("path"), Yaml::from_str("news"));
+        hash.insert(Yaml::from_str("method"), Yaml::from_str("get"));
+        hash.insert(Yaml::from_str("since"), Yaml::Integer(2));
+        hash.insert(Yaml::from_str("removed_in"), Yaml::Integer(5));
+        hash.insert(Yaml::from_str("response"), Yaml::from_str("array[str]"));
+        let mut override_v3 = Hash::new();
+        override_v3.insert(Yaml::from_str("response"), Yaml::from_str("news_page"));
+        let mut overrides = Hash::new();
+        overrides.insert(Yaml::Integer(3), Yaml::Hash(override_v3));
+        hash.insert(Yaml::from_str("overrides"), Yaml::Hash(overrides));
+        let mut parser = InterfaceParser {
+            types_usage: &mut HashMap::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
+        };
+
+        let decl = parser.parse(&hash).unwrap();
+
+        let response_in = |version: u32| {
+            decl.for_version(version).map(|decl| {
+                let InterfaceSpec::Api(api) = decl.spec;
+                api.responses.unwrap()[&StatusCode::Fixed(200)]
+                    .as_ref()
+                    .unwrap()
+                    .to_string()
+            })
+        };
+        assert_eq!(None, response_in(1));
+        assert_eq!(Some("array[str]".to_string()), response_in(2));
+        assert_eq!(Some("news_page".to_string()), response_in(3));
+        assert_eq!(Some("news_page".to_string()), response_in(4));
+        assert_eq!(None, response_in(5));
+    }
+
+    #[test]
+    fn invalid_versions() {
+        let mut hash = Hash::new();
+        hash.insert(Yaml::from_str("path"), Yaml::from_str("news"));
+        hash.insert(Yaml::from_str("method"), Yaml::from_str("get"));
+        hash.insert(
+            Yaml::from_str("versions"),
+            Yaml::Array(vec![Yaml::Integer(1), Yaml::Integer(2)]),
+        );
+        hash.insert(Yaml::from_str("since"), Yaml::Integer(2));
+        let mut parser = InterfaceParser {
+            types_usage: &mut HashMap::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
+        };
+
+        let result = parser.parse(&hash);
+
+        assert_eq!(Err(InterfaceDeclError::InvalidVersions), result);
+    }
+
+    #[test]
+    fn operation_id_and_tags() {
+        let mut hash = Hash::new();
+        hash.insert(Yaml::from_str("path"), Yaml::from_str("news/{entry_id}"));
+        hash.insert(Yaml::from_str("method"), Yaml::from_str("get"));
+        hash.insert(
+            Yaml::from_str("tags"),
+            Yaml::Array(vec![Yaml::from_str("news"), Yaml::from_str("public")]),
+        );
+        hash.insert(Yaml::from_str("group"), Yaml::from_str("content"));
+        let mut parser = InterfaceParser {
+            types_usage: &mut HashMap::new(),
+            extension_methods: &ExtensionMethods::default(),
+            status_code_inference: &StatusCodeInference::default(),
+            shared_responses: &SharedResponses::default(),
+            warnings: Vec::new(),
+        };
+
+        let decl = parser.parse(&hash).unwrap();
+        assert_eq!("get_news_by_entry_id", decl.operation_id);
+        assert_eq!(vec!["news".to_string(), "public".to_string()], decl.tags);
+        assert_eq!(Some("content".to_string()), decl.group);
+
+        hash.insert(Yaml::from_str("id"), Yaml::from_str("fetchEntry"));
+        let decl = parser.parse(&hash).unwrap();
+        assert_eq!("fetchEntry", decl.operation_id);
+
+        hash.insert(Yaml::from_str("id"), Yaml::from_str("fetch entry"));
+        assert_eq!(
+            Err(InterfaceDeclError::InvalidOperationId),
+            parser.parse(&hash)
+        );
+
+        hash.remove(&Yaml::from_str("id"));
+        hash.insert(Yaml::from_str("group"), Yaml::Integer(1));
+        assert_eq!(Err(InterfaceDeclError::InvalidGroup), parser.parse(&hash));
+    }
 }
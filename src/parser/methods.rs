@@ -0,0 +1,96 @@
+use crate::parser::utils::{as_str_or, YamlHash};
+use crate::schema::{ExtensionMethods, HttpMethod, PayloadKind};
+use std::fmt::{Debug, Display, Formatter};
+use yaml_rust::Yaml;
+
+pub struct ExtensionMethodsParser<'a> {
+    pub main: &'a Yaml,
+}
+
+impl<'a> ExtensionMethodsParser<'a> {
+    pub fn parse(&self) -> Result<ExtensionMethods, ExtensionMethodsError> {
+        let mut extension_methods = ExtensionMethods::default();
+        let raw_methods: &YamlHash = match &self.main["extension_methods"] {
+            Yaml::BadValue => return Ok(extension_methods),
+            Yaml::Hash(val) => val,
+            _ => return Err(ExtensionMethodsError::UnsupportedSection),
+        };
+        for (key, value) in raw_methods {
+            let name = as_str_or(key, ExtensionMethodsError::UnsupportedKeyType)?;
+            let name = self.name(&name)?;
+            let payload_kind = match value {
+                Yaml::Null => None,
+                Yaml::String(val) if val == "query" => Some(PayloadKind::Query),
+                Yaml::String(val) if val == "body" => Some(PayloadKind::Body),
+                _ => return Err(ExtensionMethodsError::UnsupportedPayload(name)),
+            };
+            extension_methods.payload_kinds.insert(name, payload_kind);
+        }
+        Ok(extension_methods)
+    }
+
+    fn name(&self, raw: &str) -> Result<String, ExtensionMethodsError> {
+        let name = raw.to_uppercase();
+        let is_token = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c));
+        if !is_token {
+            return Err(ExtensionMethodsError::InvalidName(raw.to_string()));
+        }
+        if HttpMethod::standard().any(|method| method.to_string() == name) {
+            return Err(ExtensionMethodsError::StandardMethod(raw.to_string()));
+        }
+        Ok(name)
+    }
+}
+
+pub enum ExtensionMethodsError {
+    UnsupportedSection,
+    UnsupportedKeyType,
+    InvalidName(String),
+    StandardMethod(String),
+    UnsupportedPayload(String),
+}
+
+impl ExtensionMethodsError {
+    fn default_fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExtensionMethodsError::UnsupportedSection => {
+                write!(f, "'extension_methods' should be a hashmap.")
+            }
+            ExtensionMethodsError::UnsupportedKeyType => {
+                write!(f, "Extension method name must be string.")
+            }
+            ExtensionMethodsError::InvalidName(name) => {
+                write!(f, "'{}' is not a valid method name.", name)
+            }
+            ExtensionMethodsError::StandardMethod(name) => {
+                write!(
+                    f,
+                    "'{}' is a standard method and cannot be redeclared.",
+                    name
+                )
+            }
+            ExtensionMethodsError::UnsupportedPayload(name) => write!(
+                f,
+                "Payload of extension method {} should be 'query', 'body' or empty.",
+                name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExtensionMethodsError {}
+
+impl Display for ExtensionMethodsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+impl Debug for ExtensionMethodsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
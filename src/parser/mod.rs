@@ -1,6 +1,13 @@
 mod hosts;
-mod imports;
+pub(crate) mod imports;
 mod interfaces;
+mod methods;
+mod modules;
+mod namespaces;
+pub(crate) mod packages;
+mod responses;
+mod semantics;
+mod status_codes;
 mod types;
 pub(crate) mod utils;
 mod versioning;
@@ -9,17 +16,25 @@ use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::Display;
 
-use crate::parser::hosts::HostsParser;
-use crate::parser::imports::detect;
+use crate::parser::hosts::{check_deployments, HostsParser};
+use crate::parser::methods::ExtensionMethodsParser;
+use crate::parser::modules::SectionsParser;
+use crate::parser::packages::PackagesParser;
+use crate::parser::responses::SharedResponsesParser;
+use crate::parser::status_codes::StatusCodeInferenceParser;
 use crate::parser::types::TypesParser;
 use crate::parser::{utils::read_yaml, versioning::VersioningParser};
-use crate::schema::{ImportError, Schema, TypeUsageMeta, UnknownType};
+use crate::schema::{
+    DeclLocations, ImportGraph, Package, Schema, Suppressions, TypeUsageMeta, UnknownType,
+};
 
 use self::interfaces::InterfacesParser;
+use self::namespaces::NamespaceResolver;
+use self::semantics::{SemanticChecker, SemanticErrors};
 
 #[derive(Debug)]
 pub struct MissingTypeDeclError {
-    pub list: Vec<UnknownType>
+    pub list: Vec<(String, UnknownType)>
 }
 
 impl Error for MissingTypeDeclError {
@@ -27,63 +42,125 @@ impl Error for MissingTypeDeclError {
 
 impl Display for MissingTypeDeclError {
    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-       f.write_str(&format!("{:?}", self.list))
+       let messages: Vec<String> = self
+           .list
+           .iter()
+           .map(|(type_name, unknown)| unknown_type_message(type_name, unknown))
+           .collect();
+       f.write_str(&messages.join("\n"))
    } 
 }
 
+fn unknown_type_message(type_name: &str, unknown: &UnknownType) -> String {
+    match unknown {
+        UnknownType::InTypeDeclaration(ti, pi) => {
+            format!("Unknown type {} at {} in property at {}", type_name, ti, pi)
+        }
+        UnknownType::InPayload(ii, pi) => {
+            format!("Unknown type {} in interface (#{}) input {}", type_name, ii, pi)
+        }
+        UnknownType::InResponse(ii, code, pi) => format!(
+            "Unknown type {} in interface (#{}) output status code {} in property at {}",
+            type_name, ii, code, pi
+        ),
+        UnknownType::InNamedResponse(name, pi) => format!(
+            "Unknown type {} in shared response {} in property at {}",
+            type_name, name, pi
+        ),
+    }
+}
+
+/// Writes the lockfile pinning versions and checksums of packages vendored for the schema.
+pub fn lock(parent_path: &str) -> Result<Vec<Package>, Box<dyn std::error::Error>> {
+    let file_path = &(parent_path.to_string() + "/main.yaml");
+    let yaml = read_yaml(file_path)?;
+    let packages_parser = PackagesParser {
+        main: &yaml[0],
+        root_path: parent_path,
+    };
+    Ok(packages_parser.lock()?)
+}
+
 pub fn parse(parent_path: &str) -> Result<Schema, Box<dyn std::error::Error>> {
     let file_path = &(parent_path.to_string() + "/main.yaml");
     let yaml = read_yaml(file_path)?;
     let main = &yaml[0];
+    let packages_parser = PackagesParser {
+        main,
+        root_path: parent_path,
+    };
+    let packages = packages_parser.parse()?;
     let hosts_parser = HostsParser { main };
     let hosts = hosts_parser.parse()?;
     let versioning_parser = VersioningParser { main };
     let versioning = versioning_parser.parse()?;
+    let extension_methods_parser = ExtensionMethodsParser { main };
+    let extension_methods = extension_methods_parser.parse()?;
+    let status_code_inference_parser = StatusCodeInferenceParser {
+        main,
+        extension_methods: &extension_methods,
+    };
+    let status_code_inference = status_code_inference_parser.parse()?;
     let mut types_usage: HashMap<String, TypeUsageMeta> = HashMap::new();
-    let main_types_hash = main["types"]
-        .as_hash()
-        .ok_or(ImportError::InvalidInputSource)?;
-    let types_imports = detect(&main_types_hash, parent_path);
+    let mut imports = ImportGraph::default();
+    let sections_parser = SectionsParser {
+        main,
+        root_path: parent_path,
+        packages: &packages,
+    };
+    let sources = sections_parser.parse(&mut imports)?;
+    let mut locations = DeclLocations::default();
+    let mut suppressions = Suppressions::default();
     let mut types_parser = TypesParser {
-        parent_path,
         types_usage: &mut types_usage,
+        locations: &mut locations.types,
+        suppressions: &mut suppressions.types,
     };
-    let mut types: Vec<_> = vec![];
-    for import in types_imports {
-        types.extend(types_parser.parse(import?)?);
-    }
-    let main_interfaces_hash = main["interfaces"]
-        .as_hash()
-        .ok_or(ImportError::InvalidInputSource)?;
-    let interfaces_imports = detect(&main_interfaces_hash, parent_path);
+    let mut types = types_parser.parse(sources.types)?;
+    let mut warnings = Vec::new();
+    let mut shared_responses_parser = SharedResponsesParser {
+        main,
+        types_usage: &mut types_usage,
+        warnings: &mut warnings,
+    };
+    let mut shared_responses = shared_responses_parser.parse()?;
     let mut interfaces_parser = InterfacesParser {
-        parent_path,
         types_usage: &mut types_usage,
+        extension_methods: &extension_methods,
+        status_code_inference: &status_code_inference,
+        shared_responses: &shared_responses,
+        warnings: &mut warnings,
+        locations: &mut locations.interfaces,
+        suppressions: &mut suppressions.interfaces,
+    };
+    let mut interfaces = interfaces_parser.parse(sources.interfaces);
+    let mut namespace_resolver = NamespaceResolver {
+        types: &mut types,
+        interfaces: &mut interfaces,
+        shared_responses: &mut shared_responses,
+        locations: &locations,
+        imports: &imports,
+    };
+    let mut semantic_errors = namespace_resolver.resolve();
+    let semantic_checker = SemanticChecker {
         types: &types,
+        interfaces: &interfaces,
+        locations: &locations,
     };
-    let mut interfaces: Vec<_> = vec![];
-    for import in interfaces_imports {
-        interfaces.extend(interfaces_parser.parse(import?)?);
+    semantic_errors.extend(semantic_checker.check(&mut warnings));
+    if !semantic_errors.is_empty() {
+        let err = SemanticErrors {
+            list: semantic_errors,
+        };
+        return Err(Box::new(err));
     }
-    let mut missing_declations: Vec<UnknownType> = Vec::new();
+    check_deployments(&hosts, &interfaces)?;
+    let mut missing_declations: Vec<(String, UnknownType)> = Vec::new();
     for (type_name, unknown) in &types_usage {
         if let Some(unknown) = unknown {
             for e in unknown {
-                missing_declations.push(e.clone());
-                match e {
-                    UnknownType::InTypeDeclaration(ti, pi) => {
-                        println!("Unknown type {} at {} in property at {}", type_name, ti, pi);
-                    }
-                    UnknownType::InPayload(ii, pi) => {
-                        println!("Unknown type {} in interface (#{}) input {}", type_name, ii, pi);
-                    }
-                    UnknownType::InResponse(ii, code, pi) => {
-                        println!(
-                        "Unknown type {} in interface (#{}) output status code {} in property at {}",
-                        type_name, ii, code, pi
-                    );
-                    }
-                }
+                println!("{}", unknown_type_message(type_name, e));
+                missing_declations.push((type_name.clone(), e.clone()));
             }
         }
     }
@@ -94,8 +171,16 @@ pub fn parse(parent_path: &str) -> Result<Schema, Box<dyn std::error::Error>> {
     let schema = Schema {
         hosts,
         versioning,
+        extension_methods,
+        status_code_inference,
+        shared_responses,
         types,
         interfaces,
+        locations,
+        suppressions,
+        imports,
+        packages,
+        warnings,
     };
     Ok(schema)
 }
@@ -0,0 +1,223 @@
+use yaml_rust::Yaml;
+
+use crate::parser::imports::{FailedImport, ImportResolver, ImportResults, SourceImport};
+use crate::schema::{ImportError, ImportGraph, Package};
+
+/// Sources of declarations, split by the section they belong to.
+#[derive(Default)]
+pub struct SectionSources {
+    pub types: ImportResults,
+    pub interfaces: ImportResults,
+}
+
+/// Collects sources of types and interfaces from `types` and `interfaces` sections of the root document,
+/// and from module files imported in its `modules` section.
+///
+/// A module file holds both sections, each with its own `_import` of plain types or interfaces files,
+/// while `_import` at the top level of a module imports other modules.
+pub struct SectionsParser<'a> {
+    pub main: &'a Yaml,
+    pub root_path: &'a str,
+    pub packages: &'a [Package],
+}
+
+impl<'a> SectionsParser<'a> {
+    pub fn parse(&self, graph: &mut ImportGraph) -> Result<SectionSources, ImportError> {
+        let modules = match &self.main["modules"] {
+            Yaml::BadValue => Vec::new(),
+            Yaml::Hash(val) => {
+                ImportResolver::new(self.root_path, self.packages, graph).resolve(val, "main.yaml")
+            }
+            _ => return Err(ImportError::InvalidInputSource),
+        };
+        let has_modules = !modules.is_empty();
+        let mut sources = SectionSources::default();
+        let modules: Vec<SourceImport> = modules
+            .into_iter()
+            .filter_map(|module| match module.and_then(validated_module) {
+                Ok(module) => Some(module),
+                Err(failure) => {
+                    sources.types.push(Err(failure));
+                    None
+                }
+            })
+            .collect();
+        graph
+            .modules
+            .extend(modules.iter().map(|module| module.path.clone()));
+        for (section, output) in [
+            ("types", &mut sources.types),
+            ("interfaces", &mut sources.interfaces),
+        ] {
+            let mut resolver = ImportResolver::new(self.root_path, self.packages, graph);
+            match &self.main[section] {
+                Yaml::Hash(val) => output.extend(resolver.resolve(val, "main.yaml")),
+                Yaml::BadValue if has_modules => {}
+                _ => return Err(ImportError::InvalidInputSource),
+            }
+            for module in &modules {
+                let content = module.imported_source[section].clone();
+                let nested: Vec<_> = match &content {
+                    Yaml::Hash(val) => vec![val.clone()],
+                    Yaml::Array(items) => items
+                        .iter()
+                        .filter_map(|item| item.as_hash())
+                        .cloned()
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                if content.is_badvalue() || content.is_null() {
+                    continue;
+                }
+                output.push(Ok(SourceImport {
+                    path: module.path.clone(),
+                    namespace: module.namespace.clone(),
+                    only: module.only.clone(),
+                    imported_source: content,
+                }));
+                for hash in nested {
+                    output.extend(resolver.resolve(&hash, &module.path));
+                }
+            }
+        }
+        Ok(sources)
+    }
+}
+
+fn validated_module(module: SourceImport) -> Result<SourceImport, FailedImport> {
+    let is_valid = module.imported_source.as_hash().is_some_and(|hash| {
+        hash.keys().all(|key| {
+            matches!(
+                key.as_str(),
+                Some("_import") | Some("_namespace") | Some("types") | Some("interfaces")
+            )
+        })
+    });
+    if is_valid {
+        Ok(module)
+    } else {
+        Err(FailedImport {
+            importer: module.path.clone(),
+            error: ImportError::InvalidModule(module.path),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use yaml_rust::YamlLoader;
+
+    use crate::parser::imports::ImportResults;
+    use crate::parser::modules::SectionsParser;
+    use crate::schema::{ImportError, ImportGraph};
+    use crate::test_utils::TempSpec;
+
+    #[test]
+    fn test_sections() {
+        let spec = TempSpec::new(
+            "modules",
+            &[
+                (
+                    "features/feed.yml",
+                    "_import: common.yml\ntypes:\n  _import: ../feed_types.yml\n  feed_item:\n    id: str\ninterfaces:\n  - path: feed\n    method: get\n",
+                ),
+                ("features/common.yml", "types:\n  page:\n    next: str\n"),
+                ("feed_types.yml", "feed_extra:\n  id: str\n"),
+                ("types.yml", "user:\n  id: str\n"),
+                ("broken.yml", "user:\n  id: str\n"),
+            ],
+        );
+        let main = YamlLoader::load_from_str(
+            "types:\n  _import: types.yml\nmodules:\n  _import: [features/feed.yml, broken.yml]",
+        )
+        .unwrap();
+        let parser = SectionsParser {
+            main: &main[0],
+            root_path: spec.path(),
+            packages: &[],
+        };
+        let mut graph = ImportGraph::default();
+
+        let sources = parser.parse(&mut graph).unwrap();
+
+        let paths = |sources: &ImportResults| -> Vec<String> {
+            sources
+                .iter()
+                .map(|source| match source {
+                    Ok(source) => source.path.clone(),
+                    Err(failure) => format!("error: {}", failure.error),
+                })
+                .collect()
+        };
+        assert_eq!(
+            vec![
+                format!(
+                    "error: {}",
+                    ImportError::InvalidModule("broken.yml".to_string())
+                ),
+                "types.yml".to_string(),
+                "features/feed.yml".to_string(),
+                "feed_types.yml".to_string(),
+                "features/common.yml".to_string(),
+            ],
+            paths(&sources.types)
+        );
+        assert_eq!(
+            vec!["features/feed.yml".to_string()],
+            paths(&sources.interfaces)
+        );
+        let mut modules: Vec<&String> = graph.modules.iter().collect();
+        modules.sort();
+        assert_eq!(vec!["features/common.yml", "features/feed.yml"], modules);
+    }
+
+    #[test]
+    fn test_single_section_modules() {
+        let spec = TempSpec::new(
+            "modules-single-section",
+            &[
+                ("users.yml", "types:\n  user:\n    id: str\n"),
+                (
+                    "health.yml",
+                    "interfaces:\n  - path: health\n    method: get\n    response: user\n",
+                ),
+            ],
+        );
+        let main =
+            YamlLoader::load_from_str("modules:\n  _import: [users.yml, health.yml]").unwrap();
+        let parser = SectionsParser {
+            main: &main[0],
+            root_path: spec.path(),
+            packages: &[],
+        };
+        let mut graph = ImportGraph::default();
+
+        let sources = parser.parse(&mut graph).unwrap();
+
+        let paths = |sources: &ImportResults| -> Vec<String> {
+            sources
+                .iter()
+                .map(|source| source.as_ref().unwrap().path.clone())
+                .collect()
+        };
+        assert_eq!(vec!["users.yml".to_string()], paths(&sources.types));
+        assert_eq!(vec!["health.yml".to_string()], paths(&sources.interfaces));
+
+        spec.write(&[(
+            "main.yaml",
+            "hosts:\n  prod: https://acme.com\nmodules:\n  _import: [users.yml, health.yml]\n",
+        )]);
+        let schema = spec.parse();
+        assert_eq!(
+            vec!["user".to_string()],
+            schema
+                .types
+                .iter()
+                .map(|val| val.as_ref().unwrap().name.clone())
+                .collect::<Vec<_>>()
+        );
+        let interfaces: Vec<_> = schema.interfaces.iter().collect();
+        assert_eq!(1, interfaces.len());
+        assert_eq!("health", interfaces[0].as_ref().unwrap().ident);
+    }
+}
@@ -0,0 +1,454 @@
+use std::collections::HashMap;
+
+use crate::parser::semantics::SemanticError;
+use crate::schema::{
+    ApiSpec, DataType, DataTypeDecl, DeclLocations, HttpPayload, ImportGraph, InterfaceDeclResults,
+    InterfaceSpec, Location, PropertyDecl, SharedResponses, TypeDeclResults,
+};
+
+/// Resolves references to types across namespaces of imported files.
+///
+/// A reference is either qualified (`billing.invoice`), or looked up in the namespace it is made from first,
+/// and among all namespaces then. Resolved references are rewritten to the name of the type, qualified only
+/// when several namespaces declare a type with that name.
+pub struct NamespaceResolver<'a> {
+    pub types: &'a mut TypeDeclResults,
+    pub interfaces: &'a mut InterfaceDeclResults,
+    pub shared_responses: &'a mut SharedResponses,
+    pub locations: &'a DeclLocations,
+    pub imports: &'a ImportGraph,
+}
+
+impl<'a> NamespaceResolver<'a> {
+    pub fn resolve(&mut self) -> Vec<SemanticError> {
+        let mut declared: HashMap<String, Vec<String>> = HashMap::new();
+        for type_decl in self.types.iter().flatten() {
+            if let Some(namespace) = &type_decl.namespace {
+                let namespaces = declared.entry(type_decl.name.clone()).or_default();
+                if !namespaces.contains(namespace) {
+                    namespaces.push(namespace.clone());
+                }
+            }
+        }
+        let mut lookup = Lookup {
+            declared,
+            errors: Vec::new(),
+        };
+        for (index, type_decl) in self.types.iter_mut().enumerate() {
+            let Ok(type_decl) = type_decl else {
+                continue;
+            };
+            let context = Context {
+                namespace: type_decl.namespace.clone(),
+                location: self.locations.types[index].clone(),
+            };
+            lookup.resolve_properties(&mut type_decl.property_decls, &context);
+        }
+        for (index, interface) in self.interfaces.iter_mut().enumerate() {
+            let Ok(interface) = interface else {
+                continue;
+            };
+            let location = self.locations.interfaces[index].clone();
+            let context = Context {
+                namespace: self.imports.namespaces.get(&location.file).cloned(),
+                location,
+            };
+            let InterfaceSpec::Api(api) = &mut interface.spec;
+            lookup.resolve_api(api, &context);
+            for api in interface.versions.overrides.values_mut() {
+                lookup.resolve_api(api, &context);
+            }
+        }
+        let context = Context {
+            namespace: None,
+            location: Location {
+                file: "main.yaml".to_string(),
+                index: 0,
+            },
+        };
+        let responses = self
+            .shared_responses
+            .named
+            .values_mut()
+            .chain(self.shared_responses.defaults.values_mut());
+        for response in responses.flatten() {
+            lookup.resolve_data_type_decl(response, &context);
+        }
+        lookup.errors
+    }
+}
+
+struct Context {
+    namespace: Option<String>,
+    location: Location,
+}
+
+struct Lookup {
+    /// Namespaces declaring a type, by its name.
+    declared: HashMap<String, Vec<String>>,
+    errors: Vec<SemanticError>,
+}
+
+impl Lookup {
+    fn resolve_api(&mut self, api: &mut ApiSpec, context: &Context) {
+        match &mut api.payload {
+            Some(HttpPayload::Query(properties)) | Some(HttpPayload::Body(properties)) => {
+                self.resolve_properties(properties, context)
+            }
+            None => {}
+        }
+        let responses = api.responses.iter_mut().flat_map(|val| val.values_mut());
+        for response in responses.flatten() {
+            self.resolve_data_type_decl(response, context);
+        }
+    }
+
+    fn resolve_properties(&mut self, properties: &mut [PropertyDecl], context: &Context) {
+        for property in properties {
+            if let Ok(data_type_decl) = &mut property.data_type_decl {
+                self.resolve_data_type_decl(data_type_decl, context);
+            }
+        }
+    }
+
+    fn resolve_data_type_decl(&mut self, data_type_decl: &mut DataTypeDecl, context: &Context) {
+        self.resolve_data_type(&mut data_type_decl.data_type, context);
+    }
+
+    fn resolve_data_type(&mut self, data_type: &mut DataType, context: &Context) {
+        match data_type {
+            DataType::Primitive(_) | DataType::Format(_) | DataType::Any => {}
+            DataType::Array(val) | DataType::Dict(_, val) => self.resolve_data_type(val, context),
+            DataType::ObjectDecl(type_decl) => {
+                self.resolve_properties(&mut type_decl.property_decls, context)
+            }
+            DataType::Object(reference) => match self.resolve_reference(reference, context) {
+                Ok(Some(resolved)) => *reference = resolved,
+                Ok(None) => {}
+                Err(err) => self.errors.push(err),
+            },
+        }
+    }
+
+    /// Returns resolved name, or `None` for unqualified names not declared anywhere,
+    /// which are reported as unknown types already.
+    fn resolve_reference(
+        &self,
+        reference: &str,
+        context: &Context,
+    ) -> Result<Option<String>, SemanticError> {
+        if let Some((namespace, name)) = reference.rsplit_once('.') {
+            return match self.declared.get(name) {
+                Some(namespaces) if namespaces.iter().any(|val| val == namespace) => {
+                    Ok(Some(self.canonical(namespace, name)))
+                }
+                _ => Err(SemanticError::UnknownQualifiedType {
+                    reference: reference.to_string(),
+                    location: context.location.clone(),
+                }),
+            };
+        }
+        let Some(namespaces) = self.declared.get(reference) else {
+            return Ok(None);
+        };
+        if let Some(namespace) = &context.namespace {
+            if namespaces.contains(namespace) {
+                return Ok(Some(self.canonical(namespace, reference)));
+            }
+        }
+        match namespaces.as_slice() {
+            [namespace] => Ok(Some(self.canonical(namespace, reference))),
+            _ => Err(SemanticError::AmbiguousType {
+                reference: reference.to_string(),
+                candidates: namespaces
+                    .iter()
+                    .map(|namespace| format!("{}.{}", namespace, reference))
+                    .collect(),
+                location: context.location.clone(),
+            }),
+        }
+    }
+
+    fn canonical(&self, namespace: &str, name: &str) -> String {
+        match self.declared.get(name) {
+            Some(namespaces) if namespaces.len() > 1 => format!("{}.{}", namespace, name),
+            _ => name.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::parser::namespaces::NamespaceResolver;
+    use crate::parser::semantics::SemanticError;
+    use crate::schema::{
+        ApiSpec, DataType, DataTypeDecl, DeclLocations, HttpMethod, HttpPayload, ImportError,
+        ImportGraph, InterfaceDecl, InterfaceSpec, InterfaceVersions, Location, PropertyDecl,
+        SharedResponses, TypeDecl, TypeDeclError, TypeDeclResults,
+    };
+    use crate::test_utils::TempSpec;
+
+    fn property(name: &str, reference: &str) -> PropertyDecl {
+        PropertyDecl {
+            name: name.to_string(),
+            data_type_decl: Ok(DataTypeDecl {
+                data_type: DataType::Object(reference.to_string()),
+                is_required: true,
+            }),
+        }
+    }
+
+    fn type_decl(namespace: &str, name: &str, properties: Vec<PropertyDecl>) -> TypeDecl {
+        TypeDecl {
+            name: name.to_string(),
+            namespace: Some(namespace.to_string()),
+            property_decls: properties,
+        }
+    }
+
+    fn location(file: &str) -> Location {
+        Location {
+            file: file.to_string(),
+            index: 0,
+        }
+    }
+
+    fn references(properties: &[PropertyDecl]) -> Vec<String> {
+        properties
+            .iter()
+            .map(|property| property.data_type_decl.as_ref().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut types = vec![
+            Ok(type_decl("billing", "status", vec![])),
+            Ok(type_decl("shipping", "status", vec![])),
+            Ok(type_decl("common", "money", vec![])),
+            Ok(type_decl(
+                "billing",
+                "invoice",
+                vec![
+                    property("status", "status"),
+                    property("delivery", "shipping.status"),
+                    property("total", "money"),
+                ],
+            )),
+        ];
+        let mut interfaces = vec![Ok(InterfaceDecl {
+            ident: "orders".to_string(),
+            params: vec![],
+            operation_id: "post_orders".to_string(),
+            tags: vec![],
+            group: None,
+            spec: InterfaceSpec::Api(ApiSpec {
+                method: HttpMethod::Post,
+                payload: Some(HttpPayload::Body(vec![
+                    property("status", "status"),
+                    property("invoice", "billing.invoice"),
+                    property("refund", "refunds.status"),
+                ])),
+                responses: None,
+                inferred_status_code: None,
+            }),
+            versions: InterfaceVersions::default(),
+        })];
+        let locations = DeclLocations {
+            types: vec![
+                location("billing.yml"),
+                location("shipping.yml"),
+                location("common.yml"),
+                location("billing.yml"),
+            ],
+            interfaces: vec![location("orders.yml")],
+        };
+        let imports = ImportGraph {
+            edges: HashMap::new(),
+            namespaces: HashMap::from([("orders.yml".to_string(), "orders".to_string())]),
+            ..Default::default()
+        };
+        let mut resolver = NamespaceResolver {
+            types: &mut types,
+            interfaces: &mut interfaces,
+            shared_responses: &mut SharedResponses::default(),
+            locations: &locations,
+            imports: &imports,
+        };
+
+        let errors = resolver.resolve();
+
+        assert_eq!(
+            vec![
+                SemanticError::AmbiguousType {
+                    reference: "status".to_string(),
+                    candidates: vec!["billing.status".to_string(), "shipping.status".to_string()],
+                    location: location("orders.yml"),
+                },
+                SemanticError::UnknownQualifiedType {
+                    reference: "refunds.status".to_string(),
+                    location: location("orders.yml"),
+                },
+            ],
+            errors
+        );
+        let invoice = types[3].as_ref().unwrap();
+        assert_eq!(
+            vec!["billing.status", "shipping.status", "money"],
+            references(&invoice.property_decls)
+        );
+        let InterfaceSpec::Api(api) = &interfaces[0].as_ref().unwrap().spec;
+        let Some(HttpPayload::Body(body)) = &api.payload else {
+            panic!("Expect to have a body");
+        };
+        assert_eq!(
+            vec!["status", "invoice", "refunds.status"],
+            references(body)
+        );
+    }
+
+    /// Errors of resolving references between the types, declared in the files.
+    fn resolve_types(types: &mut TypeDeclResults, files: &[&str]) -> Vec<SemanticError> {
+        let locations = DeclLocations {
+            types: files.iter().map(|file| location(file)).collect(),
+            interfaces: vec![],
+        };
+        let mut resolver = NamespaceResolver {
+            types,
+            interfaces: &mut vec![],
+            shared_responses: &mut SharedResponses::default(),
+            locations: &locations,
+            imports: &ImportGraph::default(),
+        };
+        resolver.resolve()
+    }
+
+    #[test]
+    fn test_ambiguous_reference() {
+        let mut types = vec![
+            Ok(type_decl("billing", "status", vec![])),
+            Ok(type_decl("shipping", "status", vec![])),
+            Ok(type_decl("common", "money", vec![])),
+            Ok(type_decl(
+                "billing",
+                "invoice",
+                vec![property("status", "status"), property("total", "money")],
+            )),
+            Ok(type_decl(
+                "common",
+                "order",
+                vec![
+                    property("status", "status"),
+                    property("delivery", "shipping.status"),
+                ],
+            )),
+        ];
+
+        let errors = resolve_types(
+            &mut types,
+            &[
+                "billing.yml",
+                "shipping.yml",
+                "common.yml",
+                "billing.yml",
+                "common.yml",
+            ],
+        );
+
+        assert_eq!(
+            vec![SemanticError::AmbiguousType {
+                reference: "status".to_string(),
+                candidates: vec!["billing.status".to_string(), "shipping.status".to_string()],
+                location: location("common.yml"),
+            }],
+            errors
+        );
+        let invoice = types[3].as_ref().unwrap();
+        assert_eq!(
+            vec!["billing.status", "money"],
+            references(&invoice.property_decls)
+        );
+        let order = types[4].as_ref().unwrap();
+        assert_eq!(
+            vec!["status", "shipping.status"],
+            references(&order.property_decls)
+        );
+    }
+
+    #[test]
+    fn test_unknown_namespace() {
+        let mut types = vec![
+            Ok(type_decl("common", "money", vec![])),
+            Ok(type_decl(
+                "billing",
+                "invoice",
+                vec![
+                    property("refund", "refunds.money"),
+                    property("fee", "common.fee"),
+                    property("total", "common.money"),
+                ],
+            )),
+        ];
+
+        let errors = resolve_types(&mut types, &["common.yml", "billing.yml"]);
+
+        let unknown = |reference: &str| SemanticError::UnknownQualifiedType {
+            reference: reference.to_string(),
+            location: location("billing.yml"),
+        };
+        assert_eq!(
+            vec![unknown("refunds.money"), unknown("common.fee")],
+            errors
+        );
+        let invoice = types[1].as_ref().unwrap();
+        assert_eq!(
+            vec!["refunds.money", "common.fee", "money"],
+            references(&invoice.property_decls)
+        );
+    }
+
+    #[test]
+    fn test_selection_of_missing_type() {
+        let spec = TempSpec::new(
+            "namespaces-selection",
+            &[
+                (
+                    "main.yaml",
+                    "hosts:\n  prod: https://acme.com\ntypes:\n  _import:\n    - file: common.yml\n      only: [money, tax]\ninterfaces:\n  _import: interfaces.yml\n",
+                ),
+                ("common.yml", "money:\n  amount: int\naddress:\n  street: str\n"),
+                (
+                    "interfaces.yml",
+                    "declarations:\n  - path: pay\n    method: get\n    response: money\n",
+                ),
+            ],
+        );
+
+        let schema = spec.parse();
+
+        let names: Vec<String> = schema
+            .types
+            .iter()
+            .map(|result| match result {
+                Ok(type_decl) => type_decl.qualified_name(),
+                Err(err) => format!("error: {}", err),
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                "common.money".to_string(),
+                format!(
+                    "error: {}",
+                    TypeDeclError::ImportFailure(ImportError::UnknownSelection(
+                        "common.yml".to_string(),
+                        "tax".to_string()
+                    ))
+                ),
+            ],
+            names
+        );
+        assert_eq!(location("common.yml"), schema.locations.types[1]);
+    }
+}
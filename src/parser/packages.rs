@@ -0,0 +1,354 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::fs;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+use yaml_rust::Yaml;
+
+use crate::parser::utils::{as_str_or, read_yaml};
+use crate::schema::Package;
+
+/// Directory with vendored packages, relative to the root document.
+pub const MODULES_DIR: &str = "isle_modules";
+/// Lockfile pinning versions and checksums of vendored packages, next to the root document.
+pub const LOCKFILE: &str = "isle.lock";
+/// Manifest of a package, in its directory.
+pub const PACKAGE_MANIFEST: &str = "package.yml";
+
+/// Reads packages required by `packages` section of the root document, and verifies vendored copies
+/// against the lockfile.
+pub struct PackagesParser<'a> {
+    pub main: &'a Yaml,
+    pub root_path: &'a str,
+}
+
+impl<'a> PackagesParser<'a> {
+    pub fn parse(&self) -> Result<Vec<Package>, PackagesError> {
+        let required = self.required()?;
+        if required.is_empty() {
+            return Ok(Vec::new());
+        }
+        let lockfile = Path::new(self.root_path).join(LOCKFILE);
+        let locked =
+            read_yaml(&lockfile.to_string_lossy()).map_err(|_| PackagesError::MissingLockfile)?;
+        let locked = locked.first().cloned().unwrap_or(Yaml::Null);
+        let mut packages = Vec::new();
+        for (name, version) in required {
+            let package = self.vendored(&name, &version)?;
+            let locked_package = &locked[name.as_str()];
+            if locked_package.is_badvalue() {
+                return Err(PackagesError::NotLocked(name));
+            }
+            let locked_version = version_from(&locked_package["version"]);
+            let locked_checksum = locked_package["checksum"].as_str();
+            if locked_version.as_deref() != Some(version.as_str())
+                || locked_checksum != Some(package.checksum.as_str())
+            {
+                return Err(PackagesError::LockMismatch(name));
+            }
+            packages.push(package);
+        }
+        Ok(packages)
+    }
+
+    /// Reads vendored packages and writes the lockfile pinning them.
+    pub fn lock(&self) -> Result<Vec<Package>, PackagesError> {
+        let mut packages = Vec::new();
+        for (name, version) in self.required()? {
+            packages.push(self.vendored(&name, &version)?);
+        }
+        let mut contents = "# Generated by `arc-isle <path> lock`, do not edit.\n".to_string();
+        for package in &packages {
+            contents.push_str(&format!(
+                "{}:\n  version: \"{}\"\n  checksum: {}\n",
+                package.name, package.version, package.checksum
+            ));
+        }
+        fs::write(Path::new(self.root_path).join(LOCKFILE), contents)
+            .map_err(|err| PackagesError::ReadFailure(LOCKFILE.to_string(), err.to_string()))?;
+        Ok(packages)
+    }
+
+    fn required(&self) -> Result<Vec<(String, String)>, PackagesError> {
+        let raw_packages = match &self.main["packages"] {
+            Yaml::BadValue => return Ok(Vec::new()),
+            Yaml::Hash(val) => val,
+            _ => return Err(PackagesError::UnsupportedSection),
+        };
+        let mut required = Vec::new();
+        for (key, value) in raw_packages {
+            let name = as_str_or(key, PackagesError::UnsupportedSection)?;
+            if !is_package_name(&name) {
+                return Err(PackagesError::InvalidName(name));
+            }
+            let version = version_from(value)
+                .filter(|version| is_version(version))
+                .ok_or(PackagesError::InvalidVersion(name.clone()))?;
+            required.push((name, version));
+        }
+        Ok(required)
+    }
+
+    fn vendored(&self, name: &str, version: &str) -> Result<Package, PackagesError> {
+        let directory = Path::new(self.root_path).join(MODULES_DIR).join(name);
+        if !directory.is_dir() {
+            return Err(PackagesError::MissingPackage(name.to_string()));
+        }
+        let manifest = read_yaml(&directory.join(PACKAGE_MANIFEST).to_string_lossy())
+            .map_err(|_| PackagesError::MissingManifest(name.to_string()))?;
+        let vendored_version = manifest
+            .first()
+            .and_then(|manifest| version_from(&manifest["version"]))
+            .ok_or(PackagesError::MissingManifest(name.to_string()))?;
+        if vendored_version != version {
+            return Err(PackagesError::VersionMismatch {
+                name: name.to_string(),
+                required: version.to_string(),
+                vendored: vendored_version,
+            });
+        }
+        Ok(Package {
+            name: name.to_string(),
+            version: vendored_version,
+            checksum: checksum(&directory)?,
+        })
+    }
+}
+
+/// Names become directories under `MODULES_DIR` and keys in the lockfile, so no separators or dots.
+fn is_package_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn is_version(version: &str) -> bool {
+    !version.is_empty()
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | '_'))
+}
+
+fn version_from(raw: &Yaml) -> Option<String> {
+    match raw {
+        Yaml::String(val) | Yaml::Real(val) => Some(val.to_string()),
+        Yaml::Integer(val) => Some(val.to_string()),
+        _ => None,
+    }
+}
+
+/// SHA-256 of all files in a directory, with their paths, in sorted order.
+pub fn checksum(directory: &Path) -> Result<String, PackagesError> {
+    let mut files = Vec::new();
+    collect_files(directory, directory, &mut files)?;
+    files.sort();
+    let mut hasher = Sha256::new();
+    for file in files {
+        let contents = fs::read(directory.join(&file))
+            .map_err(|err| PackagesError::ReadFailure(file.clone(), err.to_string()))?;
+        hasher.update(file.as_bytes());
+        hasher.update([0]);
+        hasher.update(&contents);
+        hasher.update([0]);
+    }
+    let digest: String = hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    Ok(format!("sha256:{}", digest))
+}
+
+fn collect_files(
+    root: &Path,
+    directory: &Path,
+    files: &mut Vec<String>,
+) -> Result<(), PackagesError> {
+    let read_failure = |err: std::io::Error| {
+        PackagesError::ReadFailure(directory.to_string_lossy().to_string(), err.to_string())
+    };
+    for entry in fs::read_dir(directory).map_err(read_failure)? {
+        let path = entry.map_err(read_failure)?.path();
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            files.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(())
+}
+
+pub enum PackagesError {
+    UnsupportedSection,
+    InvalidName(String),
+    InvalidVersion(String),
+    MissingPackage(String),
+    MissingManifest(String),
+    MissingLockfile,
+    NotLocked(String),
+    LockMismatch(String),
+    VersionMismatch {
+        name: String,
+        required: String,
+        vendored: String,
+    },
+    ReadFailure(String, String),
+}
+
+impl PackagesError {
+    fn default_fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PackagesError::UnsupportedSection => {
+                write!(
+                    f,
+                    "'packages' should be a hashmap of package names to versions."
+                )
+            }
+            PackagesError::InvalidName(name) => write!(
+                f,
+                "Invalid package name {}, only letters, digits, '_' and '-' are allowed",
+                name
+            ),
+            PackagesError::InvalidVersion(name) => {
+                write!(f, "Invalid version of package {}", name)
+            }
+            PackagesError::MissingPackage(name) => {
+                write!(f, "Package {} is not vendored in {}", name, MODULES_DIR)
+            }
+            PackagesError::MissingManifest(name) => write!(
+                f,
+                "Package {} has no {} with its version",
+                name, PACKAGE_MANIFEST
+            ),
+            PackagesError::MissingLockfile => write!(
+                f,
+                "Missing {}, run `arc-isle <path> lock` to create it",
+                LOCKFILE
+            ),
+            PackagesError::NotLocked(name) => write!(
+                f,
+                "Package {} is not in {}, run `arc-isle <path> lock` to add it",
+                name, LOCKFILE
+            ),
+            PackagesError::LockMismatch(name) => write!(
+                f,
+                "Vendored package {} does not match its version or checksum in {}",
+                name, LOCKFILE
+            ),
+            PackagesError::VersionMismatch {
+                name,
+                required,
+                vendored,
+            } => write!(
+                f,
+                "Package {} {} is required, but {} is vendored",
+                name, required, vendored
+            ),
+            PackagesError::ReadFailure(path, err) => {
+                write!(f, "Failed to access {}: {}", path, err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PackagesError {}
+
+impl Display for PackagesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+impl Debug for PackagesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use yaml_rust::YamlLoader;
+
+    use crate::parser::packages::{PackagesError, PackagesParser};
+    use crate::test_utils::TempSpec;
+
+    fn write_package(name: &str, version: &str) -> TempSpec {
+        let manifest = format!("version: {}\n", version);
+        TempSpec::new(
+            &format!("packages-{}", name),
+            &[
+                ("isle_modules/common/package.yml", &manifest),
+                ("isle_modules/common/money.yml", "money:\n  amount: int\n"),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_lock_and_verify() {
+        let spec = write_package("lock", "1.2.0");
+        let main = YamlLoader::load_from_str("packages:\n  common: 1.2.0").unwrap();
+        let parser = PackagesParser {
+            main: &main[0],
+            root_path: spec.path(),
+        };
+        assert!(matches!(
+            parser.parse(),
+            Err(PackagesError::MissingLockfile)
+        ));
+
+        let locked = parser.lock().unwrap();
+        let packages = parser.parse().unwrap();
+        assert_eq!(locked, packages);
+        assert_eq!("1.2.0", packages[0].version);
+        assert!(packages[0].checksum.starts_with("sha256:"));
+
+        spec.write(&[(
+            "isle_modules/common/money.yml",
+            "money:\n  amount: double\n",
+        )]);
+        assert!(
+            matches!(parser.parse(), Err(PackagesError::LockMismatch(name)) if name == "common")
+        );
+    }
+
+    #[test]
+    fn test_version_mismatch() {
+        let spec = write_package("version", "1.3.0");
+        let main = YamlLoader::load_from_str("packages:\n  common: 1.2.0").unwrap();
+        let parser = PackagesParser {
+            main: &main[0],
+            root_path: spec.path(),
+        };
+        assert!(matches!(
+            parser.lock(),
+            Err(PackagesError::VersionMismatch { vendored, .. }) if vendored == "1.3.0"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        let spec = write_package("names", "1.2.0");
+        for source in [
+            "packages:\n  ../common: 1.2.0",
+            "packages:\n  acme/common: 1.2.0",
+            "packages:\n  'common: x': 1.2.0",
+        ] {
+            let main = YamlLoader::load_from_str(source).unwrap();
+            let parser = PackagesParser {
+                main: &main[0],
+                root_path: spec.path(),
+            };
+            assert!(matches!(parser.lock(), Err(PackagesError::InvalidName(_))));
+        }
+        let main = YamlLoader::load_from_str("packages:\n  common: '1.2\"'").unwrap();
+        let parser = PackagesParser {
+            main: &main[0],
+            root_path: spec.path(),
+        };
+        assert!(matches!(
+            parser.lock(),
+            Err(PackagesError::InvalidVersion(_))
+        ));
+    }
+}
@@ -0,0 +1,240 @@
+use crate::parser::types::{TypeDeclSource, TypeParser};
+use crate::parser::utils::{as_str_or, YamlHash};
+use crate::schema::{
+    DataType, DataTypeDecl, InterfaceDeclError, SchemaWarning, SharedResponses, StatusCode,
+    TypeUsageMeta,
+};
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+use yaml_rust::Yaml;
+
+pub struct SharedResponsesParser<'a> {
+    pub main: &'a Yaml,
+    pub types_usage: &'a mut HashMap<String, TypeUsageMeta>,
+    pub warnings: &'a mut Vec<SchemaWarning>,
+}
+
+impl<'a> SharedResponsesParser<'a> {
+    pub fn parse(&mut self) -> Result<SharedResponses, SharedResponsesError> {
+        let mut shared_responses = SharedResponses::default();
+        if let Some(raw_named) = self.section("responses")? {
+            for (key, value) in raw_named {
+                let name = as_str_or(key, SharedResponsesError::UnsupportedKeyType)?;
+                let mut parser = ResponseParser {
+                    types_usage: self.types_usage,
+                    shared_responses: &shared_responses,
+                };
+                let source = TypeDeclSource::NamedResponse(name.clone());
+                let response = parser
+                    .parse(&name, value, source)
+                    .map_err(|err| SharedResponsesError::InvalidResponse(name.clone(), err))?;
+                shared_responses.named.insert(name, response);
+            }
+        }
+        if let Some(raw_defaults) = self.section("default_responses")? {
+            let mut defaults = HashMap::new();
+            for (key, value) in raw_defaults {
+                let status_code = status_code_from(key).map_err(|err| {
+                    SharedResponsesError::InvalidResponse("default_responses".to_string(), err)
+                })?;
+                let mut parser = ResponseParser {
+                    types_usage: self.types_usage,
+                    shared_responses: &shared_responses,
+                };
+                let source = TypeDeclSource::NamedResponse(status_code.to_string());
+                let response = parser
+                    .parse(&status_code.to_string(), value, source)
+                    .map_err(|err| {
+                        SharedResponsesError::InvalidResponse(status_code.to_string(), err)
+                    })?;
+                insert_response(&mut defaults, status_code.clone(), response).map_err(|err| {
+                    SharedResponsesError::InvalidResponse("default_responses".to_string(), err)
+                })?;
+            }
+            self.warnings
+                .extend(shadowed_status_codes("default_responses", &defaults));
+            shared_responses.defaults = defaults;
+        }
+        Ok(shared_responses)
+    }
+
+    fn section(&self, key: &str) -> Result<Option<&'a YamlHash>, SharedResponsesError> {
+        match &self.main[key] {
+            Yaml::BadValue => Ok(None),
+            Yaml::Hash(val) => Ok(Some(val)),
+            _ => Err(SharedResponsesError::UnsupportedSection(key.to_string())),
+        }
+    }
+}
+
+pub struct ResponseParser<'a> {
+    pub types_usage: &'a mut HashMap<String, TypeUsageMeta>,
+    pub shared_responses: &'a SharedResponses,
+}
+
+impl<'a> ResponseParser<'a> {
+    pub fn parse(
+        &mut self,
+        name: &str,
+        value: &Yaml,
+        source: TypeDeclSource,
+    ) -> Result<Option<DataTypeDecl>, InterfaceDeclError> {
+        match value {
+            Yaml::Null => Ok(None),
+            Yaml::String(val) if val.starts_with('$') => {
+                let reference = &val[1..];
+                self.shared_responses
+                    .named
+                    .get(reference)
+                    .cloned()
+                    .ok_or(InterfaceDeclError::ResponseNotFound(reference.to_string()))
+            }
+            Yaml::String(_) => {
+                let mut parser = TypeParser {
+                    key: name,
+                    value: &YamlHash::new(),
+                    types_usage: self.types_usage,
+                    source,
+                };
+                parser
+                    .make_data_type_decl(value, name)
+                    .map(Some)
+                    .map_err(|_| InterfaceDeclError::InvalidResponseTypeDeclaration)
+            }
+            Yaml::Hash(val) => {
+                let mut parser = TypeParser {
+                    key: name,
+                    value: val,
+                    types_usage: self.types_usage,
+                    source,
+                };
+                parser
+                    .parse()
+                    .map(|type_decl| {
+                        Some(DataTypeDecl {
+                            data_type: DataType::ObjectDecl(type_decl),
+                            is_required: true,
+                        })
+                    })
+                    .map_err(|_| InterfaceDeclError::InvalidResponseTypeDeclaration)
+            }
+            _ => Err(InterfaceDeclError::InvalidResponseDeclaration),
+        }
+    }
+
+    /// Returns whether a response value has content, following references to named responses.
+    pub fn has_content(&self, value: &Yaml) -> bool {
+        match value {
+            Yaml::Null => false,
+            Yaml::String(val) if val.starts_with('$') => self
+                .shared_responses
+                .named
+                .get(&val[1..])
+                .map_or(true, |response| response.is_some()),
+            _ => true,
+        }
+    }
+}
+
+pub fn status_code_from(key: &Yaml) -> Result<StatusCode, InterfaceDeclError> {
+    match key {
+        Yaml::Integer(val) => fixed_status_code(*val),
+        Yaml::String(val) if val == "default" => Ok(StatusCode::Default),
+        Yaml::String(val) => match val.parse::<i64>() {
+            Ok(code) => fixed_status_code(code),
+            Err(_) => as_status_code_pattern(val),
+        },
+        _ => Err(InterfaceDeclError::InvalidKey),
+    }
+}
+
+fn fixed_status_code(code: i64) -> Result<StatusCode, InterfaceDeclError> {
+    if !(100..=599).contains(&code) {
+        return Err(InterfaceDeclError::InvalidStatusCode);
+    }
+    Ok(StatusCode::Fixed(code as u16))
+}
+
+fn as_status_code_pattern(key: &str) -> Result<StatusCode, InterfaceDeclError> {
+    let chars: Vec<char> = key.chars().collect();
+    let is_pattern = chars.len() == 3
+        && chars[1..].iter().all(|c| *c == 'x' || *c == 'X')
+        && ('1'..='5').contains(&chars[0]);
+    if !is_pattern {
+        return Err(InterfaceDeclError::InvalidStatusCode);
+    }
+    let num = chars[0]
+        .to_digit(10)
+        .ok_or(InterfaceDeclError::InvalidStatusCode)?;
+    Ok(StatusCode::Prefix(num as u16))
+}
+
+/// Inserts a response, failing if the status code has been declared already.
+pub fn insert_response(
+    responses: &mut HashMap<StatusCode, Option<DataTypeDecl>>,
+    status_code: StatusCode,
+    response: Option<DataTypeDecl>,
+) -> Result<(), InterfaceDeclError> {
+    if responses.contains_key(&status_code) {
+        return Err(InterfaceDeclError::DuplicateStatusCode(status_code));
+    }
+    responses.insert(status_code, response);
+    Ok(())
+}
+
+pub fn shadowed_status_codes(
+    location: &str,
+    responses: &HashMap<StatusCode, Option<DataTypeDecl>>,
+) -> Vec<SchemaWarning> {
+    let mut warnings = Vec::new();
+    for fixed in responses.keys() {
+        for pattern in responses.keys() {
+            if pattern.covers(fixed) {
+                warnings.push(SchemaWarning::ShadowedStatusCode {
+                    location: location.to_string(),
+                    fixed: fixed.clone(),
+                    pattern: pattern.clone(),
+                });
+            }
+        }
+    }
+    warnings.sort_by_key(|warning| match warning {
+        SchemaWarning::ShadowedStatusCode { fixed, .. } => fixed.as_key(),
+        _ => String::new(),
+    });
+    warnings
+}
+
+pub enum SharedResponsesError {
+    UnsupportedSection(String),
+    UnsupportedKeyType,
+    InvalidResponse(String, InterfaceDeclError),
+}
+
+impl SharedResponsesError {
+    fn default_fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SharedResponsesError::UnsupportedSection(key) => {
+                write!(f, "'{}' should be a hashmap.", key)
+            }
+            SharedResponsesError::UnsupportedKeyType => write!(f, "Response name must be string."),
+            SharedResponsesError::InvalidResponse(name, err) => {
+                write!(f, "Invalid response '{}': {:?}", name, err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SharedResponsesError {}
+
+impl Display for SharedResponsesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+impl Debug for SharedResponsesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+
+use crate::schema::{
+    DeclLocations, HttpMethod, InterfaceDeclResults, InterfaceSpec, Location, SchemaWarning,
+    TypeDeclResults,
+};
+
+/// Checks declarations against each other, once all of them are parsed.
+pub struct SemanticChecker<'a> {
+    pub types: &'a TypeDeclResults,
+    pub interfaces: &'a InterfaceDeclResults,
+    pub locations: &'a DeclLocations,
+}
+
+impl<'a> SemanticChecker<'a> {
+    pub fn check(&self, warnings: &mut Vec<SchemaWarning>) -> Vec<SemanticError> {
+        let mut list = Vec::new();
+        self.check_types(&mut list);
+        self.check_interfaces(&mut list, warnings);
+        list
+    }
+
+    fn check_types(&self, list: &mut Vec<SemanticError>) {
+        let mut declared: HashMap<String, &Location> = HashMap::new();
+        for (index, type_decl) in self.types.iter().enumerate() {
+            let Ok(type_decl) = type_decl else {
+                continue;
+            };
+            let location = &self.locations.types[index];
+            let name = type_decl.qualified_name();
+            match declared.get(&name) {
+                Some(first) => list.push(SemanticError::TypeRedeclared {
+                    name,
+                    first: (*first).clone(),
+                    second: location.clone(),
+                }),
+                None => {
+                    declared.insert(name, location);
+                }
+            }
+        }
+    }
+
+    fn check_interfaces(&self, list: &mut Vec<SemanticError>, warnings: &mut Vec<SchemaWarning>) {
+        let mut operation_ids: HashMap<&str, &Location> = HashMap::new();
+        let mut routes: Vec<(&HttpMethod, &str, &Location)> = Vec::new();
+        for (index, interface) in self.interfaces.iter().enumerate() {
+            let Ok(decl) = interface else {
+                continue;
+            };
+            let location = &self.locations.interfaces[index];
+            let InterfaceSpec::Api(api) = &decl.spec;
+            match operation_ids.get(decl.operation_id.as_str()) {
+                Some(first) => list.push(SemanticError::OperationIdReused {
+                    operation_id: decl.operation_id.clone(),
+                    first: (*first).clone(),
+                    second: location.clone(),
+                }),
+                None => {
+                    operation_ids.insert(&decl.operation_id, location);
+                }
+            }
+            for (method, ident, first) in &routes {
+                if **method != api.method {
+                    continue;
+                }
+                match route_overlap(ident, &decl.ident) {
+                    RouteOverlap::None => {}
+                    RouteOverlap::Same => list.push(SemanticError::DuplicateRoute {
+                        method: api.method.clone(),
+                        ident: decl.ident.clone(),
+                        first: (*first).clone(),
+                        second: location.clone(),
+                    }),
+                    RouteOverlap::Ambiguous => warnings.push(SchemaWarning::AmbiguousRoute {
+                        method: api.method.clone(),
+                        first: (ident.to_string(), (*first).clone()),
+                        second: (decl.ident.clone(), location.clone()),
+                    }),
+                }
+            }
+            routes.push((&api.method, &decl.ident, location));
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum RouteOverlap {
+    None,
+    Same,
+    Ambiguous,
+}
+
+/// Compares paths segment by segment, treating any parameter as matching any segment.
+fn route_overlap(lhs: &str, rhs: &str) -> RouteOverlap {
+    let lhs: Vec<_> = lhs.split('/').filter(|val| !val.is_empty()).collect();
+    let rhs: Vec<_> = rhs.split('/').filter(|val| !val.is_empty()).collect();
+    if lhs.len() != rhs.len() {
+        return RouteOverlap::None;
+    }
+    let mut overlap = RouteOverlap::Same;
+    for (lhs, rhs) in lhs.iter().zip(rhs.iter()) {
+        match (is_param(lhs), is_param(rhs)) {
+            (true, true) => {}
+            (false, false) if lhs == rhs => {}
+            (false, false) => return RouteOverlap::None,
+            _ => overlap = RouteOverlap::Ambiguous,
+        }
+    }
+    overlap
+}
+
+fn is_param(segment: &str) -> bool {
+    segment.starts_with('{') && segment.ends_with('}')
+}
+
+#[derive(PartialEq)]
+pub enum SemanticError {
+    TypeRedeclared {
+        name: String,
+        first: Location,
+        second: Location,
+    },
+    OperationIdReused {
+        operation_id: String,
+        first: Location,
+        second: Location,
+    },
+    DuplicateRoute {
+        method: HttpMethod,
+        ident: String,
+        first: Location,
+        second: Location,
+    },
+    UnknownQualifiedType {
+        reference: String,
+        location: Location,
+    },
+    AmbiguousType {
+        reference: String,
+        candidates: Vec<String>,
+        location: Location,
+    },
+}
+
+impl SemanticError {
+    fn default_fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SemanticError::TypeRedeclared {
+                name,
+                first,
+                second,
+            } => write!(
+                f,
+                "Type `{}` is declared in {} and again in {}",
+                name, first, second
+            ),
+            SemanticError::OperationIdReused {
+                operation_id,
+                first,
+                second,
+            } => write!(
+                f,
+                "Operation `{}` is declared in {} and again in {}",
+                operation_id, first, second
+            ),
+            SemanticError::DuplicateRoute {
+                method,
+                ident,
+                first,
+                second,
+            } => write!(
+                f,
+                "Route {} {} is declared in {} and again in {}",
+                method, ident, first, second
+            ),
+            SemanticError::UnknownQualifiedType {
+                reference,
+                location,
+            } => write!(f, "Unknown type `{}` in {}", reference, location),
+            SemanticError::AmbiguousType {
+                reference,
+                candidates,
+                location,
+            } => write!(
+                f,
+                "Type `{}` in {} may refer to any of {}",
+                reference,
+                location,
+                candidates.join(", ")
+            ),
+        }
+    }
+}
+
+impl Display for SemanticError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+impl Debug for SemanticError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+pub struct SemanticErrors {
+    pub list: Vec<SemanticError>,
+}
+
+impl SemanticErrors {
+    fn default_fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let list: Vec<_> = self.list.iter().map(|err| err.to_string()).collect();
+        f.write_str(&list.join("\n"))
+    }
+}
+
+impl std::error::Error for SemanticErrors {}
+
+impl Display for SemanticErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+impl Debug for SemanticErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::semantics::{route_overlap, RouteOverlap, SemanticChecker, SemanticError};
+    use crate::schema::{
+        ApiSpec, DeclLocations, HttpMethod, InterfaceDecl, InterfaceSpec, InterfaceVersions,
+        Location, SchemaWarning, TypeDecl,
+    };
+
+    fn location(file: &str, index: usize) -> Location {
+        Location {
+            file: file.to_string(),
+            index,
+        }
+    }
+
+    fn interface(method: HttpMethod, ident: &str, operation_id: &str) -> InterfaceDecl {
+        InterfaceDecl {
+            ident: ident.to_string(),
+            params: vec![],
+            operation_id: operation_id.to_string(),
+            tags: vec![],
+            group: None,
+            spec: InterfaceSpec::Api(ApiSpec {
+                method,
+                payload: None,
+                responses: None,
+                inferred_status_code: None,
+            }),
+            versions: InterfaceVersions::default(),
+        }
+    }
+
+    #[test]
+    fn test_route_overlap() {
+        assert_eq!(
+            RouteOverlap::Same,
+            route_overlap("users/{id}", "users/{user_id}")
+        );
+        assert_eq!(
+            RouteOverlap::Ambiguous,
+            route_overlap("users/{id}", "users/me")
+        );
+        assert_eq!(
+            RouteOverlap::None,
+            route_overlap("users/{id}", "users/{id}/posts")
+        );
+        assert_eq!(RouteOverlap::None, route_overlap("users/me", "posts/me"));
+    }
+
+    #[test]
+    fn test_duplicate_types() {
+        let type_decl = |name: &str| {
+            Ok(TypeDecl {
+                name: name.to_string(),
+                namespace: Some("types".to_string()),
+                property_decls: vec![],
+            })
+        };
+        let types = vec![type_decl("user"), type_decl("post"), type_decl("user")];
+        let locations = DeclLocations {
+            types: vec![
+                location("a.yml", 0),
+                location("a.yml", 1),
+                location("b.yml", 0),
+            ],
+            interfaces: vec![],
+        };
+        let checker = SemanticChecker {
+            types: &types,
+            interfaces: &vec![],
+            locations: &locations,
+        };
+
+        let errors = checker.check(&mut Vec::new());
+
+        assert_eq!(
+            vec![SemanticError::TypeRedeclared {
+                name: "types.user".to_string(),
+                first: location("a.yml", 0),
+                second: location("b.yml", 0),
+            }],
+            errors
+        );
+    }
+
+    #[test]
+    fn test_duplicate_interfaces() {
+        let interfaces = vec![
+            Ok(interface(HttpMethod::Get, "users/{id}", "get_user")),
+            Ok(interface(HttpMethod::Get, "users/me", "get_me")),
+            Ok(interface(HttpMethod::Delete, "users/{id}", "delete_user")),
+            Ok(interface(HttpMethod::Get, "users/{user_id}", "fetch_user")),
+            Ok(interface(HttpMethod::Post, "users", "get_me")),
+        ];
+        let locations = DeclLocations {
+            types: vec![],
+            interfaces: (0..5).map(|index| location("users.yml", index)).collect(),
+        };
+        let checker = SemanticChecker {
+            types: &vec![],
+            interfaces: &interfaces,
+            locations: &locations,
+        };
+        let mut warnings = Vec::new();
+
+        let errors = checker.check(&mut warnings);
+
+        assert_eq!(
+            vec![
+                SemanticError::DuplicateRoute {
+                    method: HttpMethod::Get,
+                    ident: "users/{user_id}".to_string(),
+                    first: location("users.yml", 0),
+                    second: location("users.yml", 3),
+                },
+                SemanticError::OperationIdReused {
+                    operation_id: "get_me".to_string(),
+                    first: location("users.yml", 1),
+                    second: location("users.yml", 4),
+                },
+            ],
+            errors
+        );
+        assert_eq!(
+            vec![
+                SchemaWarning::AmbiguousRoute {
+                    method: HttpMethod::Get,
+                    first: ("users/{id}".to_string(), location("users.yml", 0)),
+                    second: ("users/me".to_string(), location("users.yml", 1)),
+                },
+                SchemaWarning::AmbiguousRoute {
+                    method: HttpMethod::Get,
+                    first: ("users/me".to_string(), location("users.yml", 1)),
+                    second: ("users/{user_id}".to_string(), location("users.yml", 3)),
+                },
+            ],
+            warnings
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use crate::parser::interfaces::parse_method;
+use crate::parser::utils::as_str_or;
+use crate::schema::{ExtensionMethods, StatusCode, StatusCodeInference, StatusCodeRule};
+use std::fmt::{Debug, Display, Formatter};
+use yaml_rust::Yaml;
+
+pub struct StatusCodeInferenceParser<'a> {
+    pub main: &'a Yaml,
+    pub extension_methods: &'a ExtensionMethods,
+}
+
+impl<'a> StatusCodeInferenceParser<'a> {
+    pub fn parse(&self) -> Result<StatusCodeInference, StatusCodeInferenceError> {
+        let raw_rules: &Yaml = &self.main["status_code_inference"];
+        if raw_rules.is_badvalue() {
+            return Ok(StatusCodeInference::default());
+        }
+        let raw_rules = raw_rules
+            .as_vec()
+            .ok_or(StatusCodeInferenceError::RulesNotAList)?;
+        let mut rules = Vec::new();
+        for raw_rule in raw_rules {
+            rules.push(self.rule(raw_rule)?);
+        }
+        Ok(StatusCodeInference { rules })
+    }
+
+    fn rule(&self, raw_rule: &Yaml) -> Result<StatusCodeRule, StatusCodeInferenceError> {
+        if raw_rule.as_hash().is_none() {
+            return Err(StatusCodeInferenceError::RulesNotAList);
+        }
+        let method = match &raw_rule["method"] {
+            Yaml::BadValue => None,
+            raw_method => {
+                let raw_method = as_str_or(raw_method, StatusCodeInferenceError::InvalidMethod)?;
+                let method = parse_method(&raw_method, self.extension_methods)
+                    .map_err(|_| StatusCodeInferenceError::InvalidMethod)?;
+                Some(method)
+            }
+        };
+        let has_content = self.flag(&raw_rule["content"], "content")?;
+        let is_async = self.flag(&raw_rule["async"], "async")?;
+        let status_code = raw_rule["code"]
+            .as_i64()
+            .filter(|val| (100..=599).contains(val))
+            .and_then(|val| u16::try_from(val).ok())
+            .map(StatusCode::Fixed)
+            .ok_or(StatusCodeInferenceError::MissingCode)?;
+        Ok(StatusCodeRule {
+            method,
+            has_content,
+            is_async,
+            status_code,
+        })
+    }
+
+    fn flag(&self, raw: &Yaml, key: &str) -> Result<Option<bool>, StatusCodeInferenceError> {
+        match raw {
+            Yaml::BadValue => Ok(None),
+            Yaml::Boolean(val) => Ok(Some(*val)),
+            _ => Err(StatusCodeInferenceError::InvalidFlag(key.to_string())),
+        }
+    }
+}
+
+pub enum StatusCodeInferenceError {
+    RulesNotAList,
+    InvalidMethod,
+    InvalidFlag(String),
+    MissingCode,
+}
+
+impl StatusCodeInferenceError {
+    fn default_fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatusCodeInferenceError::RulesNotAList => {
+                write!(f, "'status_code_inference' should be a list of rules.")
+            }
+            StatusCodeInferenceError::InvalidMethod => {
+                write!(f, "Invalid 'method' in status code inference rule.")
+            }
+            StatusCodeInferenceError::InvalidFlag(key) => {
+                write!(f, "'{}' in status code inference rule should be a boolean.", key)
+            }
+            StatusCodeInferenceError::MissingCode => {
+                write!(f, "Missing or invalid 'code' in status code inference rule.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for StatusCodeInferenceError {}
+
+impl Display for StatusCodeInferenceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+impl Debug for StatusCodeInferenceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
@@ -1,7 +1,7 @@
-use crate::parser::imports::detect;
-use crate::parser::utils::as_str_or;
+use crate::parser::imports::{ImportResults, SourceImport};
+use crate::parser::utils::{allowed_lints, as_str_or, ALLOW_KEY};
 use crate::schema::{
-    DataType, DataTypeDecl, ImportError, Primitive, PropertyDecl, StatusCode, TypeDecl,
+    DataType, DataTypeDecl, Format, ImportError, Location, Primitive, PropertyDecl, StatusCode, TypeDecl,
     TypeDeclError, TypeDeclResults, TypeUsageMeta, UnknownType,
 };
 use std::collections::HashMap;
@@ -10,27 +10,22 @@ use yaml_rust::Yaml;
 use crate::parser::utils::YamlHash;
 
 pub struct TypesParser<'a> {
-    pub parent_path: &'a str,
     pub types_usage: &'a mut HashMap<String, TypeUsageMeta>,
+    pub locations: &'a mut Vec<Location>,
+    pub suppressions: &'a mut Vec<Vec<String>>,
 }
 
 impl<'a> TypesParser<'a> {
-    pub fn parse(&mut self, main: Yaml) -> Result<TypeDeclResults, TypeDeclError> {
+    pub fn parse(&mut self, sources: ImportResults) -> Result<TypeDeclResults, TypeDeclError> {
         let mut results = Vec::new();
-        let mut sources = Vec::new();
-        let inner: Option<&YamlHash> = main.as_hash();
-        let inner = inner.ok_or(TypeDeclError::ImportFailure(
-            ImportError::InvalidInputSource,
-        ))?;
-        let imports = detect(inner, self.parent_path);
-        for i in imports {
-            sources.push(i);
-        }
-        sources.insert(0, Ok(main));
         for source in sources {
             match source {
                 Ok(source) => self.parse_composed_source(&source, &mut results)?,
-                Err(err) => results.push(Err(TypeDeclError::ImportFailure(err.clone()))),
+                Err(failure) => {
+                    self.locations.push(Location { file: failure.importer, index: 0 });
+                    self.suppressions.push(Vec::new());
+                    results.push(Err(TypeDeclError::ImportFailure(failure.error)))
+                }
             }
         }
         Ok(results)
@@ -38,27 +33,44 @@ impl<'a> TypesParser<'a> {
 
     fn parse_composed_source(
         &mut self,
-        source: &Yaml,
+        source: &SourceImport,
         output: &mut TypeDeclResults,
     ) -> Result<(), TypeDeclError> {
-        let source = source
+        let hash = source
+            .imported_source
             .as_hash()
             .ok_or(TypeDeclError::UnsupportedTypeDeclaration)?;
-        for (i, e) in source.iter().enumerate() {
+        for (i, e) in hash.iter().enumerate() {
             let (key, value) = e;
             let key = as_str_or(key, TypeDeclError::UnsupportedKeyType)?;
-            if key == "_import" {
+            if key == "_import" || key == "_namespace" {
+                continue;
+            }
+            if source.only.as_ref().is_some_and(|only| !only.contains(&key)) {
                 continue;
             }
             let mut object_parser = TypeParser {
                 key: &key,
-                value: &value.as_hash().unwrap(),
-                types_usage: &mut self.types_usage,
+                value: value.as_hash().unwrap(),
+                types_usage: self.types_usage,
                 source: TypeDeclSource::Type(i),
             };
-            let result = object_parser.parse();
+            let result = object_parser.parse().map(|type_decl| TypeDecl {
+                namespace: Some(source.namespace.clone()),
+                ..type_decl
+            });
+            self.locations.push(Location { file: source.path.clone(), index: i });
+            self.suppressions.push(value.as_hash().map(allowed_lints).unwrap_or_default());
             output.push(result);
         }
+        for name in source.only.iter().flatten() {
+            if !hash.contains_key(&Yaml::String(name.to_string())) {
+                let error = ImportError::UnknownSelection(source.path.clone(), name.to_string());
+                self.locations.push(Location { file: source.path.clone(), index: 0 });
+                self.suppressions.push(Vec::new());
+                output.push(Err(TypeDeclError::ImportFailure(error)));
+            }
+        }
         Ok(())
     }
 }
@@ -68,6 +80,7 @@ pub enum TypeDeclSource {
     Type(usize),
     InterfaceInput(usize),
     InterfaceOutput(usize, StatusCode),
+    NamedResponse(String),
 }
 
 pub struct TypeParser<'a> {
@@ -82,6 +95,9 @@ impl<'a> TypeParser<'a> {
         let mut property_decls = Vec::new();
         for (property_name, property_type) in self.value.iter() {
             let property_name = as_str_or(property_name, TypeDeclError::UnsupportedKeyType)?;
+            if property_name == ALLOW_KEY {
+                continue;
+            }
             let data_type_decl = self.make_data_type_decl(property_type, &property_name);
             let property_decl = PropertyDecl {
                 name: property_name,
@@ -92,11 +108,12 @@ impl<'a> TypeParser<'a> {
         self.types_usage.insert(self.key.to_string(), None);
         Ok(TypeDecl {
             name: self.key.to_string(),
+            namespace: None,
             property_decls,
         })
     }
 
-    fn make_data_type_decl(
+    pub fn make_data_type_decl(
         &mut self,
         raw_type: &Yaml,
         property_name: &str,
@@ -112,13 +129,15 @@ impl<'a> TypeParser<'a> {
         if string_value.is_empty() {
             return Err(TypeDeclError::EmptyTypeDeclaration);
         }
-        let chars: Vec<char> = string_value.chars().collect();
+        // Spacing is insignificant, like in `dict[str,int]` or `array[ str ]`.
+        let chars: Vec<char> = string_value.chars().filter(|c| !c.is_whitespace()).collect();
         let mut last_read_index = 0;
         let mut type_name = String::new();
         while last_read_index < chars.len()
             && (chars[last_read_index].is_alphabetic()
                 || chars[last_read_index] == '_'
-                || last_read_index > 0 && chars[last_read_index].is_numeric())
+                || last_read_index > 0 && chars[last_read_index].is_numeric()
+                || last_read_index > 0 && chars[last_read_index] == '.')
         {
             type_name.push(chars[last_read_index]);
             last_read_index += 1;
@@ -165,35 +184,37 @@ impl<'a> TypeParser<'a> {
             types_usage: self.types_usage,
             source: self.source.clone(),
         };
-        let object_decl = parser
+        parser
             .parse()
             .map(|val| DataTypeDecl {
                 data_type: DataType::ObjectDecl(val),
                 is_required: true,
             })
-            .map_err(|_| TypeDeclError::UnsupportedTypeDeclaration);
-        object_decl
+            .map_err(|_| TypeDeclError::UnsupportedTypeDeclaration)
     }
 
     fn make_data_type(
         &mut self,
         type_name: &str,
-        subtypes: &Vec<String>,
+        subtypes: &[String],
     ) -> Result<DataType, TypeDeclError> {
-        match self.make_primitive(type_name) {
-            Ok(primitive) => return Ok(DataType::Primitive(primitive)),
-            Err(_) => {}
-        };
+        if let Ok(primitive) = self.make_primitive(type_name) {
+            return Ok(DataType::Primitive(primitive));
+        }
         match type_name {
+            "array" if subtypes.is_empty() => Ok(DataType::Array(Box::new(DataType::Any))),
+            "dict" if subtypes.is_empty() => {
+                Ok(DataType::Dict(Primitive::Str, Box::new(DataType::Any)))
+            }
             "array" => {
                 let contained_type = self.make_data_type(&subtypes[0], &Vec::new())?;
                 Ok(DataType::Array(Box::new(contained_type)))
             }
             "dict" => self.make_dict_data_type(subtypes),
-            "date_iso8601" => Ok(DataType::Primitive(Primitive::Str)),
-            "url" => Ok(DataType::Primitive(Primitive::Str)),
-            "timestamp" => Ok(DataType::Primitive(Primitive::Int)),
-            "uuid" => Ok(DataType::Primitive(Primitive::Str)),
+            "date_iso8601" => Ok(DataType::Format(Format::DateIso8601)),
+            "url" => Ok(DataType::Format(Format::Url)),
+            "timestamp" => Ok(DataType::Format(Format::Timestamp)),
+            "uuid" => Ok(DataType::Format(Format::Uuid)),
             other => {
                 self.handle_if_unknown_type(other);
                 Ok(DataType::Object(other.to_string()))
@@ -202,32 +223,37 @@ impl<'a> TypeParser<'a> {
     }
 
     fn handle_if_unknown_type(&mut self, type_name: &str) {
+        // Qualified names are resolved once all namespaces are known.
+        if type_name.contains('.') {
+            return;
+        }
         let meta = self.types_usage.get_mut(type_name);
         let make_unknown = || match &self.source {
             TypeDeclSource::Type(i) => UnknownType::InTypeDeclaration(*i, 0),
             TypeDeclSource::InterfaceInput(i) => UnknownType::InPayload(*i, 0),
             TypeDeclSource::InterfaceOutput(i, code) => UnknownType::InResponse(*i, code.clone(), 0),
+            TypeDeclSource::NamedResponse(name) => UnknownType::InNamedResponse(name.clone(), 0),
         };
         match meta {
-            Some(val) => match val {
-                Some(val) => {
+            Some(val) => {
+                if let Some(val) = val {
                     val.push(make_unknown());
                 }
-                None => {}
-            },
+            }
             None => {
                 self.types_usage.insert(type_name.to_string(), Some(vec![make_unknown()]));
             }
         }
     }
 
-    fn make_dict_data_type(&mut self, subtypes: &Vec<String>) -> Result<DataType, TypeDeclError> {
+    fn make_dict_data_type(&mut self, subtypes: &[String]) -> Result<DataType, TypeDeclError> {
         let key = self.make_primitive(&subtypes[0])?;
         let mut value_type_name: &str = &subtypes[1];
         let value_subtypes: Vec<String>;
         if let Some(mut start_index) = value_type_name.find("[") {
             value_type_name = &value_type_name[..start_index];
-            value_subtypes = self.subtypes(&subtypes[1].chars().collect(), &mut start_index)?;
+            value_subtypes =
+                self.subtypes(&subtypes[1].chars().collect::<Vec<_>>(), &mut start_index)?;
         } else {
             value_subtypes = Vec::new();
         }
@@ -235,7 +261,7 @@ impl<'a> TypeParser<'a> {
         Ok(DataType::Dict(key, Box::new(value)))
     }
 
-    fn subtypes(&self, chars: &Vec<char>, index: &mut usize) -> Result<Vec<String>, TypeDeclError> {
+    fn subtypes(&self, chars: &[char], index: &mut usize) -> Result<Vec<String>, TypeDeclError> {
         let mut _i = *index;
         let mut subtypes: Vec<String> = Vec::new();
         if chars[_i] == '[' {
@@ -254,7 +280,7 @@ impl<'a> TypeParser<'a> {
                 if chars[_i] == ',' {
                     subtypes.push(subtype_value.clone());
                     subtype_value = String::new();
-                    _i += 2;
+                    _i += 1;
                 }
                 if chars[_i] == '[' {
                     n_open_braces += 1;
@@ -473,7 +499,7 @@ mod tests {
         let value = Yaml::Hash(hash);
         let mut parser = TypeParser {
             key: &key,
-            value: &value.as_hash().unwrap(),
+            value: value.as_hash().unwrap(),
             types_usage: &mut HashMap::new(),
             source: TypeDeclSource::Type(0),
         };
@@ -485,6 +511,7 @@ mod tests {
         let expected = DataTypeDecl {
             data_type: DataType::ObjectDecl(TypeDecl {
                 name: "nested_object".to_string(),
+                namespace: None,
                 property_decls: Vec::from([
                     PropertyDecl {
                         name: "id".to_string(),
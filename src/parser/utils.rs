@@ -1,5 +1,8 @@
+use std::collections::HashMap;
 use std::{fs, io};
-use yaml_rust::{Yaml, YamlEmitter, YamlLoader};
+use yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust::scanner::Marker;
+use yaml_rust::{ScanError, Yaml, YamlEmitter, YamlLoader};
 
 pub type YamlHash = yaml_rust::yaml::Hash;
 
@@ -12,8 +15,11 @@ impl std::error::Error for ReadError {
 }
 
 impl std::fmt::Display for ReadError {
-    fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.internal_error {
+            either::Either::Left(err) => write!(f, "{}", err),
+            either::Either::Right(err) => write!(f, "{}", err)
+        }
     }
 }
 
@@ -42,9 +48,74 @@ pub fn read_yaml(file_path: &str) -> Result<Vec<Yaml>, ReadError> {
         .map_err(|err| ReadError { internal_error: either::Either::Left(err) })?;
     let yaml: Vec<Yaml> = YamlLoader::load_from_str(&file_contents)
         .map_err(|err| ReadError { internal_error: either::Either::Right(err) })?;
+    check_duplicate_keys(&file_contents)
+        .map_err(|err| ReadError { internal_error: either::Either::Right(err) })?;
     Ok(yaml)
 }
 
+/// Fails on the first key repeated within a mapping, which YAML loader would silently overwrite.
+pub fn check_duplicate_keys(contents: &str) -> Result<(), ScanError> {
+    let mut receiver = DuplicateKeysReceiver { frames: Vec::new(), duplicate: None };
+    let mut parser = Parser::new(contents.chars());
+    parser.load(&mut receiver, true)?;
+    match receiver.duplicate {
+        Some((key, first, second)) => Err(ScanError::new(
+            second,
+            &format!("duplicate key `{}`, first declared at line {}", key, first.line())
+        )),
+        None => Ok(())
+    }
+}
+
+enum KeysFrame {
+    Mapping { keys: HashMap<String, Marker>, expecting_key: bool },
+    Sequence,
+}
+
+struct DuplicateKeysReceiver {
+    frames: Vec<KeysFrame>,
+    duplicate: Option<(String, Marker, Marker)>,
+}
+
+impl DuplicateKeysReceiver {
+    fn complete_node(&mut self, key: Option<(String, Marker)>) {
+        let Some(KeysFrame::Mapping { keys, expecting_key }) = self.frames.last_mut() else {
+            return;
+        };
+        if *expecting_key {
+            if let Some((key, mark)) = key {
+                match keys.get(&key) {
+                    Some(first) if self.duplicate.is_none() => {
+                        self.duplicate = Some((key, *first, mark));
+                    }
+                    Some(_) => {}
+                    None => {
+                        keys.insert(key, mark);
+                    }
+                }
+            }
+        }
+        *expecting_key = !*expecting_key;
+    }
+}
+
+impl MarkedEventReceiver for DuplicateKeysReceiver {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        match event {
+            Event::MappingStart(_) => self.frames.push(KeysFrame::Mapping { keys: HashMap::new(), expecting_key: true }),
+            Event::SequenceStart(_) => self.frames.push(KeysFrame::Sequence),
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.frames.pop();
+                self.complete_node(None);
+            }
+            Event::Scalar(value, ..) => self.complete_node(Some((value, mark))),
+            Event::Alias(_) => self.complete_node(None),
+            _ => {}
+        }
+    }
+}
+
+#[allow(dead_code)]
 pub fn serialize_to_string(yaml: &Yaml) -> String {
     let mut out_str = String::new();
     {
@@ -59,3 +130,37 @@ pub fn as_str_or<Err>(yaml: &Yaml, err: Err) -> Result<String, Err> {
     let value = value.ok_or(err)?.to_string();
     Ok(value)
 }
+
+/// Reserved key suppressing lint rules for a declaration.
+pub const ALLOW_KEY: &str = "_allow";
+
+/// Names of lint rules listed under `_allow` of a declaration, as a single name or a list.
+pub fn allowed_lints(hash: &YamlHash) -> Vec<String> {
+    match hash.get(&Yaml::String(ALLOW_KEY.to_string())) {
+        Some(Yaml::String(name)) => vec![name.clone()],
+        Some(Yaml::Array(names)) => names
+            .iter()
+            .filter_map(|name| name.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::utils::check_duplicate_keys;
+
+    #[test]
+    fn test_no_duplicate_keys() {
+        let contents = "user:\n  id: str\n  name: str\npost:\n  id: str\n  tags: [a, a]\n";
+        assert!(check_duplicate_keys(contents).is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_keys() {
+        let contents = "user:\n  id: str\n  name:\n    first: str\n  id: int\n";
+        let err = check_duplicate_keys(contents).unwrap_err();
+        assert_eq!(5, err.marker().line());
+        assert!(err.to_string().contains("duplicate key `id`, first declared at line 2"));
+    }
+}
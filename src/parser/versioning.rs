@@ -7,38 +7,71 @@ pub struct VersioningParser<'a> {
 }
 
 impl<'a> VersioningParser<'a> {
-    pub fn parse(&self) -> Result<Versioning, VersioningError> {
+    pub fn parse(&self) -> Result<Option<Versioning>, VersioningError> {
         let raw_versioning: &Yaml = &self.main["versioning"];
+        if raw_versioning.is_badvalue() {
+            return Ok(None);
+        }
         let raw_format: Option<&str> = raw_versioning["format"].as_str();
-        let raw_format = raw_format.ok_or(VersioningError::NotFound)?;
-        match raw_format {
+        let raw_format = raw_format.ok_or(VersioningError::MissingFormat)?;
+        let format = match raw_format {
             "headers" => {
                 let header = match raw_versioning["header"].as_str() {
-                    Some(header_name) => Some(header_name.to_string()),
+                    Some(header_name) => header_name.to_string(),
                     None => return Err(VersioningError::MissingHeader)
                 };
-                Ok(Versioning { format: VersioningFormat::Headers, header })
+                VersioningFormat::Headers { header }
+            },
+            "path" => {
+                let prefix = self.template(raw_versioning, "prefix", "v{version}")?;
+                VersioningFormat::Path { prefix: prefix.trim_matches('/').to_string() }
+            },
+            "query" => {
+                let parameter = raw_versioning["parameter"].as_str().unwrap_or("api-version");
+                VersioningFormat::Query { parameter: parameter.to_string() }
             },
-            other => Err(VersioningError::UnsupportedFormat(other.to_string()))
+            "media_type" => {
+                if raw_versioning["media_type"].is_badvalue() {
+                    return Err(VersioningError::MissingMediaType);
+                }
+                let media_type = self.template(raw_versioning, "media_type", "")?;
+                VersioningFormat::MediaType { media_type }
+            },
+            other => return Err(VersioningError::UnsupportedFormat(other.to_string()))
+        };
+        Ok(Some(Versioning { format }))
+    }
+
+    fn template(&self, raw_versioning: &Yaml, key: &str, default: &str) -> Result<String, VersioningError> {
+        let template = raw_versioning[key].as_str().unwrap_or(default);
+        if !template.contains("{version}") {
+            return Err(VersioningError::MissingPlaceholder(key.to_string()));
         }
+        Ok(template.to_string())
     }
 }
 
 pub enum VersioningError {
-    NotFound,
+    MissingFormat,
     UnsupportedFormat(String),
-    MissingHeader
+    MissingHeader,
+    MissingMediaType,
+    MissingPlaceholder(String)
 }
 
 impl VersioningError {
     fn default_fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            VersioningError::NotFound =>
-                write!(f, "Versioning info wasn't found in schema."),
+            VersioningError::MissingFormat =>
+                write!(f, "Missing 'format' key inside 'versioning'."),
             VersioningError::UnsupportedFormat(key) =>
                 write!(f, "'{}' format is not supported for versioning", key),
             VersioningError::MissingHeader =>
-                write!(f, "Missing 'header' key inside 'versioning'.")
+                write!(f, "Missing 'header' key inside 'versioning'."),
+            VersioningError::MissingMediaType =>
+                write!(f, "Missing 'media_type' key inside 'versioning'."),
+            VersioningError::MissingPlaceholder(key) =>
+                write!(f, "'{}' inside 'versioning' should contain '{{version}}' placeholder.", key)
         }
     }
 }
@@ -57,3 +90,75 @@ impl Debug for VersioningError {
         self.default_fmt(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use yaml_rust::YamlLoader;
+    use crate::parser::versioning::{VersioningError, VersioningParser};
+    use crate::schema::{Versioning, VersioningFormat};
+
+    fn parse(source: &str) -> Result<Option<Versioning>, VersioningError> {
+        let yaml = YamlLoader::load_from_str(source).unwrap();
+        VersioningParser { main: &yaml[0] }.parse()
+    }
+
+    #[test]
+    fn test_no_versioning() {
+        assert_eq!(parse("hosts:\n  prod: https://api.acme.com").unwrap(), None);
+    }
+
+    #[test]
+    fn test_path_format() {
+        let versioning = parse("versioning:\n  format: path\n  prefix: /api/v{version}/").unwrap().unwrap();
+        assert_eq!(versioning.format, VersioningFormat::Path { prefix: "api/v{version}".to_string() });
+        assert_eq!(versioning.versioned_ident("news", Some(2)), "api/v2/news");
+
+        let versioning = parse("versioning:\n  format: path").unwrap().unwrap();
+        assert_eq!(versioning.format, VersioningFormat::Path { prefix: "v{version}".to_string() });
+    }
+
+    #[test]
+    fn test_query_format() {
+        let versioning = parse("versioning:\n  format: query\n  parameter: v").unwrap().unwrap();
+        assert_eq!(versioning.format, VersioningFormat::Query { parameter: "v".to_string() });
+        assert_eq!(versioning.describe(Some(3)), "?v=3");
+
+        let versioning = parse("versioning:\n  format: query").unwrap().unwrap();
+        assert_eq!(versioning.format, VersioningFormat::Query { parameter: "api-version".to_string() });
+    }
+
+    #[test]
+    fn test_media_type_format() {
+        let source = "
+versioning:
+  format: media_type
+  media_type: application/vnd.acme.v{version}+json
+";
+        let versioning = parse(source).unwrap().unwrap();
+        assert_eq!(versioning.format, VersioningFormat::MediaType {
+            media_type: "application/vnd.acme.v{version}+json".to_string()
+        });
+        assert_eq!(versioning.describe(Some(1)), "Accept: application/vnd.acme.v1+json");
+        assert_eq!(versioning.versioned_ident("news", Some(1)), "news");
+
+        assert!(matches!(parse("versioning:\n  format: media_type"), Err(VersioningError::MissingMediaType)));
+        assert!(matches!(
+            parse("versioning:\n  format: media_type\n  media_type: application/json"),
+            Err(VersioningError::MissingPlaceholder(key)) if key == "media_type"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_format() {
+        assert!(matches!(
+            parse("versioning:\n  format: subdomain"),
+            Err(VersioningError::UnsupportedFormat(format)) if format == "subdomain"
+        ));
+        assert!(matches!(parse("versioning:\n  header: X-Version"), Err(VersioningError::MissingFormat)));
+        assert!(matches!(parse("versioning:\n  format: headers"), Err(VersioningError::MissingHeader)));
+        assert!(matches!(
+            parse("versioning:\n  format: path\n  prefix: api"),
+            Err(VersioningError::MissingPlaceholder(key)) if key == "prefix"
+        ));
+    }
+}
@@ -0,0 +1,389 @@
+use yaml_rust::yaml::Hash;
+use yaml_rust::Yaml;
+
+use crate::schema::{DataType, DataTypeDecl, Format, Primitive, Schema, TypeDecl};
+
+/// Nesting below which arrays and dicts are left empty and optional properties omitted,
+/// so that recursive types produce finite instances.
+const MAX_DEPTH: usize = 4;
+
+const WORDS: &[&str] = &[
+    "amber", "birch", "cedar", "delta", "ember", "fjord", "grove", "harbor", "island", "juniper",
+    "kestrel", "lagoon", "meadow", "nimbus", "orchid", "pebble", "quartz", "ridge", "summit",
+    "tundra", "umber", "valley", "willow", "yarrow", "zephyr",
+];
+
+/// Which optional properties generated instances have.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum OptionalFields {
+    #[default]
+    Include,
+    Omit,
+    /// Each one with even chance.
+    Random,
+}
+
+/// Generates instances of declared types. The same seed gives the same instances.
+pub struct SampleGenerator<'a> {
+    schema: &'a Schema,
+    state: u64,
+    pub optional_fields: OptionalFields,
+}
+
+impl<'a> SampleGenerator<'a> {
+    pub fn new(schema: &'a Schema, seed: u64) -> Self {
+        SampleGenerator {
+            schema,
+            state: seed,
+            optional_fields: OptionalFields::default(),
+        }
+    }
+
+    /// Instance of the type referenced by the name, plain or qualified, `None` if it isn't declared.
+    pub fn sample(&mut self, type_name: &str) -> Option<Yaml> {
+        let type_decl = self.schema.find_type(type_name)?;
+        Some(self.object(type_decl, 0))
+    }
+
+    /// Several instances of the type, different from each other.
+    pub fn samples(&mut self, type_name: &str, count: usize) -> Option<Vec<Yaml>> {
+        (0..count).map(|_| self.sample(type_name)).collect()
+    }
+
+    pub fn sample_data_type_decl(&mut self, data_type_decl: &DataTypeDecl) -> Yaml {
+        self.data_type(&data_type_decl.data_type, 0)
+    }
+
+    fn object(&mut self, type_decl: &TypeDecl, depth: usize) -> Yaml {
+        let mut hash = Hash::new();
+        for property in &type_decl.property_decls {
+            let Ok(data_type_decl) = &property.data_type_decl else {
+                continue;
+            };
+            if !data_type_decl.is_required && !self.includes_optional(depth) {
+                continue;
+            }
+            let value = self.data_type(&data_type_decl.data_type, depth + 1);
+            hash.insert(Yaml::String(property.name.clone()), value);
+        }
+        Yaml::Hash(hash)
+    }
+
+    fn data_type(&mut self, data_type: &DataType, depth: usize) -> Yaml {
+        match data_type {
+            DataType::Primitive(primitive) => self.primitive(primitive),
+            DataType::Format(format) => self.format(format),
+            DataType::Any => Yaml::String(self.word().to_string()),
+            DataType::Array(item_type) => {
+                let count = self.count(depth);
+                Yaml::Array(
+                    (0..count)
+                        .map(|_| self.data_type(item_type, depth + 1))
+                        .collect(),
+                )
+            }
+            DataType::Dict(key_type, value_type) => {
+                let mut hash = Hash::new();
+                for _ in 0..self.count(depth) {
+                    let key = Yaml::String(self.key(key_type));
+                    let value = self.data_type(value_type, depth + 1);
+                    hash.insert(key, value);
+                }
+                Yaml::Hash(hash)
+            }
+            DataType::ObjectDecl(type_decl) => self.object(type_decl, depth),
+            // Only types requiring themselves get this deep, and have no valid instances anyway.
+            DataType::Object(_) if depth > MAX_DEPTH * 2 => Yaml::Null,
+            DataType::Object(name) => match self.schema.find_type(name) {
+                Some(type_decl) => self.object(type_decl, depth),
+                None => Yaml::Null,
+            },
+        }
+    }
+
+    fn includes_optional(&mut self, depth: usize) -> bool {
+        match self.optional_fields {
+            _ if depth >= MAX_DEPTH => false,
+            OptionalFields::Include => true,
+            OptionalFields::Omit => false,
+            OptionalFields::Random => self.below(2) == 1,
+        }
+    }
+
+    fn primitive(&mut self, primitive: &Primitive) -> Yaml {
+        match primitive {
+            Primitive::Int => Yaml::Integer(self.below(1000) as i64),
+            Primitive::Double => Yaml::Real(format!("{}.{:02}", self.below(1000), self.below(100))),
+            Primitive::Bool => Yaml::Boolean(self.below(2) == 1),
+            Primitive::Str => Yaml::String(self.word().to_string()),
+        }
+    }
+
+    fn format(&mut self, format: &Format) -> Yaml {
+        match format {
+            Format::Uuid => Yaml::String(self.uuid()),
+            Format::Url => Yaml::String(format!(
+                "https://example.com/{}/{}",
+                self.word(),
+                self.below(1000)
+            )),
+            Format::DateIso8601 => Yaml::String(format!(
+                "{}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+                2020 + self.below(6),
+                1 + self.below(12),
+                1 + self.below(28),
+                self.below(24),
+                self.below(60),
+                self.below(60)
+            )),
+            Format::Timestamp => Yaml::Integer(1_600_000_000 + self.below(200_000_000) as i64),
+        }
+    }
+
+    /// Random UUID, version 4.
+    fn uuid(&mut self) -> String {
+        let high = self.next() & 0xffff_ffff_ffff_0fff | 0x4000;
+        let low = self.next() & 0x3fff_ffff_ffff_ffff | 0x8000_0000_0000_0000;
+        format!(
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            high >> 32,
+            (high >> 16) & 0xffff,
+            high & 0xffff,
+            low >> 48,
+            low & 0xffff_ffff_ffff
+        )
+    }
+
+    fn key(&mut self, key_type: &Primitive) -> String {
+        match key_type {
+            Primitive::Str => self.word().to_string(),
+            Primitive::Int => self.below(1000).to_string(),
+            Primitive::Double => format!("{}.5", self.below(1000)),
+            Primitive::Bool => (self.below(2) == 1).to_string(),
+        }
+    }
+
+    fn count(&mut self, depth: usize) -> usize {
+        match depth >= MAX_DEPTH {
+            true => 0,
+            false => 1 + self.below(3) as usize,
+        }
+    }
+
+    fn word(&mut self) -> &'static str {
+        WORDS[self.below(WORDS.len() as u64) as usize]
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+
+    /// SplitMix64, small and good enough for sample data.
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        value ^ (value >> 31)
+    }
+}
+
+/// Compact JSON of a value, as produced by the generator or parsed from JSON.
+pub fn to_json(value: &Yaml) -> String {
+    match value {
+        Yaml::Hash(hash) => {
+            let entries: Vec<String> = hash
+                .iter()
+                .map(|(key, value)| {
+                    let key = match key {
+                        Yaml::String(key) => key.clone(),
+                        key => to_json(key),
+                    };
+                    format!("{}:{}", to_json(&Yaml::String(key)), to_json(value))
+                })
+                .collect();
+            format!("{{{}}}", entries.join(","))
+        }
+        Yaml::Array(items) => {
+            let items: Vec<String> = items.iter().map(to_json).collect();
+            format!("[{}]", items.join(","))
+        }
+        Yaml::String(value) => {
+            let mut result = String::from("\"");
+            for c in value.chars() {
+                match c {
+                    '"' => result.push_str("\\\""),
+                    '\\' => result.push_str("\\\\"),
+                    '\n' => result.push_str("\\n"),
+                    '\t' => result.push_str("\\t"),
+                    c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+                    c => result.push(c),
+                }
+            }
+            result.push('"');
+            result
+        }
+        Yaml::Integer(value) => value.to_string(),
+        Yaml::Real(value) => value.clone(),
+        Yaml::Boolean(value) => value.to_string(),
+        _ => "null".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sample::{to_json, OptionalFields, SampleGenerator, MAX_DEPTH};
+    use crate::test_utils::TempSpec;
+    use crate::validation::InstanceValidator;
+
+    #[test]
+    fn test_sample() {
+        let schema = TempSpec::with_main(
+            "sample",
+            "user:\n  id: uuid\n  name: str\n  site: url?\n  born: date_iso8601\n  seen: timestamp\n  scores: dict[str, array[double]]\n  friends: array[user]?\n  manager: user?\n",
+            "declarations:\n  - path: users\n    method: get\n    response: array[user]\n",
+        )
+        .parse();
+        let validator = InstanceValidator { schema: &schema };
+
+        let sample = SampleGenerator::new(&schema, 42).sample("user").unwrap();
+        let mut generator = SampleGenerator::new(&schema, 42);
+        generator.optional_fields = OptionalFields::Omit;
+        let samples = generator.samples("user", 3).unwrap();
+
+        assert_eq!(
+            Ok(()),
+            validator
+                .validate("user", &sample)
+                .map_err(|err| err.to_string())
+        );
+        assert_eq!(
+            to_json(&sample),
+            to_json(&SampleGenerator::new(&schema, 42).sample("user").unwrap())
+        );
+        for sample in &samples {
+            assert!(validator.validate("user", sample).is_ok());
+            assert!(sample["site"].is_badvalue() && sample["manager"].is_badvalue());
+        }
+        assert_ne!(to_json(&samples[0]), to_json(&samples[1]));
+        assert_ne!(
+            to_json(&sample),
+            to_json(&SampleGenerator::new(&schema, 7).sample("user").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_random_optional_fields() {
+        let schema = TempSpec::with_main(
+            "sample-random",
+            "user:\n  id: uuid\n  site: url?\n  nick: str?\n",
+            "declarations:\n  - path: users\n    method: get\n    response: array[user]\n",
+        )
+        .parse();
+        let validator = InstanceValidator { schema: &schema };
+        let mut generator = SampleGenerator::new(&schema, 42);
+        generator.optional_fields = OptionalFields::Random;
+
+        let samples = generator.samples("user", 20).unwrap();
+
+        for sample in &samples {
+            assert!(validator.validate("user", sample).is_ok());
+            assert!(!sample["id"].is_badvalue());
+        }
+        let with_site = samples
+            .iter()
+            .filter(|sample| !sample["site"].is_badvalue())
+            .count();
+        assert!(0 < with_site && with_site < samples.len());
+        assert!(samples
+            .iter()
+            .any(|sample| sample["site"].is_badvalue() != sample["nick"].is_badvalue()));
+    }
+
+    #[test]
+    fn test_dict_keys() {
+        let schema = TempSpec::with_main(
+            "sample-dict-keys",
+            "stats:\n  counts: dict[int, str]\n  weights: dict[double, int]\n  flags: dict[bool, str]\n",
+            "declarations:\n  - path: stats\n    method: get\n    response: stats\n",
+        )
+        .parse();
+        let validator = InstanceValidator { schema: &schema };
+
+        for seed in 0..10 {
+            let sample = SampleGenerator::new(&schema, seed).sample("stats").unwrap();
+
+            assert_eq!(
+                Ok(()),
+                validator
+                    .validate("stats", &sample)
+                    .map_err(|err| err.to_string())
+            );
+            let keys = |name: &str| -> Vec<String> {
+                let hash = sample[name].as_hash().unwrap();
+                assert!(!hash.is_empty());
+                hash.keys()
+                    .map(|key| key.as_str().unwrap().to_string())
+                    .collect()
+            };
+            assert!(keys("counts").iter().all(|key| key.parse::<i64>().is_ok()));
+            assert!(keys("weights").iter().all(|key| key.parse::<f64>().is_ok()));
+            assert!(keys("flags")
+                .iter()
+                .all(|key| key == "true" || key == "false"));
+        }
+    }
+
+    #[test]
+    fn test_recursion_limit() {
+        let schema = TempSpec::with_main(
+            "sample-recursion",
+            "user:\n  id: str\n  manager: user\n  reports: array[user]\n",
+            "declarations:\n  - path: users\n    method: get\n    response: user\n",
+        )
+        .parse();
+
+        let sample = SampleGenerator::new(&schema, 42).sample("user").unwrap();
+
+        // Required references are followed until twice the depth limit, then cut with null.
+        let mut depth = 0;
+        let mut user = &sample;
+        while !user.is_null() {
+            assert!(user["id"].as_str().is_some());
+            user = &user["manager"];
+            depth += 1;
+        }
+        assert_eq!(depth, MAX_DEPTH * 2 + 1);
+        assert!(sample["manager"]["manager"]["manager"]["reports"]
+            .as_vec()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_uuid() {
+        let schema = TempSpec::with_main(
+            "sample-uuid",
+            "user:\n  id: uuid\n",
+            "declarations:\n  - path: users\n    method: get\n    response: user\n",
+        )
+        .parse();
+
+        for seed in 0..50 {
+            let uuid = SampleGenerator::new(&schema, seed).uuid();
+
+            let groups: Vec<&str> = uuid.split('-').collect();
+            let lengths: Vec<usize> = groups.iter().map(|group| group.len()).collect();
+            assert_eq!(lengths, vec![8, 4, 4, 4, 12]);
+            assert!(uuid
+                .chars()
+                .all(|c| c == '-' || c.is_ascii_hexdigit() && !c.is_ascii_uppercase()));
+            assert!(groups[2].starts_with('4'), "version of {}", uuid);
+            assert!(
+                groups[3].starts_with(['8', '9', 'a', 'b']),
+                "variant of {}",
+                uuid
+            );
+        }
+    }
+}
@@ -2,16 +2,133 @@
 
 use crate::parser::utils::ReadError;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     error::Error,
     fmt::{Debug, Display, Formatter},
 };
 
 pub struct Schema {
     pub hosts: Hosts,
-    pub versioning: Versioning,
+    pub versioning: Option<Versioning>,
+    pub extension_methods: ExtensionMethods,
+    pub status_code_inference: StatusCodeInference,
+    pub shared_responses: SharedResponses,
     pub types: TypeDeclResults,
     pub interfaces: InterfaceDeclResults,
+    pub locations: DeclLocations,
+    pub suppressions: Suppressions,
+    pub imports: ImportGraph,
+    pub packages: Vec<Package>,
+    pub warnings: Vec<SchemaWarning>,
+}
+
+/// Vendored package of shared declarations, verified against the lockfile.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    pub checksum: String,
+}
+
+/// Files imported by each file of the schema, starting from the root document.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ImportGraph {
+    pub edges: HashMap<String, Vec<String>>,
+    /// Namespace of each imported file.
+    pub namespaces: HashMap<String, String>,
+    /// Files imported as modules, declaring types and interfaces in sections of their own.
+    pub modules: HashSet<String>,
+}
+
+impl ImportGraph {
+    pub fn add_edge(&mut self, importer: &str, imported: &str) {
+        let imports = self.edges.entry(importer.to_string()).or_default();
+        if !imports.iter().any(|val| val == imported) {
+            imports.push(imported.to_string());
+        }
+    }
+}
+
+/// Place of a declaration: file it comes from and its position there.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Location {
+    pub file: String,
+    pub index: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (#{})", self.file, self.index + 1)
+    }
+}
+
+/// Locations of `Schema.types` and `Schema.interfaces`, by the same indices.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DeclLocations {
+    pub types: Vec<Location>,
+    pub interfaces: Vec<Location>,
+}
+
+/// Lint rules allowed by `_allow` of `Schema.types` and `Schema.interfaces`, by the same indices.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Suppressions {
+    pub types: Vec<Vec<String>>,
+    pub interfaces: Vec<Vec<String>>,
+}
+
+impl Schema {
+    /// Type declared under the name, as referenced from other declarations: plain or qualified.
+    pub fn find_type(&self, name: &str) -> Option<&TypeDecl> {
+        let mut types = self.types.iter().flatten();
+        if name.contains('.') {
+            types.find(|type_decl| type_decl.qualified_name() == name)
+        } else {
+            types.find(|type_decl| type_decl.name == name)
+        }
+    }
+
+    /// Qualified names of types referenced by interfaces, directly or through other types.
+    pub fn used_types(&self) -> HashSet<String> {
+        let mut used = HashSet::new();
+        let mut pending: Vec<&DataType> = Vec::new();
+        for decl in self.interfaces.iter().flatten() {
+            let InterfaceSpec::Api(base) = &decl.spec;
+            for api in std::iter::once(base).chain(decl.versions.overrides.values()) {
+                if let Some(HttpPayload::Query(properties) | HttpPayload::Body(properties)) =
+                    &api.payload
+                {
+                    pending.extend(properties_data_types(properties));
+                }
+                let responses = api.responses.iter().flat_map(|val| val.values()).flatten();
+                pending.extend(responses.map(|decl| &decl.data_type));
+            }
+        }
+        while let Some(data_type) = pending.pop() {
+            match data_type {
+                DataType::Array(val) | DataType::Dict(_, val) => pending.push(val),
+                DataType::ObjectDecl(type_decl) => {
+                    pending.extend(properties_data_types(&type_decl.property_decls))
+                }
+                DataType::Object(name) => {
+                    let Some(type_decl) = self.find_type(name) else {
+                        continue;
+                    };
+                    if used.insert(type_decl.qualified_name()) {
+                        pending.extend(properties_data_types(&type_decl.property_decls));
+                    }
+                }
+                DataType::Primitive(_) | DataType::Format(_) | DataType::Any => {}
+            }
+        }
+        used
+    }
+}
+
+fn properties_data_types(properties: &[PropertyDecl]) -> impl Iterator<Item = &DataType> {
+    properties
+        .iter()
+        .filter_map(|property| property.data_type_decl.as_ref().ok())
+        .map(|decl| &decl.data_type)
 }
 
 impl Display for Schema {
@@ -19,6 +136,18 @@ impl Display for Schema {
         let mut result = "Schema {\n".to_string();
         result.push_str(&format!("  hosts = {:?}\n", self.hosts));
         result.push_str(&format!("  versioning = {:?}\n", self.versioning));
+        result.push_str(&format!(
+            "  extension_methods = {:?}\n",
+            self.extension_methods
+        ));
+        result.push_str(&format!(
+            "  status_code_inference = {:?}\n",
+            self.status_code_inference
+        ));
+        result.push_str(&format!(
+            "  shared_responses = {:?}\n",
+            self.shared_responses
+        ));
         result.push_str(&format!(
             "  types = {}\n",
             self.types
@@ -47,23 +176,189 @@ impl Display for Schema {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Host {
     pub env: String,
     pub address: String,
+    pub description: Option<String>,
+    pub variables: Vec<HostVariable>,
+    /// Paths of interfaces deployed to the host, `None` when all of them are.
+    pub interfaces: Option<Vec<String>>,
+    /// API versions deployed to the host, `None` when all of them are.
+    pub versions: Option<Vec<u32>>,
+}
+
+impl Host {
+    pub fn new(env: String, address: String) -> Self {
+        Self {
+            env,
+            address,
+            description: None,
+            variables: Vec::new(),
+            interfaces: None,
+            versions: None,
+        }
+    }
+
+    /// Names of variables used in the address template, in order of appearance.
+    pub fn template_variables(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut rest = self.address.as_str();
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}') else {
+                break;
+            };
+            names.push(rest[start + 1..start + end].to_string());
+            rest = &rest[start + end + 1..];
+        }
+        names
+    }
+
+    /// Address with variables substituted by given values, falling back to their defaults.
+    pub fn resolve_address(&self, values: &HashMap<String, String>) -> Result<String, String> {
+        let mut address = self.address.clone();
+        for name in self.template_variables() {
+            let variable = self.variables.iter().find(|variable| variable.name == name);
+            let value = values
+                .get(&name)
+                .or(variable.and_then(|variable| variable.default.as_ref()))
+                .ok_or(name.clone())?;
+            if let Some(allowed) = variable.and_then(|variable| variable.values.as_ref()) {
+                if !allowed.contains(value) {
+                    return Err(name);
+                }
+            }
+            address = address.replace(&format!("{{{}}}", name), value);
+        }
+        Ok(address)
+    }
+
+    pub fn deploys(&self, ident: &str, version: Option<u32>) -> bool {
+        let has_interface = self
+            .interfaces
+            .as_ref()
+            .map_or(true, |interfaces| interfaces.iter().any(|val| val == ident));
+        let has_version = match (&self.versions, version) {
+            (Some(versions), Some(version)) => versions.contains(&version),
+            _ => true,
+        };
+        has_interface && has_version
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct HostVariable {
+    pub name: String,
+    pub values: Option<Vec<String>>,
+    pub default: Option<String>,
+    pub description: Option<String>,
 }
 
 pub type Hosts = Vec<Host>;
 
-#[derive(Debug)]
+/// Way the API version is passed by clients, templates contain `{version}` placeholder.
+#[derive(Debug, PartialEq, Clone)]
 pub enum VersioningFormat {
-    Headers,
+    Headers { header: String },
+    Path { prefix: String },
+    Query { parameter: String },
+    MediaType { media_type: String },
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Versioning {
     pub format: VersioningFormat,
-    pub header: Option<String>,
+}
+
+impl Versioning {
+    /// Path of an interface with the version prefix, if the version is passed in the path.
+    /// Without a concrete version the `{version}` placeholder is kept.
+    pub fn versioned_ident(&self, ident: &str, version: Option<u32>) -> String {
+        match &self.format {
+            VersioningFormat::Path { prefix } => {
+                format!("{}/{}", fill_version(prefix, version), ident)
+            }
+            _ => ident.to_string(),
+        }
+    }
+
+    pub fn describe(&self, version: Option<u32>) -> String {
+        let value = version.map_or("{version}".to_string(), |val| val.to_string());
+        match &self.format {
+            VersioningFormat::Headers { header } => format!("{}: {}", header, value),
+            VersioningFormat::Path { prefix } => format!("/{}/...", fill_version(prefix, version)),
+            VersioningFormat::Query { parameter } => format!("?{}={}", parameter, value),
+            VersioningFormat::MediaType { media_type } => {
+                format!("Accept: {}", fill_version(media_type, version))
+            }
+        }
+    }
+}
+
+fn fill_version(template: &str, version: Option<u32>) -> String {
+    match version {
+        Some(version) => template.replace("{version}", &version.to_string()),
+        None => template.to_string(),
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct StatusCodeInference {
+    pub rules: Vec<StatusCodeRule>,
+}
+
+impl StatusCodeInference {
+    pub fn infer(&self, method: &HttpMethod, has_content: bool, is_async: bool) -> StatusCode {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(method, has_content, is_async))
+            .map(|rule| rule.status_code.clone())
+            .unwrap_or(StatusCode::Fixed(200))
+    }
+}
+
+impl Default for StatusCodeInference {
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                StatusCodeRule {
+                    method: None,
+                    has_content: None,
+                    is_async: Some(true),
+                    status_code: StatusCode::Fixed(202),
+                },
+                StatusCodeRule {
+                    method: Some(HttpMethod::Post),
+                    has_content: Some(true),
+                    is_async: None,
+                    status_code: StatusCode::Fixed(201),
+                },
+                StatusCodeRule {
+                    method: Some(HttpMethod::Delete),
+                    has_content: Some(false),
+                    is_async: None,
+                    status_code: StatusCode::Fixed(204),
+                },
+            ],
+        }
+    }
+}
+
+/// Condition under which an untagged response gets `status_code`, unset fields match anything.
+#[derive(Debug, PartialEq, Clone)]
+pub struct StatusCodeRule {
+    pub method: Option<HttpMethod>,
+    pub has_content: Option<bool>,
+    pub is_async: Option<bool>,
+    pub status_code: StatusCode,
+}
+
+impl StatusCodeRule {
+    pub fn matches(&self, method: &HttpMethod, has_content: bool, is_async: bool) -> bool {
+        self.method.as_ref().map_or(true, |val| val == method)
+            && self.has_content.map_or(true, |val| val == has_content)
+            && self.is_async.map_or(true, |val| val == is_async)
+    }
 }
 
 pub type TypeDeclResults = Vec<Result<TypeDecl, TypeDeclError>>;
@@ -71,9 +366,20 @@ pub type TypeDeclResults = Vec<Result<TypeDecl, TypeDeclError>>;
 #[derive(PartialEq, Clone, Debug)]
 pub struct TypeDecl {
     pub name: String,
+    /// Namespace of the file declaring the type, `None` for nested object declarations.
+    pub namespace: Option<String>,
     pub property_decls: Vec<PropertyDecl>,
 }
 
+impl TypeDecl {
+    pub fn qualified_name(&self) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{}.{}", namespace, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
 impl Display for TypeDecl {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut result = format!("type `{}` {{ ", self.name);
@@ -112,7 +418,7 @@ impl TypeDeclError {
     fn default_fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         match self {
             TypeDeclError::ImportFailure(import_error) => {
-                write!(f, "Import failed: {}", import_error.to_string())
+                write!(f, "Import failed: {}", import_error)
             }
             TypeDeclError::UnsupportedTypeDeclaration => {
                 write!(f, "This type declaration format is not supported.")
@@ -147,7 +453,7 @@ impl Display for DataTypeDecl {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut result = format!("{}", self.data_type);
         if !self.is_required {
-            result.push_str("?");
+            result.push('?');
         }
         f.write_str(&result)
     }
@@ -160,6 +466,9 @@ pub enum DataType {
     Dict(Primitive, Box<DataType>),
     Object(String),
     ObjectDecl(TypeDecl),
+    Format(Format),
+    /// Any JSON value, like elements of an `array` declared without braces.
+    Any,
 }
 
 impl Display for DataType {
@@ -168,12 +477,44 @@ impl Display for DataType {
             DataType::Primitive(primitive) => f.write_str(&format!("{}", primitive)),
             DataType::Array(data_type) => f.write_str(&format!("array[{}]", data_type)),
             DataType::Dict(key, value) => f.write_str(&format!("dict{{ {}: {} }}", key, value)),
-            DataType::Object(ident) => f.write_str(&format!("{}", ident)),
+            DataType::Object(ident) => f.write_str(&ident.to_string()),
+            DataType::Format(format) => f.write_str(&format!("{}", format)),
+            DataType::Any => f.write_str("any"),
             DataType::ObjectDecl(type_decl) => f.write_str(&format!("{}", type_decl)),
         }
     }
 }
 
+/// Primitive value with a specific format, like `uuid`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    DateIso8601,
+    Url,
+    Timestamp,
+    Uuid,
+}
+
+impl Format {
+    /// Primitive carrying the formatted value.
+    pub fn primitive(&self) -> Primitive {
+        match self {
+            Format::Timestamp => Primitive::Int,
+            Format::DateIso8601 | Format::Url | Format::Uuid => Primitive::Str,
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Format::DateIso8601 => f.write_str("date_iso8601"),
+            Format::Url => f.write_str("url"),
+            Format::Timestamp => f.write_str("timestamp"),
+            Format::Uuid => f.write_str("uuid"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Primitive {
     Int,
@@ -195,15 +536,30 @@ impl Display for Primitive {
 
 #[derive(Clone)]
 pub enum ImportError {
-    IOError(ReadError),
+    IOError(String, ReadError),
     InvalidInputSource,
     InvalidImportValue,
+    Cycle(Vec<String>),
+    NoMatches(String),
+    UnknownSelection(String, String),
+    UnknownPackage(String),
+    InvalidModule(String),
 }
 
 impl PartialEq for ImportError {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
-            (ImportError::IOError(lhs), ImportError::IOError(rhs)) => lhs == rhs,
+            (ImportError::IOError(lhs_path, lhs), ImportError::IOError(rhs_path, rhs)) => {
+                lhs_path == rhs_path && lhs == rhs
+            }
+            (ImportError::Cycle(lhs), ImportError::Cycle(rhs)) => lhs == rhs,
+            (ImportError::NoMatches(lhs), ImportError::NoMatches(rhs)) => lhs == rhs,
+            (ImportError::UnknownPackage(lhs), ImportError::UnknownPackage(rhs)) => lhs == rhs,
+            (ImportError::InvalidModule(lhs), ImportError::InvalidModule(rhs)) => lhs == rhs,
+            (
+                ImportError::UnknownSelection(lhs_path, lhs),
+                ImportError::UnknownSelection(rhs_path, rhs),
+            ) => lhs_path == rhs_path && lhs == rhs,
             (ImportError::InvalidInputSource, ImportError::InvalidInputSource) => true,
             (ImportError::InvalidImportValue, ImportError::InvalidImportValue) => true,
             _ => false,
@@ -215,11 +571,33 @@ impl PartialEq for ImportError {
 
 pub type InterfaceDeclResults = Vec<Result<InterfaceDecl, InterfaceDeclError>>;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct InterfaceDecl {
     pub ident: String,
     pub params: Vec<String>,
+    /// Name of the operation, unique across the schema. Derived from method and path when not declared.
+    pub operation_id: String,
+    pub tags: Vec<String>,
+    pub group: Option<String>,
     pub spec: InterfaceSpec,
+    pub versions: InterfaceVersions,
+}
+
+impl InterfaceDecl {
+    /// View of the interface as it exists in the given API version, `None` if it is absent there.
+    pub fn for_version(&self, version: u32) -> Option<InterfaceDecl> {
+        if !self.versions.contains(version) {
+            return None;
+        }
+        let spec = match self.versions.override_for(version) {
+            Some(api) => InterfaceSpec::Api(api.clone()),
+            None => self.spec.clone(),
+        };
+        Some(InterfaceDecl {
+            spec,
+            ..self.clone()
+        })
+    }
 }
 
 impl Display for InterfaceDecl {
@@ -241,7 +619,10 @@ impl Display for InterfaceDecl {
                 "Responses: {}",
                 responses
                     .iter()
-                    .map(|(k, v)| { format!("{}: {}\n", k, v) })
+                    .map(|(k, v)| match v {
+                        Some(v) => format!("{}: {}\n", k, v),
+                        None => format!("{}: no content\n", k),
+                    })
                     .collect::<String>()
             ));
         }
@@ -249,7 +630,7 @@ impl Display for InterfaceDecl {
     }
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone)]
 pub enum InterfaceSpec {
     Api(ApiSpec),
 }
@@ -262,11 +643,12 @@ impl Debug for InterfaceSpec {
     }
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct ApiSpec {
     pub method: HttpMethod,
     pub payload: Option<HttpPayload>,
     pub responses: HttpResponses,
+    pub inferred_status_code: Option<StatusCode>,
 }
 
 impl Display for ApiSpec {
@@ -282,12 +664,69 @@ impl Display for ApiSpec {
     }
 }
 
-pub type HttpResponses = Option<HashMap<StatusCode, TypeDecl>>;
+/// API versions an interface exists in, either listed explicitly or as a `since`..`removed_in`
+/// range, together with the spec it has starting from each overriding version.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct InterfaceVersions {
+    pub listed: Option<Vec<u32>>,
+    pub since: Option<u32>,
+    pub removed_in: Option<u32>,
+    pub overrides: HashMap<u32, ApiSpec>,
+}
+
+impl InterfaceVersions {
+    pub fn contains(&self, version: u32) -> bool {
+        if let Some(listed) = &self.listed {
+            return listed.contains(&version);
+        }
+        self.since.map_or(true, |since| since <= version)
+            && self.removed_in.map_or(true, |removed_in| version < removed_in)
+    }
+
+    pub fn override_for(&self, version: u32) -> Option<&ApiSpec> {
+        self.overrides
+            .iter()
+            .filter(|(since, _)| **since <= version)
+            .max_by_key(|(since, _)| **since)
+            .map(|(_, api)| api)
+    }
+
+    pub fn is_unrestricted(&self) -> bool {
+        self.listed.is_none() && self.since.is_none() && self.removed_in.is_none()
+    }
+}
+
+impl Display for InterfaceVersions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(listed) = &self.listed {
+            let listed: Vec<String> = listed.iter().map(|val| val.to_string()).collect();
+            return write!(f, "versions {}", listed.join(", "));
+        }
+        match (self.since, self.removed_in) {
+            (Some(since), Some(removed_in)) => write!(f, "since {}, removed in {}", since, removed_in),
+            (Some(since), None) => write!(f, "since {}", since),
+            (None, Some(removed_in)) => write!(f, "removed in {}", removed_in),
+            (None, None) => write!(f, "all versions"),
+        }
+    }
+}
+
+/// Response declared for each status code, `None` stands for an explicit "no content".
+pub type HttpResponses = Option<HashMap<StatusCode, Option<DataTypeDecl>>>;
+
+/// Responses declared once at the spec level: `named` ones are referenced from interfaces
+/// as `$name`, `defaults` are merged into every interface which doesn't declare the same code.
+#[derive(Default, PartialEq, Clone, Debug)]
+pub struct SharedResponses {
+    pub named: HashMap<String, Option<DataTypeDecl>>,
+    pub defaults: HashMap<StatusCode, Option<DataTypeDecl>>,
+}
 
 #[derive(Eq, Hash, PartialEq, Clone, Debug)]
 pub enum StatusCode {
     Fixed(u16),
     Prefix(u16),
+    Default,
 }
 
 impl StatusCode {
@@ -295,20 +734,25 @@ impl StatusCode {
         match self {
             StatusCode::Fixed(val) => val.to_string(),
             StatusCode::Prefix(val) => val.to_string() + "xx",
+            StatusCode::Default => "default".to_string(),
+        }
+    }
+
+    pub fn covers(&self, other: &StatusCode) -> bool {
+        match (self, other) {
+            (StatusCode::Prefix(prefix), StatusCode::Fixed(code)) => code / 100 == *prefix,
+            _ => false,
         }
     }
 }
 
 impl Display for StatusCode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            StatusCode::Fixed(val) => f.write_str(&val.to_string()),
-            StatusCode::Prefix(val) => f.write_str(&(val.to_string() + "xx")),
-        }
+        f.write_str(&self.as_key())
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum HttpMethod {
     Get,
     Post,
@@ -316,6 +760,29 @@ pub enum HttpMethod {
     Delete,
     Patch,
     Head,
+    Options,
+    Trace,
+    Connect,
+    Extension(String),
+}
+
+/// Payload each standard method is allowed to declare.
+const STANDARD_PAYLOAD_KINDS: &[(HttpMethod, Option<PayloadKind>)] = &[
+    (HttpMethod::Get, Some(PayloadKind::Query)),
+    (HttpMethod::Head, Some(PayloadKind::Query)),
+    (HttpMethod::Post, Some(PayloadKind::Body)),
+    (HttpMethod::Put, Some(PayloadKind::Body)),
+    (HttpMethod::Patch, Some(PayloadKind::Body)),
+    (HttpMethod::Delete, None),
+    (HttpMethod::Options, None),
+    (HttpMethod::Trace, None),
+    (HttpMethod::Connect, None),
+];
+
+impl HttpMethod {
+    pub fn standard() -> impl Iterator<Item = &'static HttpMethod> {
+        STANDARD_PAYLOAD_KINDS.iter().map(|(method, _)| method)
+    }
 }
 
 impl Display for HttpMethod {
@@ -327,11 +794,40 @@ impl Display for HttpMethod {
             HttpMethod::Delete => f.write_str("DELETE"),
             HttpMethod::Patch => f.write_str("PATCH"),
             HttpMethod::Head => f.write_str("HEAD"),
+            HttpMethod::Options => f.write_str("OPTIONS"),
+            HttpMethod::Trace => f.write_str("TRACE"),
+            HttpMethod::Connect => f.write_str("CONNECT"),
+            HttpMethod::Extension(name) => f.write_str(name),
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PayloadKind {
+    Query,
+    Body,
+}
+
+/// Non-standard methods, like WebDAV `PROPFIND`, explicitly allowed by the spec,
+/// keyed by upper-cased name together with the payload each of them may declare.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ExtensionMethods {
+    pub payload_kinds: HashMap<String, Option<PayloadKind>>,
+}
+
+impl ExtensionMethods {
+    pub fn payload_kind(&self, method: &HttpMethod) -> Option<PayloadKind> {
+        match method {
+            HttpMethod::Extension(name) => self.payload_kinds.get(name).copied().flatten(),
+            standard => STANDARD_PAYLOAD_KINDS
+                .iter()
+                .find(|(method, _)| method == standard)
+                .and_then(|(_, payload_kind)| *payload_kind),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum HttpPayload {
     Query(Vec<PropertyDecl>),
     Body(Vec<PropertyDecl>),
@@ -382,15 +878,62 @@ pub enum InterfaceDeclError {
     InvalidQuery,
     InvalidBody,
     InvalidResponseTypeDeclaration,
+    InvalidAsync,
+    ResponseNotFound(String),
+    DuplicateStatusCode(StatusCode),
+    InvalidVersions,
+    InvalidOperationId,
+    InvalidTags,
+    InvalidGroup,
+}
+
+impl InterfaceDeclError {
+    fn default_fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            InterfaceDeclError::ImportFailure(import_error) => {
+                write!(f, "Import failed: {}", import_error)
+            }
+            InterfaceDeclError::BodyNotAllowed => write!(f, "Body is not allowed for this method."),
+            InterfaceDeclError::QueryNotAllowed => {
+                write!(f, "Query is not allowed for this method.")
+            }
+            InterfaceDeclError::InvalidKey => write!(f, "Interface has an unsupported key."),
+            InterfaceDeclError::InvalidStatusCode => write!(f, "Status code is not valid."),
+            InterfaceDeclError::TypeNotFound(name) => write!(f, "Type {} is not declared.", name),
+            InterfaceDeclError::InvalidResponseDeclaration => {
+                write!(f, "Response declaration is not valid.")
+            }
+            InterfaceDeclError::InvalidInterfaceDeclaration => {
+                write!(f, "Interface declaration is not valid.")
+            }
+            InterfaceDeclError::InvalidIdent => write!(f, "Path is not valid."),
+            InterfaceDeclError::EmptyParam => write!(f, "Path parameter cannot be empty."),
+            InterfaceDeclError::InvalidMethod => write!(f, "Method is not supported."),
+            InterfaceDeclError::InvalidQuery => write!(f, "Query declaration is not valid."),
+            InterfaceDeclError::InvalidBody => write!(f, "Body declaration is not valid."),
+            InterfaceDeclError::InvalidResponseTypeDeclaration => {
+                write!(f, "Response type declaration is not valid.")
+            }
+            InterfaceDeclError::InvalidAsync => write!(f, "`async` must be a boolean."),
+            InterfaceDeclError::ResponseNotFound(name) => {
+                write!(f, "Named response {} is not declared.", name)
+            }
+            InterfaceDeclError::DuplicateStatusCode(code) => {
+                write!(f, "Status code {} is declared more than once.", code)
+            }
+            InterfaceDeclError::InvalidVersions => write!(f, "Versions declaration is not valid."),
+            InterfaceDeclError::InvalidOperationId => write!(f, "Operation id is not valid."),
+            InterfaceDeclError::InvalidTags => write!(f, "Tags must be a list of strings."),
+            InterfaceDeclError::InvalidGroup => write!(f, "Group must be a string."),
+        }
+    }
 }
 
 impl Error for InterfaceDeclError {}
 
 impl Display for InterfaceDeclError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            _ => f.write_str("InterfaceDeclError"),
-        }
+        self.default_fmt(f)
     }
 }
 
@@ -400,6 +943,45 @@ pub type TypeUsageMeta = Option<Vec<UnknownType>>;
 pub enum UnknownType {
     InTypeDeclaration(usize, usize),
     InPayload(usize, usize),
-    InResponse(usize, StatusCode, usize)
+    InResponse(usize, StatusCode, usize),
+    InNamedResponse(String, usize),
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub enum SchemaWarning {
+    ShadowedStatusCode {
+        location: String,
+        fixed: StatusCode,
+        pattern: StatusCode,
+    },
+    AmbiguousRoute {
+        method: HttpMethod,
+        first: (String, Location),
+        second: (String, Location),
+    },
+}
+
+impl Display for SchemaWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaWarning::ShadowedStatusCode {
+                location,
+                fixed,
+                pattern,
+            } => write!(
+                f,
+                "{}: status code {} overlaps with {} declared next to it.",
+                location, fixed, pattern
+            ),
+            SchemaWarning::AmbiguousRoute {
+                method,
+                first,
+                second,
+            } => write!(
+                f,
+                "{} {} in {} may match the same requests as {} {} in {}.",
+                method, second.0, second.1, method, first.0, first.1
+            ),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::parser;
+use crate::schema::Schema;
+
+/// Root document importing `types.yml` and `interfaces.yml`, enough for most tests.
+pub const MAIN: &str = "
+hosts:
+  prod: https://acme.com
+types:
+  _import: types.yml
+interfaces:
+  _import: interfaces.yml
+";
+
+/// Spec files in a temporary directory, removed when dropped, so also when a test fails.
+pub struct TempSpec {
+    pub root: PathBuf,
+}
+
+impl TempSpec {
+    /// Directory unique to the name and the process, with the files at paths relative to it.
+    pub fn new(name: &str, files: &[(&str, &str)]) -> Self {
+        let root = std::env::temp_dir().join(format!("arc-isle-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let spec = TempSpec { root };
+        spec.write(files);
+        spec
+    }
+
+    /// Spec with `MAIN` as the root document.
+    pub fn with_main(name: &str, types: &str, interfaces: &str) -> Self {
+        Self::new(
+            name,
+            &[
+                ("main.yaml", MAIN),
+                ("types.yml", types),
+                ("interfaces.yml", interfaces),
+            ],
+        )
+    }
+
+    pub fn write(&self, files: &[(&str, &str)]) {
+        for (path, contents) in files {
+            let path = self.root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+    }
+
+    pub fn read(&self, path: &str) -> String {
+        fs::read_to_string(self.root.join(path)).unwrap()
+    }
+
+    pub fn path(&self) -> &str {
+        self.root.to_str().unwrap()
+    }
+
+    pub fn parse(&self) -> Schema {
+        parser::parse(self.path()).unwrap()
+    }
+}
+
+impl Drop for TempSpec {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
@@ -0,0 +1,840 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+
+use yaml_rust::Yaml;
+
+use crate::schema::{
+    ApiSpec, DataType, DataTypeDecl, HttpPayload, InterfaceDecl, InterfaceSpec, Primitive,
+    PropertyDecl, Schema, StatusCode, TypeDecl, VersioningFormat,
+};
+use crate::validation::{parse_json, InstanceValidator, ValidationError};
+
+/// Checks HTTP traffic recorded in HAR files against interfaces declared in the schema.
+pub struct TrafficChecker<'a> {
+    pub schema: &'a Schema,
+}
+
+/// Outcome of checking a recording: number of checked requests and issues found in them.
+#[derive(Debug, PartialEq)]
+pub struct TrafficReport {
+    pub entries: usize,
+    pub issues: Vec<TrafficIssue>,
+}
+
+impl<'a> TrafficChecker<'a> {
+    pub fn check_har(&self, har: &str) -> Result<TrafficReport, TrafficError> {
+        let har = parse_json(har).map_err(|err| TrafficError::InvalidHar(err.to_string()))?;
+        let Yaml::Array(entries) = &har["log"]["entries"] else {
+            return Err(TrafficError::MissingEntries);
+        };
+        let mut issues = Vec::new();
+        for (index, entry) in entries.iter().enumerate() {
+            issues.extend(self.check_entry(index, entry));
+        }
+        Ok(TrafficReport {
+            entries: entries.len(),
+            issues,
+        })
+    }
+
+    /// Checks a single entry of `log.entries` in a HAR file.
+    pub fn check_entry(&self, index: usize, entry: &Yaml) -> Vec<TrafficIssue> {
+        let request = &entry["request"];
+        let method = request["method"]
+            .as_str()
+            .unwrap_or_default()
+            .to_uppercase();
+        let url = request["url"].as_str().unwrap_or_default();
+        let path = url_path(url);
+        let mut issues = Vec::new();
+        let mut report = |kind| {
+            issues.push(TrafficIssue {
+                entry: index,
+                request: format!("{} {}", method, path),
+                kind,
+            })
+        };
+        let Some(api) = self.find_api(&method, &path, request) else {
+            report(TrafficIssueKind::UndocumentedEndpoint);
+            return issues;
+        };
+        let validator = InstanceValidator {
+            schema: self.schema,
+        };
+        let query = self.query(request);
+        match &api.payload {
+            Some(HttpPayload::Query(property_decls)) => {
+                let value = query_value(property_decls, &query);
+                let errors = validate(&validator, &object(property_decls), &value);
+                if !errors.is_empty() {
+                    report(TrafficIssueKind::ShapeMismatch(MessagePart::Query, errors));
+                }
+            }
+            _ if !query.is_empty() => {
+                report(TrafficIssueKind::UndeclaredContent(MessagePart::Query))
+            }
+            _ => {}
+        }
+        let post_data = &request["postData"];
+        let body = post_data["text"].as_str().unwrap_or_default();
+        match &api.payload {
+            Some(HttpPayload::Body(property_decls)) => {
+                if let Some(kind) = check_content(
+                    &validator,
+                    &object(property_decls),
+                    post_data,
+                    MessagePart::Body,
+                ) {
+                    report(kind);
+                }
+            }
+            _ if !body.is_empty() => report(TrafficIssueKind::UndeclaredContent(MessagePart::Body)),
+            _ => {}
+        }
+        let response = &entry["response"];
+        // Requests which never got a response are recorded with status 0.
+        let status = match response["status"].as_i64() {
+            Some(status) if status > 0 => status as u16,
+            _ => return issues,
+        };
+        let part = MessagePart::Response(status);
+        let content = &response["content"];
+        let text = content["text"].as_str().unwrap_or_default();
+        match response_decl(&api, status) {
+            None => report(TrafficIssueKind::UndocumentedStatusCode(status)),
+            // Binary content is recorded encoded, and isn't JSON anyway.
+            Some(_) if content["encoding"].as_str().is_some() => {}
+            Some(Some(data_type_decl)) => {
+                if let Some(kind) = check_content(&validator, data_type_decl, content, part) {
+                    report(kind);
+                }
+            }
+            Some(None) if !text.is_empty() => report(TrafficIssueKind::UndeclaredContent(part)),
+            Some(None) => {}
+        }
+        issues
+    }
+
+    /// Finds spec of the interface serving the request, preferring routes with more literal segments,
+    /// like `news/latest` over `news/{entry_id}`.
+    pub(crate) fn find_api(&self, method: &str, path: &str, request: &Yaml) -> Option<ApiSpec> {
+        let segments = path_segments(path);
+        let routed = self.routed_segments(&segments);
+        let versioning = self.schema.versioning.as_ref();
+        let mut candidates: Vec<(usize, InterfaceDecl)> = Vec::new();
+        for decl in self.schema.interfaces.iter().flatten() {
+            let InterfaceSpec::Api(api) = &decl.spec;
+            if !api.method.to_string().eq_ignore_ascii_case(method) {
+                continue;
+            }
+            let route = match versioning {
+                Some(versioning) => versioning.versioned_ident(&decl.ident, None),
+                None => decl.ident.clone(),
+            };
+            let Some((literals, path_version)) = routed
+                .iter()
+                .find_map(|(segments, exact)| match_route(&route, segments, *exact))
+            else {
+                continue;
+            };
+            let version = path_version.or_else(|| self.request_version(request));
+            let decl = match version {
+                Some(version) if versioning.is_some() => match decl.for_version(version) {
+                    Some(decl) => decl,
+                    None => continue,
+                },
+                _ => decl.clone(),
+            };
+            candidates.push((literals, decl));
+        }
+        let (_, decl) = candidates
+            .into_iter()
+            .max_by_key(|(literals, _)| *literals)?;
+        let InterfaceSpec::Api(api) = decl.spec;
+        Some(api)
+    }
+
+    /// Segments of the path which routes are matched against, with whether they must match all of them.
+    /// A base path of a host is stripped. A host without a path may be behind an undeclared prefix,
+    /// so then routes only have to match trailing segments.
+    fn routed_segments<'p>(&self, segments: &'p [&'p str]) -> Vec<(&'p [&'p str], bool)> {
+        let base_paths: Vec<String> = self
+            .schema
+            .hosts
+            .iter()
+            .map(|host| url_path(&host.address))
+            .collect();
+        let mut routed: Vec<(&[&str], bool)> = base_paths
+            .iter()
+            .map(|base_path| path_segments(base_path))
+            .filter(|base| !base.is_empty() && base.len() <= segments.len())
+            .filter(|base| {
+                base.iter()
+                    .zip(segments)
+                    .all(|(template, segment)| match_segment(template, segment).is_some())
+            })
+            .map(|base| (&segments[base.len()..], true))
+            .collect();
+        if routed.is_empty() {
+            let has_bare_host = base_paths.is_empty()
+                || base_paths
+                    .iter()
+                    .any(|base_path| path_segments(base_path).is_empty());
+            routed.push((segments, !has_bare_host));
+        }
+        routed
+    }
+
+    /// Version requested in a header, query or media type, depending on the versioning format.
+    fn request_version(&self, request: &Yaml) -> Option<u32> {
+        let named = |list: &str, name: &str| -> Option<String> {
+            request[list].as_vec()?.iter().find_map(|item| {
+                let matches = item["name"].as_str()?.eq_ignore_ascii_case(name);
+                matches.then(|| item["value"].as_str().map(str::to_string))?
+            })
+        };
+        match &self.schema.versioning.as_ref()?.format {
+            VersioningFormat::Headers { header } => {
+                let value = named("headers", header)?;
+                value.trim_start_matches(['v', 'V']).parse().ok()
+            }
+            VersioningFormat::Query { parameter } => named("queryString", parameter)?.parse().ok(),
+            VersioningFormat::MediaType { media_type } => {
+                let accept = named("headers", "accept")?;
+                accept
+                    .split(',')
+                    .find_map(|value| match_segment(media_type, value.trim()))
+                    .flatten()
+            }
+            VersioningFormat::Path { .. } => None,
+        }
+    }
+
+    /// Query parameters of the request, except the one passing the API version.
+    fn query(&self, request: &Yaml) -> Vec<(String, String)> {
+        let version_parameter = match self.schema.versioning.as_ref().map(|val| &val.format) {
+            Some(VersioningFormat::Query { parameter }) => Some(parameter.as_str()),
+            _ => None,
+        };
+        let Some(items) = request["queryString"].as_vec() else {
+            return Vec::new();
+        };
+        items
+            .iter()
+            .filter_map(|item| {
+                let name = item["name"].as_str()?;
+                let value = item["value"].as_str().unwrap_or_default();
+                (Some(name) != version_parameter).then(|| (name.to_string(), value.to_string()))
+            })
+            .collect()
+    }
+}
+
+/// Path of the URL, without scheme, authority, query and fragment.
+fn url_path(url: &str) -> String {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    match rest.find('/') {
+        Some(start) if url.contains("://") => rest[start..].to_string(),
+        Some(_) => rest.to_string(),
+        None if url.contains("://") => "/".to_string(),
+        None => rest.to_string(),
+    }
+}
+
+fn path_segments(path: &str) -> Vec<&str> {
+    path.split('/').filter(|val| !val.is_empty()).collect()
+}
+
+/// Matches the route against the whole path, or only its trailing segments unless `exact`.
+/// Returns the number of literal segments and the version, if the route has its placeholder.
+fn match_route(route: &str, segments: &[&str], exact: bool) -> Option<(usize, Option<u32>)> {
+    let route = path_segments(route);
+    if route.len() > segments.len() || (exact && route.len() != segments.len()) {
+        return None;
+    }
+    let segments = &segments[segments.len() - route.len()..];
+    let mut literals = 0;
+    let mut version = None;
+    for (template, segment) in route.iter().zip(segments) {
+        if !template.contains('{') {
+            literals += 1;
+        }
+        if let Some(val) = match_segment(template, segment)? {
+            version = Some(val);
+        }
+    }
+    Some((literals, version))
+}
+
+/// Matches a segment against a template with at most one `{param}`, like `v{version}`.
+/// Returns the version, when the parameter is `{version}`.
+fn match_segment(template: &str, segment: &str) -> Option<Option<u32>> {
+    let (Some(start), Some(end)) = (template.find('{'), template.find('}')) else {
+        return (template == segment).then_some(None);
+    };
+    let (prefix, suffix) = (&template[..start], &template[end + 1..]);
+    let value = segment.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    if value.is_empty() {
+        return None;
+    }
+    if &template[start + 1..end] == "version" {
+        return value.parse().ok().map(Some);
+    }
+    Some(None)
+}
+
+/// Response declared for the status code, falling back to its class, like `4xx`, and to `default`.
+/// `Some(None)` is a declared response without content.
+pub(crate) fn response_decl(api: &ApiSpec, status: u16) -> Option<Option<&DataTypeDecl>> {
+    let responses = api.responses.as_ref()?;
+    [
+        StatusCode::Fixed(status),
+        StatusCode::Prefix(status / 100),
+        StatusCode::Default,
+    ]
+    .iter()
+    .find_map(|code| responses.get(code))
+    .map(Option::as_ref)
+}
+
+fn object(property_decls: &[PropertyDecl]) -> DataTypeDecl {
+    DataTypeDecl {
+        data_type: DataType::ObjectDecl(TypeDecl {
+            name: String::new(),
+            namespace: None,
+            property_decls: property_decls.to_vec(),
+        }),
+        is_required: true,
+    }
+}
+
+/// Query values are strings on the wire, so they are read according to declared types,
+/// and repeated parameters make an array.
+fn query_value(property_decls: &[PropertyDecl], query: &[(String, String)]) -> Yaml {
+    let mut hash = yaml_rust::yaml::Hash::new();
+    for (name, _) in query {
+        let key = Yaml::String(name.clone());
+        if hash.contains_key(&key) {
+            continue;
+        }
+        let values: Vec<&str> = query
+            .iter()
+            .filter(|(other, _)| other == name)
+            .map(|(_, value)| value.as_str())
+            .collect();
+        let data_type = property_decls
+            .iter()
+            .find(|decl| &decl.name == name)
+            .and_then(|decl| decl.data_type_decl.as_ref().ok())
+            .map(|decl| &decl.data_type);
+        let value = match data_type {
+            Some(DataType::Array(item_type)) => Yaml::Array(
+                values
+                    .iter()
+                    .map(|value| scalar(item_type, value))
+                    .collect(),
+            ),
+            Some(data_type) => scalar(data_type, values[values.len() - 1]),
+            None => Yaml::String(values[values.len() - 1].to_string()),
+        };
+        hash.insert(key, value);
+    }
+    Yaml::Hash(hash)
+}
+
+fn scalar(data_type: &DataType, value: &str) -> Yaml {
+    let primitive = match data_type {
+        DataType::Primitive(primitive) => primitive.clone(),
+        DataType::Format(format) => format.primitive(),
+        _ => Primitive::Str,
+    };
+    match primitive {
+        Primitive::Str => Yaml::String(value.to_string()),
+        _ => Yaml::from_str(value),
+    }
+}
+
+/// Validates `postData` of a request or `content` of a response.
+fn check_content(
+    validator: &InstanceValidator,
+    data_type_decl: &DataTypeDecl,
+    content: &Yaml,
+    part: MessagePart,
+) -> Option<TrafficIssueKind> {
+    let text = content["text"].as_str().unwrap_or_default().trim();
+    if text.is_empty() {
+        return Some(TrafficIssueKind::MissingContent(part));
+    }
+    let mime_type = content["mimeType"].as_str().unwrap_or_default();
+    let is_json = mime_type.is_empty() || mime_type.contains("json");
+    let value = match parse_json(text) {
+        Ok(value) if is_json => value,
+        _ => return Some(TrafficIssueKind::MalformedJson(part)),
+    };
+    let errors = validate(validator, data_type_decl, &value);
+    (!errors.is_empty()).then_some(TrafficIssueKind::ShapeMismatch(part, errors))
+}
+
+fn validate(
+    validator: &InstanceValidator,
+    data_type_decl: &DataTypeDecl,
+    value: &Yaml,
+) -> Vec<ValidationError> {
+    match validator.validate_data_type_decl(data_type_decl, value) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.list,
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum MessagePart {
+    Query,
+    Body,
+    Response(u16),
+}
+
+impl Display for MessagePart {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessagePart::Query => write!(f, "query"),
+            MessagePart::Body => write!(f, "request body"),
+            MessagePart::Response(status) => write!(f, "response {}", status),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TrafficIssue {
+    /// Index of the entry in the recording.
+    pub entry: usize,
+    /// Method and path of the request.
+    pub request: String,
+    pub kind: TrafficIssueKind,
+}
+
+impl Display for TrafficIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{} {}: {}", self.entry + 1, self.request, self.kind)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum TrafficIssueKind {
+    UndocumentedEndpoint,
+    UndocumentedStatusCode(u16),
+    ShapeMismatch(MessagePart, Vec<ValidationError>),
+    MissingContent(MessagePart),
+    UndeclaredContent(MessagePart),
+    MalformedJson(MessagePart),
+}
+
+impl Display for TrafficIssueKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrafficIssueKind::UndocumentedEndpoint => write!(f, "no interface declared."),
+            TrafficIssueKind::UndocumentedStatusCode(status) => {
+                write!(f, "status code {} is not declared.", status)
+            }
+            TrafficIssueKind::ShapeMismatch(part, errors) => {
+                write!(f, "{} doesn't match the declaration:", part)?;
+                for error in errors {
+                    write!(f, "\n    {}", error)?;
+                }
+                Ok(())
+            }
+            TrafficIssueKind::MissingContent(part) => write!(f, "{} is declared, but empty.", part),
+            TrafficIssueKind::UndeclaredContent(part) => {
+                write!(f, "{} is not declared, but present.", part)
+            }
+            TrafficIssueKind::MalformedJson(part) => write!(f, "{} is not valid JSON.", part),
+        }
+    }
+}
+
+pub enum TrafficError {
+    InvalidHar(String),
+    MissingEntries,
+}
+
+impl TrafficError {
+    fn default_fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            TrafficError::InvalidHar(reason) => write!(f, "Invalid HAR file: {}", reason),
+            TrafficError::MissingEntries => write!(f, "HAR file has no `log.entries`."),
+        }
+    }
+}
+
+impl Error for TrafficError {}
+
+impl Display for TrafficError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+impl Debug for TrafficError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::TempSpec;
+    use crate::traffic::{MessagePart, TrafficChecker, TrafficIssueKind};
+    use crate::validation::{ValidationError, ValidationErrorKind};
+
+    const MAIN: &str = "
+hosts:
+  prod: https://acme.com/base
+versioning:
+  format: path
+  prefix: api/v{version}
+types:
+  _import: types.yml
+interfaces:
+  _import: interfaces.yml
+";
+
+    const TYPES: &str = "
+entry:
+  id: uuid
+";
+
+    const INTERFACES: &str = "
+declarations:
+  - path: news
+    method: get
+    query:
+      limit: int?
+    response: array[entry]
+  - path: news
+    method: post
+    body:
+      title: str
+    response:
+      201: entry
+  - path: news/{entry_id}
+    method: get
+    since: 2
+    response: entry
+  - path: news/latest
+    method: get
+    response:
+      200: entry
+      204:
+";
+
+    const ID: &str = "\"7c9e6679-7425-40de-944b-e07fc1f90ae7\"";
+
+    /// Entry of a request to `https://acme.com/` followed by the path.
+    fn entry(method: &str, path: &str, query: &str, status: u16, text: &str) -> String {
+        format!(
+            r#"{{"request": {{"method": "{}", "url": "https://acme.com/{}", "headers": [], "queryString": [{}]}},
+              "response": {{"status": {}, "content": {{"mimeType": "application/json", "text": {:?}}}}}}}"#,
+            method, path, query, status, text
+        )
+    }
+
+    fn post(path: &str, body: &str, status: u16, text: &str) -> String {
+        format!(
+            r#"{{"request": {{"method": "POST", "url": "https://acme.com/{}", "headers": [], "queryString": [],
+                "postData": {{"mimeType": "application/json", "text": {:?}}}}},
+              "response": {{"status": {}, "content": {{"mimeType": "application/json", "text": {:?}}}}}}}"#,
+            path, body, status, text
+        )
+    }
+
+    /// Issues found in the entries, checked against a spec with `main` as the root document.
+    fn issues(
+        name: &str,
+        main: &str,
+        entries: &[String],
+    ) -> Vec<(usize, String, TrafficIssueKind)> {
+        let spec = TempSpec::new(
+            name,
+            &[
+                ("main.yaml", main),
+                ("types.yml", TYPES),
+                ("interfaces.yml", INTERFACES),
+            ],
+        );
+        let schema = spec.parse();
+        let har = format!(r#"{{"log": {{"entries": [{}]}}}}"#, entries.join(", "));
+        let checker = TrafficChecker { schema: &schema };
+
+        let report = checker.check_har(&har).unwrap();
+
+        assert_eq!(entries.len(), report.entries);
+        report
+            .issues
+            .into_iter()
+            .map(|issue| (issue.entry, issue.request, issue.kind))
+            .collect()
+    }
+
+    fn issue(
+        entry: usize,
+        request: &str,
+        kind: TrafficIssueKind,
+    ) -> (usize, String, TrafficIssueKind) {
+        (entry, request.to_string(), kind)
+    }
+
+    fn mismatch(part: MessagePart, pointer: &str, expected: &str, found: &str) -> TrafficIssueKind {
+        TrafficIssueKind::ShapeMismatch(
+            part,
+            vec![ValidationError {
+                pointer: pointer.to_string(),
+                kind: ValidationErrorKind::TypeMismatch {
+                    expected: expected.to_string(),
+                    found: found.to_string(),
+                },
+            }],
+        )
+    }
+
+    #[test]
+    fn test_route_matching() {
+        let entries = [
+            entry("GET", "base/api/v2/news/latest", "", 204, ""),
+            entry(
+                "GET",
+                "base/api/v2/news/12",
+                "",
+                200,
+                &format!("{{\"id\": {}}}", ID),
+            ),
+            entry("GET", "base/api/v1/news/12", "", 200, "{}"),
+            entry("PUT", "base/api/v2/news", "", 200, "[]"),
+            entry("get", "base/api/v2/news", "", 200, "[]"),
+        ];
+        assert_eq!(
+            vec![
+                issue(
+                    2,
+                    "GET /base/api/v1/news/12",
+                    TrafficIssueKind::UndocumentedEndpoint
+                ),
+                issue(
+                    3,
+                    "PUT /base/api/v2/news",
+                    TrafficIssueKind::UndocumentedEndpoint
+                ),
+            ],
+            issues("traffic-routes", MAIN, &entries)
+        );
+    }
+
+    #[test]
+    fn test_base_path() {
+        // Paths outside of the base path are matched as a whole.
+        let entries = [
+            entry("GET", "base/api/v2/news", "", 200, "[]"),
+            entry("GET", "api/v2/news", "", 200, "[]"),
+            entry("GET", "other/api/v2/news", "", 200, "[]"),
+        ];
+        assert_eq!(
+            vec![issue(
+                2,
+                "GET /other/api/v2/news",
+                TrafficIssueKind::UndocumentedEndpoint
+            )],
+            issues("traffic-base-path", MAIN, &entries)
+        );
+
+        // Without a base path the service may be behind an undeclared prefix.
+        let main = MAIN.replace("https://acme.com/base", "https://acme.com");
+        assert_eq!(
+            Vec::<(usize, String, TrafficIssueKind)>::new(),
+            issues("traffic-bare-host", &main, &entries)
+        );
+    }
+
+    #[test]
+    fn test_whole_path_after_base_path() {
+        let entries = [
+            entry("GET", "base/admin/api/v2/news", "", 200, "[]"),
+            entry("GET", "base/api/v2/news/latest/draft", "", 200, "{}"),
+            entry("GET", "base/base/api/v2/news", "", 200, "[]"),
+        ];
+        assert_eq!(
+            vec![
+                issue(
+                    0,
+                    "GET /base/admin/api/v2/news",
+                    TrafficIssueKind::UndocumentedEndpoint
+                ),
+                issue(
+                    1,
+                    "GET /base/api/v2/news/latest/draft",
+                    TrafficIssueKind::UndocumentedEndpoint
+                ),
+                issue(
+                    2,
+                    "GET /base/base/api/v2/news",
+                    TrafficIssueKind::UndocumentedEndpoint
+                ),
+            ],
+            issues("traffic-whole-path", MAIN, &entries)
+        );
+    }
+
+    #[test]
+    fn test_query() {
+        let entries = [
+            entry(
+                "GET",
+                "base/api/v2/news?limit=2",
+                r#"{"name": "limit", "value": "2"}"#,
+                200,
+                &format!("[{{\"id\": {}}}]", ID),
+            ),
+            entry(
+                "GET",
+                "base/api/v2/news?limit=all",
+                r#"{"name": "limit", "value": "all"}"#,
+                200,
+                "[]",
+            ),
+            entry(
+                "GET",
+                "base/api/v2/news/latest?full=1",
+                r#"{"name": "full", "value": "1"}"#,
+                204,
+                "",
+            ),
+        ];
+        assert_eq!(
+            vec![
+                issue(
+                    1,
+                    "GET /base/api/v2/news",
+                    mismatch(MessagePart::Query, "/limit", "int", "str")
+                ),
+                issue(
+                    2,
+                    "GET /base/api/v2/news/latest",
+                    TrafficIssueKind::UndeclaredContent(MessagePart::Query)
+                ),
+            ],
+            issues("traffic-query", MAIN, &entries)
+        );
+    }
+
+    #[test]
+    fn test_body() {
+        let created = format!("{{\"id\": {}}}", ID);
+        let entries = [
+            post("base/api/v2/news", "{\"title\": \"Launch\"}", 201, &created),
+            post("base/api/v2/news", "{\"title\": 1}", 201, &created),
+            post("base/api/v2/news", "", 201, &created),
+            post("base/api/v2/news/latest", "{}", 404, ""),
+            post(
+                "base/api/v2/news",
+                "{\"title\": \"Launch\"}",
+                201,
+                "{\"id\": 12}",
+            ),
+        ];
+        let news = "POST /base/api/v2/news";
+        assert_eq!(
+            vec![
+                issue(1, news, mismatch(MessagePart::Body, "/title", "str", "int")),
+                issue(2, news, TrafficIssueKind::MissingContent(MessagePart::Body)),
+                issue(
+                    3,
+                    "POST /base/api/v2/news/latest",
+                    TrafficIssueKind::UndocumentedEndpoint
+                ),
+                issue(
+                    4,
+                    news,
+                    mismatch(MessagePart::Response(201), "/id", "uuid", "int")
+                ),
+            ],
+            issues("traffic-body", MAIN, &entries)
+        );
+    }
+
+    #[test]
+    fn test_status() {
+        let entries = [
+            entry("GET", "base/api/v2/news", "", 500, ""),
+            entry("GET", "base/api/v2/news/latest", "", 204, "{}"),
+            entry("GET", "base/api/v2/news/latest", "", 200, ""),
+            entry("GET", "base/api/v2/news/latest", "", 0, ""),
+        ];
+        let latest = "GET /base/api/v2/news/latest";
+        assert_eq!(
+            vec![
+                issue(
+                    0,
+                    "GET /base/api/v2/news",
+                    TrafficIssueKind::UndocumentedStatusCode(500)
+                ),
+                issue(
+                    1,
+                    latest,
+                    TrafficIssueKind::UndeclaredContent(MessagePart::Response(204))
+                ),
+                issue(
+                    2,
+                    latest,
+                    TrafficIssueKind::MissingContent(MessagePart::Response(200))
+                ),
+            ],
+            issues("traffic-status", MAIN, &entries)
+        );
+    }
+
+    #[test]
+    fn test_json_content() {
+        let entries = [
+            entry(
+                "GET",
+                "base/api/v2/news/latest",
+                "",
+                200,
+                r#"{"id": "7c9e6679-7425-40de-944b-e07fc1f90ae7"}"#,
+            ),
+            entry(
+                "GET",
+                "base/api/v2/news/latest",
+                "",
+                200,
+                "id: 7c9e6679-7425-40de-944b-e07fc1f90ae7",
+            ),
+            entry(
+                "GET",
+                "base/api/v2/news/latest",
+                "",
+                200,
+                r#"{"id": "7c9e6679-7425-40de-944b-e07fc1f90ae7", "id": 1}"#,
+            ),
+        ];
+        let malformed = TrafficIssueKind::MalformedJson(MessagePart::Response(200));
+        let latest = "GET /base/api/v2/news/latest";
+        assert_eq!(
+            vec![
+                issue(1, latest, malformed.clone()),
+                issue(2, latest, malformed)
+            ],
+            issues("traffic-json", MAIN, &entries)
+        );
+
+        let spec = TempSpec::new(
+            "traffic-yaml-har",
+            &[
+                ("main.yaml", MAIN),
+                ("types.yml", TYPES),
+                ("interfaces.yml", INTERFACES),
+            ],
+        );
+        let schema = spec.parse();
+        let checker = TrafficChecker { schema: &schema };
+        assert!(checker.check_har("log:\n  entries: []\n").is_err());
+    }
+}
@@ -0,0 +1,433 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+use yaml_rust::Yaml;
+
+use crate::parser::imports::relative_to;
+use crate::parser::packages::MODULES_DIR;
+use crate::parser::utils::read_yaml;
+use crate::schema::{Location, Schema};
+
+/// Declarations which no interface depends on.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct UsageReport {
+    /// Qualified names of types not reachable from any interface, with their locations.
+    pub unused_types: Vec<(String, Location)>,
+    /// Imported files which declare nothing used, and import only such files.
+    pub dead_files: Vec<String>,
+}
+
+/// Finds types and files unreachable from interfaces through payloads, responses
+/// and references between types.
+pub struct UsageAnalyzer<'a> {
+    pub schema: &'a Schema,
+}
+
+impl<'a> UsageAnalyzer<'a> {
+    pub fn analyze(&self) -> UsageReport {
+        let used_types = self.schema.used_types();
+        let mut unused_types = Vec::new();
+        // Files declaring anything used: interfaces or types reachable from them.
+        let mut contributing: HashSet<&str> = HashSet::new();
+        for (index, type_decl) in self.schema.types.iter().enumerate() {
+            let Ok(type_decl) = type_decl else {
+                continue;
+            };
+            let location = &self.schema.locations.types[index];
+            let name = type_decl.qualified_name();
+            if used_types.contains(&name) {
+                contributing.insert(&location.file);
+            } else {
+                unused_types.push((name, location.clone()));
+            }
+        }
+        for (index, decl) in self.schema.interfaces.iter().enumerate() {
+            if decl.is_ok() {
+                contributing.insert(&self.schema.locations.interfaces[index].file);
+            }
+        }
+        let edges = &self.schema.imports.edges;
+        let mut files: Vec<&String> = edges.values().flatten().collect();
+        files.sort();
+        files.dedup();
+        let mut cache = HashMap::new();
+        let dead_files = files
+            .into_iter()
+            .filter(|file| !contributes(file, &contributing, edges, &mut cache, &mut Vec::new()))
+            .cloned()
+            .collect();
+        UsageReport {
+            unused_types,
+            dead_files,
+        }
+    }
+}
+
+/// Whether the file declares anything used, itself or through files it imports.
+fn contributes<'e>(
+    file: &'e str,
+    contributing: &HashSet<&str>,
+    edges: &'e HashMap<String, Vec<String>>,
+    cache: &mut HashMap<&'e str, bool>,
+    chain: &mut Vec<&'e str>,
+) -> bool {
+    if let Some(result) = cache.get(file) {
+        return *result;
+    }
+    if contributing.contains(file) {
+        return true;
+    }
+    // Cycles are reported by the parser, a file doesn't contribute through itself.
+    if chain.contains(&file) {
+        return false;
+    }
+    chain.push(file);
+    let result = edges.get(file).is_some_and(|imported| {
+        imported
+            .iter()
+            .any(|val| contributes(val, contributing, edges, cache, chain))
+    });
+    chain.pop();
+    cache.insert(file, result);
+    result
+}
+
+/// Copies the spec into the output directory without unused types. Vendored packages are copied as is,
+/// since they are verified against the lockfile. Types picked by name in a selective import are kept too,
+/// so that the import stays valid.
+pub fn prune(
+    schema: &Schema,
+    root_path: &str,
+    output_path: &str,
+    report: &UsageReport,
+) -> Result<(), PruneError> {
+    let root = Path::new(root_path);
+    let output = Path::new(output_path);
+    if output.starts_with(root) {
+        return Err(PruneError::NestedOutput);
+    }
+    let selected = selected_names(schema, root);
+    copy_dir(root, output).map_err(PruneError::Unwritable)?;
+    let mut by_file: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (name, location) in &report.unused_types {
+        let name = name.rsplit('.').next().unwrap_or(name);
+        let is_selected = selected
+            .get(&location.file)
+            .is_some_and(|names| names.contains(name));
+        if location.file.starts_with(MODULES_DIR) || is_selected {
+            continue;
+        }
+        by_file.entry(&location.file).or_default().push(name);
+    }
+    for (file, names) in by_file {
+        let path = output.join(file);
+        let contents = fs::read_to_string(&path).map_err(PruneError::Unwritable)?;
+        let is_module = schema.imports.modules.contains(file);
+        let pruned = remove_blocks(&contents, &names, is_module);
+        fs::write(&path, pruned).map_err(PruneError::Unwritable)?;
+    }
+    Ok(())
+}
+
+/// Names listed in `only` of selective imports in any file of the spec, by the imported file.
+fn selected_names(schema: &Schema, root: &Path) -> HashMap<String, HashSet<String>> {
+    let mut files: Vec<&str> = schema
+        .imports
+        .edges
+        .values()
+        .flatten()
+        .map(|val| val.as_str())
+        .collect();
+    files.push("main.yaml");
+    let mut names = HashMap::new();
+    for file in files {
+        let documents = read_yaml(&root.join(file).to_string_lossy()).unwrap_or_default();
+        for document in &documents {
+            collect_selected(document, file, &mut names);
+        }
+    }
+    names
+}
+
+fn collect_selected(value: &Yaml, importer: &str, names: &mut HashMap<String, HashSet<String>>) {
+    match value {
+        Yaml::Hash(hash) => {
+            if let (Some(file), Some(only)) = (value["file"].as_str(), value["only"].as_vec()) {
+                names
+                    .entry(relative_to(importer, file))
+                    .or_default()
+                    .extend(
+                        only.iter()
+                            .filter_map(|val| val.as_str())
+                            .map(str::to_string),
+                    );
+            }
+            for item in hash.values() {
+                collect_selected(item, importer, names);
+            }
+        }
+        Yaml::Array(items) => {
+            for item in items {
+                collect_selected(item, importer, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Removes type declarations of the named keys together with their nested lines,
+/// keeping the rest of the file untouched. A file left without declarations becomes an empty hash.
+pub(crate) fn remove_blocks(contents: &str, names: &[&str], is_module: bool) -> String {
+    let lines: Vec<&str> = contents.lines().collect();
+    let (section, level) = types_section(&lines, is_module);
+    let mut kept: Vec<&str> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let is_removed = section.contains(&i)
+            && indentation(line) == level
+            && names.iter().any(|name| {
+                let rest = line.trim_start().strip_prefix(name);
+                rest.is_some_and(|rest| rest.trim_end().starts_with(':'))
+            });
+        if !is_removed {
+            // Blank lines around a removed block aren't doubled.
+            let is_repeated_blank =
+                line.trim().is_empty() && kept.last().is_some_and(|val| val.trim().is_empty());
+            if !is_repeated_blank {
+                kept.push(line);
+            }
+            i += 1;
+            continue;
+        }
+        let indent = indentation(line);
+        i += 1;
+        while i < lines.len() && (lines[i].trim().is_empty() || indentation(lines[i]) > indent) {
+            i += 1;
+        }
+        // Trailing blank lines separate the next declaration, so they stay.
+        while lines[i - 1].trim().is_empty() {
+            i -= 1;
+        }
+    }
+    let has_content = kept
+        .iter()
+        .any(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+    let mut result: String = kept.iter().map(|line| format!("{}\n", line)).collect();
+    if !has_content {
+        result.push_str("{}\n");
+    }
+    result
+}
+
+/// Lines declaring types and their indentation: the whole types file, or `types` section of a module.
+/// A plain types file may declare a type named `types`, so the section is only looked for in modules.
+pub(crate) fn types_section(lines: &[&str], is_module: bool) -> (Range<usize>, usize) {
+    let is_content = |line: &&str| !line.trim().is_empty() && !line.trim_start().starts_with('#');
+    let section = lines.iter().position(|line| line.trim_end() == "types:");
+    let Some(section) = section.filter(|_| is_module) else {
+        return (0..lines.len(), 0);
+    };
+    let start = section + 1;
+    let end = lines[start..]
+        .iter()
+        .position(|line| is_content(line) && indentation(line) == 0)
+        .map_or(lines.len(), |val| start + val);
+    let level = lines[start..end]
+        .iter()
+        .find(|line| is_content(line))
+        .map_or(0, |line| indentation(line));
+    (start..end, level)
+}
+
+pub(crate) fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+pub enum PruneError {
+    NestedOutput,
+    Unwritable(io::Error),
+}
+
+impl PruneError {
+    fn default_fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            PruneError::NestedOutput => {
+                write!(f, "Pruned spec cannot be written inside the original one.")
+            }
+            PruneError::Unwritable(err) => write!(f, "Cannot write pruned spec: {}", err),
+        }
+    }
+}
+
+impl Error for PruneError {}
+
+impl Display for PruneError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+impl Debug for PruneError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::test_utils::TempSpec;
+    use crate::usage::{prune, remove_blocks, UsageAnalyzer};
+
+    #[test]
+    fn test_analyze() {
+        let spec = TempSpec::new(
+            "usage",
+            &[
+                (
+                    "main.yaml",
+                    "hosts:\n  prod: https://acme.com\ntypes:\n  _import: [types.yml, index.yml]\ninterfaces:\n  _import: interfaces.yml\n",
+                ),
+                (
+                    "types.yml",
+                    "user:\n  id: str\n  avatar: image\nimage:\n  url: url\ndraft:\n  id: str\n",
+                ),
+                ("index.yml", "_import: extra/legacy.yml\n"),
+                ("extra/legacy.yml", "legacy_user:\n  id: str\n"),
+                (
+                    "interfaces.yml",
+                    "declarations:\n  - path: users\n    method: get\n    response: dict[str, array[user]]\n",
+                ),
+            ],
+        );
+        let schema = spec.parse();
+        let analyzer = UsageAnalyzer { schema: &schema };
+
+        let report = analyzer.analyze();
+
+        let unused: Vec<&str> = report
+            .unused_types
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(vec!["types.draft", "legacy.legacy_user"], unused);
+        assert_eq!(
+            vec!["extra/legacy.yml".to_string(), "index.yml".to_string()],
+            report.dead_files
+        );
+    }
+
+    #[test]
+    fn test_remove_blocks() {
+        let contents = "# Users\nuser:\n  id: str\n  # Unused\n  draft:\n    id: str\n\ndraft:\n  id: str\n\npost:\n  id: str\n";
+        assert_eq!(
+            "# Users\nuser:\n  id: str\n  # Unused\n  draft:\n    id: str\n\npost:\n  id: str\n",
+            remove_blocks(contents, &["draft"], false)
+        );
+        let module = "_namespace: feed\ntypes:\n  draft:\n    id: str\ninterfaces:\n  - path: feed\n    method: get\n";
+        assert_eq!(
+            "_namespace: feed\ntypes:\ninterfaces:\n  - path: feed\n    method: get\n",
+            remove_blocks(module, &["draft"], true)
+        );
+        assert_eq!(
+            "# Drafts\n{}\n",
+            remove_blocks("# Drafts\ndraft:\n  id: str\n", &["draft"], false)
+        );
+        let module =
+            "_import:\n  prod: x.yml\ntypes:\n  prod:\n    id: str\n  page:\n    next: str\n";
+        assert_eq!(
+            "_import:\n  prod: x.yml\ntypes:\n  page:\n    next: str\n",
+            remove_blocks(module, &["prod"], true)
+        );
+        let types = "types:\n  id: str\ndraft:\n  id: str\n";
+        assert_eq!(
+            "types:\n  id: str\n",
+            remove_blocks(types, &["draft"], false)
+        );
+    }
+
+    #[test]
+    fn test_prune_selected() {
+        let spec = TempSpec::new(
+            "prune",
+            &[
+                (
+                    "main.yaml",
+                    "hosts:\n  prod: https://acme.com\ntypes:\n  _import:\n    - file: common.yml\n      only: [money, address]\n    - types.yml\ninterfaces:\n  _import: interfaces.yml\n",
+                ),
+                ("common.yml", "money:\n  amount: int\naddress:\n  street: str\n"),
+                ("types.yml", "draft:\n  id: str\n"),
+                (
+                    "interfaces.yml",
+                    "declarations:\n  - path: pay\n    method: get\n    response: money\n",
+                ),
+            ],
+        );
+        let schema = spec.parse();
+        let report = UsageAnalyzer { schema: &schema }.analyze();
+        let output = TempSpec::new("prune-output", &[]);
+        fs::remove_dir_all(&output.root).unwrap();
+
+        prune(&schema, spec.path(), output.path(), &report).unwrap();
+
+        assert_eq!("{}\n", output.read("types.yml"));
+        assert_eq!(spec.read("common.yml"), output.read("common.yml"));
+        assert!(output.parse().types.iter().all(|val| val.is_ok()));
+    }
+
+    #[test]
+    fn test_prune_selected_by_file() {
+        let spec = TempSpec::new(
+            "prune-by-file",
+            &[
+                (
+                    "main.yaml",
+                    "hosts:\n  prod: https://acme.com\ntypes:\n  _import:\n    - file: shared/common.yml\n      only: [money]\n    - legacy.yml\ninterfaces:\n  _import: interfaces.yml\n",
+                ),
+                ("shared/common.yml", "money:\n  amount: int\n"),
+                (
+                    "legacy.yml",
+                    "types:\n  id: str\nmoney:\n  amount: str\n",
+                ),
+                (
+                    "interfaces.yml",
+                    "declarations:\n  - path: pay\n    method: get\n    response: common.money\n  - path: types\n    method: get\n    response: legacy.types\n",
+                ),
+            ],
+        );
+        let schema = spec.parse();
+        let report = UsageAnalyzer { schema: &schema }.analyze();
+        let output = TempSpec::new("prune-by-file-output", &[]);
+        fs::remove_dir_all(&output.root).unwrap();
+
+        prune(&schema, spec.path(), output.path(), &report).unwrap();
+
+        assert_eq!("types:\n  id: str\n", output.read("legacy.yml"));
+        assert_eq!(
+            spec.read("shared/common.yml"),
+            output.read("shared/common.yml")
+        );
+        assert!(output.parse().types.iter().all(|val| val.is_ok()));
+    }
+}
@@ -0,0 +1,634 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use yaml_rust::yaml::Hash;
+use yaml_rust::Yaml;
+
+use crate::schema::{DataType, DataTypeDecl, Format, Primitive, PropertyDecl, Schema, TypeDecl};
+
+/// Checks JSON values against types declared in the schema.
+pub struct InstanceValidator<'a> {
+    pub schema: &'a Schema,
+}
+
+impl<'a> InstanceValidator<'a> {
+    /// Validates a value against the type declared under the given name, plain or qualified.
+    pub fn validate(&self, type_name: &str, value: &Yaml) -> Result<(), ValidationErrors> {
+        let data_type_decl = DataTypeDecl {
+            data_type: DataType::Object(type_name.to_string()),
+            is_required: true,
+        };
+        self.validate_data_type_decl(&data_type_decl, value)
+    }
+
+    /// Parses JSON and validates it against the type declared under the given name.
+    pub fn validate_json(&self, type_name: &str, json: &str) -> Result<(), Box<dyn Error>> {
+        let value = parse_json(json)?;
+        Ok(self.validate(type_name, &value)?)
+    }
+
+    /// Validates a value against a declaration, like a payload or a response of an interface.
+    pub fn validate_data_type_decl(
+        &self,
+        data_type_decl: &DataTypeDecl,
+        value: &Yaml,
+    ) -> Result<(), ValidationErrors> {
+        let mut list = Vec::new();
+        self.check_decl(data_type_decl, value, "", &mut list);
+        if list.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors { list })
+        }
+    }
+
+    fn check_decl(
+        &self,
+        data_type_decl: &DataTypeDecl,
+        value: &Yaml,
+        pointer: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        if !data_type_decl.is_required && value.is_null() {
+            return;
+        }
+        self.check(&data_type_decl.data_type, value, pointer, errors);
+    }
+
+    fn check(
+        &self,
+        data_type: &DataType,
+        value: &Yaml,
+        pointer: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let mut report = |kind| {
+            errors.push(ValidationError {
+                pointer: pointer.to_string(),
+                kind,
+            })
+        };
+        match data_type {
+            DataType::Any => {}
+            DataType::Primitive(primitive) => {
+                if !matches_primitive(primitive, value) {
+                    report(mismatch(data_type, value));
+                }
+            }
+            DataType::Format(format) => {
+                if !matches_primitive(&format.primitive(), value) {
+                    report(mismatch(data_type, value));
+                } else if !matches_format(format, value) {
+                    report(ValidationErrorKind::InvalidFormat(*format));
+                }
+            }
+            DataType::Array(item_type) => match value {
+                Yaml::Array(items) => {
+                    for (i, item) in items.iter().enumerate() {
+                        let pointer = format!("{}/{}", pointer, i);
+                        self.check(item_type, item, &pointer, errors);
+                    }
+                }
+                _ => report(mismatch(data_type, value)),
+            },
+            DataType::Dict(key_type, value_type) => match value {
+                Yaml::Hash(hash) => {
+                    for (key, item) in hash {
+                        let key = key.as_str().unwrap_or_default();
+                        let pointer = format!("{}/{}", pointer, escape(key));
+                        if !matches_key(key_type, key) {
+                            errors.push(ValidationError {
+                                pointer: pointer.clone(),
+                                kind: ValidationErrorKind::InvalidKey(key_type.clone()),
+                            });
+                        }
+                        self.check(value_type, item, &pointer, errors);
+                    }
+                }
+                _ => report(mismatch(data_type, value)),
+            },
+            DataType::ObjectDecl(type_decl) => match value {
+                Yaml::Hash(_) => self.check_properties(type_decl, value, pointer, errors),
+                _ => report(mismatch(data_type, value)),
+            },
+            DataType::Object(name) => match self.schema.find_type(name) {
+                Some(type_decl) => match value {
+                    Yaml::Hash(_) => self.check_properties(type_decl, value, pointer, errors),
+                    _ => report(mismatch(data_type, value)),
+                },
+                None => report(ValidationErrorKind::UnknownType(name.clone())),
+            },
+        }
+    }
+
+    fn check_properties(
+        &self,
+        type_decl: &TypeDecl,
+        value: &Yaml,
+        pointer: &str,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        for PropertyDecl {
+            name,
+            data_type_decl,
+        } in &type_decl.property_decls
+        {
+            let Ok(data_type_decl) = data_type_decl else {
+                continue;
+            };
+            let property_pointer = format!("{}/{}", pointer, escape(name));
+            let property = &value[name.as_str()];
+            if property.is_badvalue() {
+                if data_type_decl.is_required {
+                    errors.push(ValidationError {
+                        pointer: property_pointer,
+                        kind: ValidationErrorKind::MissingProperty,
+                    });
+                }
+                continue;
+            }
+            self.check_decl(data_type_decl, property, &property_pointer, errors);
+        }
+        if let Yaml::Hash(hash) = value {
+            for key in hash.keys() {
+                let key = key.as_str().unwrap_or_default();
+                if !type_decl.property_decls.iter().any(|decl| decl.name == key) {
+                    errors.push(ValidationError {
+                        pointer: format!("{}/{}", pointer, escape(key)),
+                        kind: ValidationErrorKind::UnknownProperty,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Parses a JSON document into the value validators walk. Duplicate keys are rejected rather than overwritten.
+pub fn parse_json(json: &str) -> Result<Yaml, serde_json::Error> {
+    serde_json::from_str(json).map(|JsonValue(value)| value)
+}
+
+struct JsonValue(Yaml);
+
+impl<'de> Deserialize<'de> for JsonValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(JsonVisitor).map(JsonValue)
+    }
+}
+
+struct JsonVisitor;
+
+impl<'de> Visitor<'de> for JsonVisitor {
+    type Value = Yaml;
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Yaml, E> {
+        Ok(Yaml::Null)
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Yaml, E> {
+        Ok(Yaml::Boolean(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Yaml, E> {
+        Ok(Yaml::Integer(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Yaml, E> {
+        // Beyond `i64` only a double can hold it.
+        Ok(i64::try_from(value).map_or_else(|_| Yaml::Real(value.to_string()), Yaml::Integer))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Yaml, E> {
+        Ok(Yaml::Real(format!("{:?}", value)))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Yaml, E> {
+        Ok(Yaml::String(value.to_string()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Yaml, A::Error> {
+        let mut items = Vec::new();
+        while let Some(JsonValue(item)) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Yaml::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Yaml, A::Error> {
+        let mut hash = Hash::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let key = Yaml::String(key);
+            if hash.contains_key(&key) {
+                let message = format!("duplicate key {}", key.as_str().unwrap_or_default());
+                return Err(de::Error::custom(message));
+            }
+            let JsonValue(value) = map.next_value()?;
+            hash.insert(key, value);
+        }
+        Ok(Yaml::Hash(hash))
+    }
+}
+
+fn matches_primitive(primitive: &Primitive, value: &Yaml) -> bool {
+    matches!(
+        (primitive, value),
+        (Primitive::Int, Yaml::Integer(_))
+            | (Primitive::Double, Yaml::Real(_) | Yaml::Integer(_))
+            | (Primitive::Bool, Yaml::Boolean(_))
+            | (Primitive::Str, Yaml::String(_))
+    )
+}
+
+fn matches_key(primitive: &Primitive, key: &str) -> bool {
+    match primitive {
+        Primitive::Int => key.parse::<i64>().is_ok(),
+        Primitive::Double => key.parse::<f64>().is_ok(),
+        Primitive::Bool => key == "true" || key == "false",
+        Primitive::Str => true,
+    }
+}
+
+fn matches_format(format: &Format, value: &Yaml) -> bool {
+    let Some(value) = value.as_str() else {
+        return matches!(format, Format::Timestamp);
+    };
+    match format {
+        Format::Uuid => is_uuid(value),
+        Format::Url => is_url(value),
+        Format::DateIso8601 => is_date_iso8601(value),
+        Format::Timestamp => true,
+    }
+}
+
+fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+fn is_url(value: &str) -> bool {
+    let Some((scheme, rest)) = value.split_once("://") else {
+        return false;
+    };
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        && !rest.is_empty()
+        && !rest.contains(char::is_whitespace)
+}
+
+/// Date like `2024-02-29`, optionally followed by time like `T10:15:30Z`, `T10:15:30.250+02:00`.
+fn is_date_iso8601(value: &str) -> bool {
+    let (date, time) = match value.split_once(['T', 't', ' ']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (value, None),
+    };
+    let Some([year, month, day]) = numbers(date, '-', [4, 2, 2]) else {
+        return false;
+    };
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => return false,
+    };
+    if day < 1 || day > days_in_month {
+        return false;
+    }
+    let Some(time) = time else {
+        return true;
+    };
+    let (time, offset) = match time.find(['Z', 'z', '+', '-']) {
+        Some(i) => time.split_at(i),
+        None => (time, ""),
+    };
+    let (time, fraction) = time.split_once('.').unwrap_or((time, "0"));
+    let is_valid_offset = match offset {
+        "" | "Z" | "z" => true,
+        _ => numbers(&offset[1..], ':', [2, 2]).is_some_and(|[h, m]| h < 24 && m < 60),
+    };
+    is_valid_offset
+        && !fraction.is_empty()
+        && fraction.chars().all(|c| c.is_ascii_digit())
+        && numbers(time, ':', [2, 2, 2]).is_some_and(|[h, m, s]| h < 24 && m < 60 && s <= 60)
+}
+
+/// Parses numbers separated by the separator, each having exact count of digits.
+fn numbers<const N: usize>(value: &str, separator: char, digits: [usize; N]) -> Option<[u32; N]> {
+    let mut result = [0; N];
+    let mut parts = value.split(separator);
+    for (i, count) in digits.iter().enumerate() {
+        let part = parts.next()?;
+        if part.len() != *count || !part.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        result[i] = part.parse().ok()?;
+    }
+    parts.next().is_none().then_some(result)
+}
+
+fn mismatch(data_type: &DataType, value: &Yaml) -> ValidationErrorKind {
+    ValidationErrorKind::TypeMismatch {
+        expected: data_type.to_string(),
+        found: kind_name(value).to_string(),
+    }
+}
+
+fn kind_name(value: &Yaml) -> &'static str {
+    match value {
+        Yaml::Real(_) => "double",
+        Yaml::Integer(_) => "int",
+        Yaml::String(_) => "str",
+        Yaml::Boolean(_) => "bool",
+        Yaml::Array(_) => "array",
+        Yaml::Hash(_) => "object",
+        Yaml::Null | Yaml::BadValue => "null",
+        Yaml::Alias(_) => "alias",
+    }
+}
+
+/// Escapes a reference token of a JSON pointer (RFC 6901).
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ValidationError {
+    /// JSON pointer to the invalid value, empty for the whole document.
+    pub pointer: String,
+    pub kind: ValidationErrorKind,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "(root)"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{}: {}", pointer, self.kind)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ValidationErrorKind {
+    MissingProperty,
+    UnknownProperty,
+    TypeMismatch { expected: String, found: String },
+    InvalidFormat(Format),
+    InvalidKey(Primitive),
+    UnknownType(String),
+}
+
+impl Display for ValidationErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationErrorKind::MissingProperty => write!(f, "required property is missing."),
+            ValidationErrorKind::UnknownProperty => write!(f, "property is not declared."),
+            ValidationErrorKind::TypeMismatch { expected, found } => {
+                write!(f, "expected {}, found {}.", expected, found)
+            }
+            ValidationErrorKind::InvalidFormat(format) => {
+                write!(f, "value is not a valid {}.", format)
+            }
+            ValidationErrorKind::InvalidKey(primitive) => {
+                write!(f, "key is not a valid {}.", primitive)
+            }
+            ValidationErrorKind::UnknownType(name) => write!(f, "type {} is not declared.", name),
+        }
+    }
+}
+
+pub struct ValidationErrors {
+    pub list: Vec<ValidationError>,
+}
+
+impl ValidationErrors {
+    fn default_fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let lines: Vec<String> = self.list.iter().map(|error| error.to_string()).collect();
+        f.write_str(&lines.join("\n"))
+    }
+}
+
+impl Error for ValidationErrors {}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+impl Debug for ValidationErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.default_fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use yaml_rust::Yaml;
+
+    use crate::schema::{
+        DataType, DataTypeDecl, DeclLocations, Format, ImportGraph, Primitive, PropertyDecl,
+        Schema, SharedResponses, StatusCodeInference, TypeDecl,
+    };
+    use crate::validation::{parse_json, InstanceValidator, ValidationError, ValidationErrorKind};
+
+    fn property(name: &str, data_type: DataType, is_required: bool) -> PropertyDecl {
+        PropertyDecl {
+            name: name.to_string(),
+            data_type_decl: Ok(DataTypeDecl {
+                data_type,
+                is_required,
+            }),
+        }
+    }
+
+    fn schema() -> Schema {
+        let user = TypeDecl {
+            name: "user".to_string(),
+            namespace: Some("types".to_string()),
+            property_decls: vec![
+                property("id", DataType::Format(Format::Uuid), true),
+                property("name", DataType::Primitive(Primitive::Str), true),
+                property("site", DataType::Format(Format::Url), false),
+                property(
+                    "tags",
+                    DataType::Array(Box::new(DataType::Primitive(Primitive::Str))),
+                    true,
+                ),
+                property(
+                    "scores",
+                    DataType::Dict(
+                        Primitive::Str,
+                        Box::new(DataType::Primitive(Primitive::Double)),
+                    ),
+                    false,
+                ),
+                property(
+                    "settings",
+                    DataType::ObjectDecl(TypeDecl {
+                        name: "settings".to_string(),
+                        namespace: None,
+                        property_decls: vec![property(
+                            "since",
+                            DataType::Format(Format::DateIso8601),
+                            true,
+                        )],
+                    }),
+                    true,
+                ),
+                property("friend", DataType::Object("user".to_string()), false),
+            ],
+        };
+        Schema {
+            hosts: Vec::new(),
+            versioning: None,
+            extension_methods: Default::default(),
+            status_code_inference: StatusCodeInference { rules: Vec::new() },
+            shared_responses: SharedResponses::default(),
+            types: vec![Ok(user)],
+            interfaces: Vec::new(),
+            locations: DeclLocations::default(),
+            suppressions: Default::default(),
+            imports: ImportGraph::default(),
+            packages: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    fn errors(json: &str) -> Vec<ValidationError> {
+        let schema = schema();
+        let validator = InstanceValidator { schema: &schema };
+        let value = parse_json(json).unwrap();
+        match validator.validate("types.user", &value) {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.list,
+        }
+    }
+
+    fn error(pointer: &str, kind: ValidationErrorKind) -> ValidationError {
+        ValidationError {
+            pointer: pointer.to_string(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_valid_instance() {
+        let json = r#"{
+            "id": "7c9e6679-7425-40de-944b-e07fc1f90ae7",
+            "name": "Jane",
+            "site": null,
+            "tags": ["a", "b"],
+            "scores": {"math": 4.5, "art": 5},
+            "settings": {"since": "2024-02-29T10:15:30.250+02:00"},
+            "friend": {
+                "id": "7c9e6679-7425-40de-944b-e07fc1f90ae8",
+                "name": "John",
+                "site": "https://example.com/john",
+                "tags": [],
+                "settings": {"since": "2023-01-01"}
+            }
+        }"#;
+        assert_eq!(Vec::<ValidationError>::new(), errors(json));
+    }
+
+    #[test]
+    fn test_invalid_instance() {
+        let json = r#"{
+            "id": "7c9e6679",
+            "tags": ["a", 1],
+            "scores": {"a/b": "high"},
+            "settings": {"since": "2023-02-29"},
+            "friend": {"name": null, "tags": [], "settings": {"since": "2023-01-01T25:00:00Z"}},
+            "age": 30
+        }"#;
+        let mismatch = |expected: &str, found: &str| ValidationErrorKind::TypeMismatch {
+            expected: expected.to_string(),
+            found: found.to_string(),
+        };
+        assert_eq!(
+            vec![
+                error("/id", ValidationErrorKind::InvalidFormat(Format::Uuid)),
+                error("/name", ValidationErrorKind::MissingProperty),
+                error("/tags/1", mismatch("str", "int")),
+                error("/scores/a~1b", mismatch("double", "str")),
+                error(
+                    "/settings/since",
+                    ValidationErrorKind::InvalidFormat(Format::DateIso8601)
+                ),
+                error("/friend/id", ValidationErrorKind::MissingProperty),
+                error("/friend/name", mismatch("str", "null")),
+                error(
+                    "/friend/settings/since",
+                    ValidationErrorKind::InvalidFormat(Format::DateIso8601)
+                ),
+                error("/age", ValidationErrorKind::UnknownProperty),
+            ],
+            errors(json)
+        );
+    }
+
+    #[test]
+    fn test_unknown_type() {
+        let schema = schema();
+        let validator = InstanceValidator { schema: &schema };
+        let errors = validator.validate("post", &Yaml::Null).unwrap_err();
+        assert_eq!(
+            vec![error(
+                "",
+                ValidationErrorKind::UnknownType("post".to_string())
+            )],
+            errors.list
+        );
+    }
+
+    #[test]
+    fn test_json_escapes() {
+        let value = parse_json(r#"{"name": "a\/b", "face": "\ud83d\ude00", "tab": "\t"}"#).unwrap();
+        assert_eq!(Some("a/b"), value["name"].as_str());
+        assert_eq!(Some("\u{1f600}"), value["face"].as_str());
+        assert_eq!(Some("\t"), value["tab"].as_str());
+    }
+
+    #[test]
+    fn test_json_numbers() {
+        let value = parse_json("[1, -2, 2.5, 1e3, 18446744073709551615]").unwrap();
+        assert_eq!(
+            Yaml::Array(vec![
+                Yaml::Integer(1),
+                Yaml::Integer(-2),
+                Yaml::Real("2.5".to_string()),
+                Yaml::Real("1000.0".to_string()),
+                Yaml::Real("18446744073709551615".to_string()),
+            ]),
+            value
+        );
+    }
+
+    #[test]
+    fn test_not_json() {
+        let schema = schema();
+        let validator = InstanceValidator { schema: &schema };
+        for json in ["name: x", "{'name': 'x'}", "{\"name\": \"x\",}", ""] {
+            assert!(parse_json(json).is_err());
+            assert!(validator.validate_json("types.user", json).is_err());
+        }
+    }
+
+    #[test]
+    fn test_duplicate_keys() {
+        let err = parse_json(r#"{"name": "a", "tags": [], "name": "b"}"#).unwrap_err();
+        assert!(err.to_string().starts_with("duplicate key name"));
+        assert!(parse_json(r#"[{"name": "a"}, {"name": "b"}]"#).is_ok());
+    }
+}